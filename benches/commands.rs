@@ -0,0 +1,62 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use reredis::commands::{get, ping};
+use reredis::{PreparedCommand, StructuredCommand, SyncClient};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::thread;
+
+fn prepared_commands(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encoding a command");
+
+    group.bench_function("fresh", |b| {
+        b.iter(|| get::<String, _>("some-fairly-long-key-name").get_bytes())
+    });
+
+    let prepared = PreparedCommand::new(|| get::<String, _>("some-fairly-long-key-name"));
+    group.bench_function("prepared", |b| b.iter(|| prepared.bytes().len()));
+
+    group.finish();
+}
+
+// Replies to every PING (and assumes that nothing else is sent), so that round trips measure the
+// client rather than redis
+fn pong_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            thread::spawn(move || {
+                let ping_len = ping().get_bytes().len();
+                let mut buffer = [0; 1024];
+                let mut unanswered = 0;
+                while let Ok(read @ 1..=1024) = stream.read(&mut buffer) {
+                    unanswered += read;
+                    while unanswered >= ping_len {
+                        unanswered -= ping_len;
+                        stream.write_all(b"+PONG\r\n").unwrap();
+                    }
+                }
+            });
+        }
+    });
+    address
+}
+
+fn round_trips(c: &mut Criterion) {
+    let mut group = c.benchmark_group("round trip");
+    let address = pong_server();
+
+    let mut threaded = SyncClient::new(address).unwrap();
+    group.bench_function("threaded", |b| b.iter(|| threaded.issue(ping()).unwrap()));
+
+    let mut single_threaded = SyncClient::new_single_threaded(address).unwrap();
+    group.bench_function("single threaded", |b| {
+        b.iter(|| single_threaded.issue(ping()).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, prepared_commands, round_trips);
+criterion_main!(benches);
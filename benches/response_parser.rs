@@ -0,0 +1,76 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+#[cfg(feature = "bytes")]
+use reredis::RedisResult;
+use reredis::{Command, SansIoClient};
+
+fn array_reply<'a>(elements: impl Iterator<Item = &'a [u8]>, len: usize) -> Vec<u8> {
+    let mut reply = format!("*{}\r\n", len).into_bytes();
+    for element in elements {
+        reply.extend_from_slice(element);
+    }
+    reply
+}
+
+fn parse_reply(reply: &[u8]) {
+    let (mut client, send_bytes) = SansIoClient::new();
+    send_bytes.send(Ok(reply.to_vec())).unwrap();
+    client.get_response(Command::cmd("BENCH")).unwrap();
+}
+
+fn large_array_replies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("large array reply");
+
+    let integers = array_reply(std::iter::repeat_n(&b":1234567\r\n"[..], 10_000), 10_000);
+    group.throughput(Throughput::Bytes(integers.len() as u64));
+    group.bench_function("integers", |b| b.iter(|| parse_reply(&integers)));
+
+    let simple_strings = array_reply(
+        std::iter::repeat_n(&b"+some-status-reply\r\n"[..], 10_000),
+        10_000,
+    );
+    group.throughput(Throughput::Bytes(simple_strings.len() as u64));
+    group.bench_function("simple strings", |b| {
+        b.iter(|| parse_reply(&simple_strings))
+    });
+
+    let bulk_strings = array_reply(
+        std::iter::repeat_n(&b"$18\r\nsome-bulk-string!!\r\n"[..], 10_000),
+        10_000,
+    );
+    group.throughput(Throughput::Bytes(bulk_strings.len() as u64));
+    group.bench_function("bulk strings", |b| b.iter(|| parse_reply(&bulk_strings)));
+
+    group.finish();
+}
+
+// Compares copying every bulk string out of the buffer with slicing them out of a shared one
+#[cfg(feature = "bytes")]
+fn shared_bulk_strings(c: &mut Criterion) {
+    use reredis::sans_io::SharedResponseParser;
+
+    let mut group = c.benchmark_group("shared bulk strings");
+    let value = vec![b'x'; 1024];
+    let mut element = format!("${}\r\n", value.len()).into_bytes();
+    element.extend_from_slice(&value);
+    element.extend_from_slice(b"\r\n");
+    let reply = array_reply(std::iter::repeat_n(&element[..], 1_000), 1_000);
+    group.throughput(Throughput::Bytes(reply.len() as u64));
+
+    let parse_shared = |reply: &[u8]| {
+        let mut parser = SharedResponseParser::new();
+        parser.feed(reply);
+        parser.get_response().unwrap().unwrap()
+    };
+    group.bench_function("copied", |b| {
+        b.iter(|| RedisResult::from(parse_shared(&reply)))
+    });
+    group.bench_function("shared", |b| b.iter(|| parse_shared(&reply)));
+
+    group.finish();
+}
+
+#[cfg(feature = "bytes")]
+criterion_group!(benches, large_array_replies, shared_bulk_strings);
+#[cfg(not(feature = "bytes"))]
+criterion_group!(benches, large_array_replies);
+criterion_main!(benches);
@@ -1,36 +1,86 @@
 // TODO: use the nightly opportunity to clear up some deprecations that may be coming our way
 
+use crate::commands::auth;
 use crate::sans_io::Client as SansIoClient;
-use crate::{Command, RBytes, RedisError, StructuredCommand};
-use futures::io::{AsyncBufReadExt, BufReader, Result as IoResult};
+use crate::{RBytes, RedisError, StructuredCommand};
+use futures::io::{
+    AsyncReadExt, AsyncWriteExt, BufReader, Error as IoError, ErrorKind as IoErrorKind, ReadHalf,
+    Result as IoResult, WriteHalf,
+};
 use runtime::net::TcpStream;
 use std::net::ToSocketAddrs;
-use std::thread;
+
+// How much is read from the connection at a time while waiting for a reply
+const READ_CHUNK: usize = 8 * 1024;
 
 pub struct Client {
     parser: SansIoClient,
-    writer: TcpStream,
+    reader: BufReader<ReadHalf<TcpStream>>,
+    writer: WriteHalf<TcpStream>,
 }
 
 impl Client {
     pub async fn new(address: impl ToSocketAddrs) -> IoResult<Self> {
-        let writer = TcpStream::connect(address).await?;
+        let stream = TcpStream::connect(address).await?;
+        let (reader, writer) = stream.split();
+        // the bytes are fed to the parser directly, so its channel is never used
         let (parser, _) = SansIoClient::new();
 
-        Ok(Self { parser, writer })
+        Ok(Self {
+            parser,
+            reader: BufReader::new(reader),
+            writer,
+        })
     }
 
     pub async fn with_auth<'a>(
         address: impl ToSocketAddrs,
         pass: impl Into<RBytes<'a>>,
-    ) -> IoResult<Self> {
-        Self::new(address).await
+    ) -> Result<Self, RedisError> {
+        let mut client = Self::new(address)
+            .await
+            .map_err(RedisError::ConnectionError)?;
+        client.issue(auth(pass)).await?;
+        Ok(client)
     }
 
-    pub fn issue<Cmd>(&mut self, cmd: Cmd) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    pub async fn issue<Cmd>(
+        &mut self,
+        cmd: Cmd,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
     where
         Cmd: StructuredCommand,
     {
-        unimplemented!("cannot yet use the async client")
+        let bytes = self.parser.issue_command(&cmd);
+        self.writer
+            .write_all(&bytes)
+            .await
+            .map_err(RedisError::ConnectionError)?;
+        self.writer
+            .flush()
+            .await
+            .map_err(RedisError::ConnectionError)?;
+
+        let mut chunk = vec![0; READ_CHUNK];
+        let reply = loop {
+            if let Some(reply) = self.parser.try_next_reply()? {
+                break reply;
+            }
+
+            let read = self
+                .reader
+                .read(&mut chunk)
+                .await
+                .map_err(RedisError::ConnectionError)?;
+            if read == 0 {
+                return Err(RedisError::ConnectionError(IoError::from(
+                    IoErrorKind::UnexpectedEof,
+                )));
+            }
+            self.parser.feed(&chunk[..read]);
+        };
+
+        cmd.convert_redis_result(reply)
+            .map_err(RedisError::from_conversion_error)
     }
 }
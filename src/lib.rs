@@ -6,6 +6,16 @@ pub use sans_io::Client as SansIoClient;
 pub mod types;
 pub use types::*;
 
+pub mod lock;
+pub use lock::LockGuard;
+
+pub mod rate_limit;
+
+#[cfg(feature = "test-server")]
+pub mod test_server;
+#[cfg(feature = "test-server")]
+pub use test_server::TestServer;
+
 #[cfg(feature = "async-client")]
 pub mod async_client;
 #[cfg(feature = "async-client")]
@@ -14,4 +24,19 @@ pub use async_client::Client as AsyncClient;
 #[cfg(feature = "sync-client")]
 pub mod sync_client;
 #[cfg(feature = "sync-client")]
-pub use sync_client::Client as SyncClient;
+pub use sync_client::{Client as SyncClient, DynamicValue};
+
+#[cfg(feature = "sync-client")]
+pub mod pipeline;
+#[cfg(feature = "sync-client")]
+pub use pipeline::Pipeline;
+
+#[cfg(feature = "sync-client")]
+pub mod subscription;
+#[cfg(feature = "sync-client")]
+pub use subscription::{BufferPolicy, Message, Subscription};
+
+#[cfg(feature = "sync-client")]
+pub mod pubsub_client;
+#[cfg(feature = "sync-client")]
+pub use pubsub_client::PubSubClient;
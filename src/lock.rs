@@ -0,0 +1,138 @@
+use crate::commands::scripting::Eval;
+use crate::commands::set::SetIfExists;
+use crate::commands::{eval, set};
+use crate::types::redis_values::ConversionError;
+use crate::{RBytes, RedisResult, StructuredCommand, ValidationError};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Only delete the key if it still contains our token - otherwise the lock has expired and been
+// taken by somebody else in the meantime, and it's not ours to release any more.
+const RELEASE_SCRIPT: &str = "\
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+else
+    return 0
+end";
+
+static TOKEN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn random_token() -> String {
+    // RandomState is seeded randomly per-process, which along with the time and a counter
+    // should be more than unique enough to identify the holder of a lock
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_usize(TOKEN_COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0),
+    );
+    hasher.write_u32(std::process::id());
+    format!("{:016x}", hasher.finish())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct LockGuard {
+    key: Vec<u8>,
+    token: String,
+}
+
+impl LockGuard {
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    // Releases the lock, but only if it is still held with this guard's token.  The command
+    // outputs `false` if the lock had already expired.
+    pub fn release(self) -> Eval<'static, bool> {
+        eval(RELEASE_SCRIPT).key(self.key).arg(self.token)
+    }
+}
+
+pub struct AcquireLock<'a> {
+    set_command: SetIfExists<'a>,
+    key: Vec<u8>,
+    token: String,
+}
+
+impl<'a> StructuredCommand for AcquireLock<'a> {
+    type Output = Option<LockGuard>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.set_command.get_bytes()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        let acquired = self.set_command.convert_redis_result(result)?;
+        if acquired {
+            Ok(Some(LockGuard {
+                key: self.key,
+                token: self.token,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+pub fn acquire<'a>(
+    key: impl Into<RBytes<'a>>,
+    ttl: Duration,
+) -> Result<AcquireLock<'a>, ValidationError> {
+    let key = key.into();
+    let owned_key = key.as_bytes().to_vec();
+    let token = random_token();
+
+    Ok(AcquireLock {
+        set_command: set(key, token.clone()).if_not_exists().with_expiry(ttl)?,
+        key: owned_key,
+        token,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_unique() {
+        assert_ne!(random_token(), random_token());
+    }
+
+    #[test]
+    fn acquiring_a_lock_sets_the_token_only_if_the_key_does_not_exist() {
+        let cmd = acquire("my-lock", Duration::from_millis(500)).unwrap();
+        let token = cmd.token.clone();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            format!(
+                "*6\r\n\
+                 $3\r\nSET\r\n\
+                 $7\r\nmy-lock\r\n\
+                 $16\r\n{}\r\n\
+                 $2\r\nPX\r\n\
+                 $3\r\n500\r\n\
+                 $2\r\nNX\r\n",
+                token
+            )
+        );
+    }
+
+    #[test]
+    fn lock_guard_is_only_returned_when_the_lock_was_acquired() {
+        let cmd = acquire("my-lock", Duration::from_millis(500)).unwrap();
+        assert_eq!(None, cmd.convert_redis_result(RedisResult::Null).unwrap());
+
+        let cmd = acquire("my-lock", Duration::from_millis(500)).unwrap();
+        let token = cmd.token.clone();
+        let guard = cmd
+            .convert_redis_result(RedisResult::String(b"OK".to_vec()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(token, guard.token());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::sync_client::Client;
+use crate::{RedisError, StructuredCommand};
+
+// A fixed set of commands that can be sent together, implemented for tuples of commands.  The
+// replies come back as a tuple in the same order, with each element typed by its command.
+pub trait PipelineCommands {
+    type Output;
+    type Results;
+
+    fn get_bytes(&self) -> Vec<u8>;
+
+    // Every reply has to be read, even once one has failed, so that the next command on the
+    // connection doesn't receive a leftover reply from the pipeline
+    fn receive(self, client: &mut Client) -> Self::Results;
+
+    fn first_error(results: Self::Results) -> Result<Self::Output, RedisError>;
+}
+
+macro_rules! impl_pipeline_commands {
+    ($($cmd:ident: $index:tt),+) => {
+        impl<$($cmd),+> PipelineCommands for ($($cmd,)+)
+        where
+            $($cmd: StructuredCommand,)+
+        {
+            type Output = ($($cmd::Output,)+);
+            type Results = ($(Result<$cmd::Output, RedisError>,)+);
+
+            fn get_bytes(&self) -> Vec<u8> {
+                let mut bytes = Vec::new();
+                $(bytes.extend(self.$index.get_bytes());)+
+                bytes
+            }
+
+            fn receive(self, client: &mut Client) -> Self::Results {
+                ($(client.receive(self.$index),)+)
+            }
+
+            fn first_error(results: Self::Results) -> Result<Self::Output, RedisError> {
+                Ok(($(results.$index?,)+))
+            }
+        }
+    };
+}
+
+impl_pipeline_commands!(A: 0);
+impl_pipeline_commands!(A: 0, B: 1);
+impl_pipeline_commands!(A: 0, B: 1, C: 2);
+impl_pipeline_commands!(A: 0, B: 1, C: 2, D: 3);
+impl_pipeline_commands!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_pipeline_commands!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_pipeline_commands!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_pipeline_commands!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+// Sends a group of commands in one write, then reads all of their replies.  This isn't a
+// transaction - other clients' commands can run in between, and commands after a failing one are
+// still run.  Commands run in order, so e.g. a trailing `wait` covers every write before it in the
+// pipeline.
+pub struct Pipeline<'c, C> {
+    client: &'c mut Client,
+    commands: C,
+}
+
+impl<'c, C: PipelineCommands> Pipeline<'c, C> {
+    pub(crate) fn new(client: &'c mut Client, commands: C) -> Self {
+        Self { client, commands }
+    }
+
+    // Returns every command's output, or the first command's error if any of them failed
+    pub fn execute(self) -> Result<C::Output, RedisError> {
+        C::first_error(self.execute_results()?)
+    }
+
+    // Returns the outcome of each command separately, so that a command failing (e.g. with
+    // WRONGTYPE) doesn't hide the results of the others.  The outer error is only for failures to
+    // send the pipeline at all.
+    pub fn execute_results(self) -> Result<C::Results, RedisError> {
+        let Pipeline { client, commands } = self;
+        let bytes = commands.get_bytes();
+        client.round_trip(&bytes, |client| Ok(commands.receive(client)))
+    }
+}
@@ -0,0 +1,100 @@
+use crate::commands::publish;
+use crate::subscription::{Message, Subscription};
+use crate::sync_client::Client;
+use crate::{RBytes, RedisError, StructuredCommand};
+use std::io::{Error as IoError, Result as IoResult};
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+// A subscribed connection can't issue normal commands, so this holds two connections to the same
+// server: one that stays subscribed, and one for everything else (including publishing).  Both
+// are opened up front, so the subscription can start out with no channels.
+#[derive(Debug)]
+pub struct PubSubClient {
+    client: Client,
+    subscription: Subscription,
+}
+
+impl PubSubClient {
+    pub fn new(address: impl ToSocketAddrs) -> IoResult<Self> {
+        let client = Client::new(address)?;
+        // a client made this way has no credentials or RESP3 to set up, so only connecting can fail
+        let subscription = match client.connect_again() {
+            Ok(connection) => Subscription::new(connection),
+            Err(RedisError::ConnectionError(error)) => return Err(error),
+            Err(other) => return Err(IoError::other(format!("{:?}", other))),
+        };
+        Ok(Self {
+            client,
+            subscription,
+        })
+    }
+
+    // Issues a command on the command connection, as with `SyncClient::issue`
+    pub fn issue<Cmd>(&mut self, cmd: Cmd) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        self.client.issue(cmd)
+    }
+
+    // Returns the number of clients that received the message, which includes this one if it's
+    // subscribed to the channel
+    pub fn publish<'a>(
+        &mut self,
+        channel: impl Into<RBytes<'a>>,
+        message: impl Into<RBytes<'a>>,
+    ) -> Result<u64, RedisError> {
+        self.client.issue(publish(channel, message))
+    }
+
+    pub fn subscribe<'a>(
+        &mut self,
+        channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        self.subscription.subscribe(channels)
+    }
+
+    pub fn unsubscribe<'a>(
+        &mut self,
+        channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        self.subscription.unsubscribe(channels)
+    }
+
+    pub fn psubscribe<'a>(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        self.subscription.psubscribe(patterns)
+    }
+
+    pub fn punsubscribe<'a>(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        self.subscription.punsubscribe(patterns)
+    }
+
+    // Blocks until the next message arrives.  With nothing subscribed to, this waits forever.
+    pub fn next_message(&mut self) -> Result<Message, RedisError> {
+        self.subscription.next_message()
+    }
+
+    pub fn next_message_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Message>, RedisError> {
+        self.subscription.next_message_timeout(timeout)
+    }
+
+    // The command connection, for anything not wrapped here (e.g. pipelines)
+    pub fn client(&mut self) -> &mut Client {
+        &mut self.client
+    }
+
+    // The subscribed connection, e.g. for shard channels or reconnecting
+    pub fn subscription(&mut self) -> &mut Subscription {
+        &mut self.subscription
+    }
+}
@@ -0,0 +1,87 @@
+use crate::commands::eval;
+use crate::commands::scripting::Eval;
+use crate::commands::set::check_millisecond_expiry;
+use crate::types::redis_values::ConversionError;
+use crate::{RBytes, RedisResult, StructuredCommand, ValidationError};
+use std::time::Duration;
+
+// The expiry is only set by the first increment in each window, and doing both inside a script
+// means that there's no gap where the counter could exist without an expiry.
+const INCREMENT_SCRIPT: &str = "\
+local count = redis.call('INCR', KEYS[1])
+if count == 1 then
+    redis.call('PEXPIRE', KEYS[1], ARGV[1])
+end
+return count";
+
+pub struct RateLimit<'a> {
+    script: Eval<'a, i64>,
+}
+
+impl<'a> StructuredCommand for RateLimit<'a> {
+    // the number of hits so far in the current window, including this one
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.script.get_bytes()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        let count = self.script.convert_redis_result(result)?;
+        if count < 0 {
+            Err(ConversionError::IntegerOutOfRange {
+                value: count,
+                target: "u64",
+            })
+        } else {
+            Ok(count as u64)
+        }
+    }
+}
+
+// A window shorter than a millisecond would be sent as `PEXPIRE key 0`, which deletes the counter
+// straight away, so it's rejected
+pub fn increment<'a>(
+    key: impl Into<RBytes<'a>>,
+    window: Duration,
+) -> Result<RateLimit<'a>, ValidationError> {
+    check_millisecond_expiry(window, "PEXPIRE")?;
+    Ok(RateLimit {
+        script: eval(INCREMENT_SCRIPT)
+            .key(key)
+            .arg(window.as_millis().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn increment_passes_the_window_in_milliseconds() {
+        let cmd = increment("hits", Duration::from_secs(60)).unwrap();
+
+        assert!(String::from_utf8(cmd.get_bytes())
+            .unwrap()
+            .ends_with("$1\r\n1\r\n$4\r\nhits\r\n$5\r\n60000\r\n"));
+    }
+
+    #[test]
+    fn increment_returns_the_current_count() {
+        let cmd = increment("hits", Duration::from_secs(60)).unwrap();
+        assert_eq!(
+            3,
+            cmd.convert_redis_result(RedisResult::Integer(3)).unwrap()
+        );
+    }
+
+    #[test]
+    fn increment_rejects_windows_shorter_than_a_millisecond() {
+        for window in [Duration::ZERO, Duration::from_micros(999)] {
+            assert_eq!(
+                Some(ValidationError::ZeroExpiry { command: "PEXPIRE" }),
+                increment("hits", window).err()
+            );
+        }
+    }
+}
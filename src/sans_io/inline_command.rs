@@ -0,0 +1,47 @@
+// Decodes a command sent with redis' inline protocol, e.g. `SET foo bar\r\n`, which is what
+// telnet-style tools send instead of a RESP array.  Clients never need this - it's for building
+// stand-in servers in tests.  Returns `None` until the whole line has arrived.  Quoted arguments
+// aren't supported, so arguments can't contain spaces.
+pub fn parse_inline_command(data: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let end = data.iter().position(|&byte| byte == b'\n')?;
+    let line = data[..end].strip_suffix(b"\r").unwrap_or(&data[..end]);
+
+    Some(
+        line.split(|byte| byte.is_ascii_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .map(<[u8]>::to_vec)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_split_into_their_arguments() {
+        assert_eq!(
+            Some(vec![b"PING".to_vec()]),
+            parse_inline_command(b"PING\r\n")
+        );
+        assert_eq!(
+            Some(vec![b"SET".to_vec(), b"foo".to_vec(), b"bar".to_vec()]),
+            parse_inline_command(b"SET foo bar\r\n")
+        );
+    }
+
+    #[test]
+    fn extra_whitespace_and_bare_newlines_are_accepted() {
+        assert_eq!(
+            Some(vec![b"GET".to_vec(), b"foo".to_vec()]),
+            parse_inline_command(b"  GET   foo \n")
+        );
+        assert_eq!(Some(Vec::new()), parse_inline_command(b"\r\n"));
+    }
+
+    #[test]
+    fn incomplete_lines_are_not_parsed() {
+        assert_eq!(None, parse_inline_command(b"SET foo"));
+        assert_eq!(None, parse_inline_command(b""));
+    }
+}
@@ -1,5 +1,9 @@
+mod inline_command;
 mod redis_impl;
 mod response_parser;
 
+pub use inline_command::parse_inline_command;
 pub use redis_impl::Client;
-pub use response_parser::ParseError;
+#[cfg(feature = "bytes")]
+pub use response_parser::SharedResponseParser;
+pub use response_parser::{ParseError, StreamedReply, StreamingResponseParser};
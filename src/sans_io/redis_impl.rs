@@ -1,31 +1,119 @@
 use crate::sans_io::response_parser::ResponseParser;
-use crate::types::redis_values::ConversionError;
-use crate::{RedisError, StructuredCommand};
+use crate::{RedisError, RedisResult, StructuredCommand};
+use std::fmt;
 use std::io::Result as IoResult;
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::mpsc::{
+    channel, sync_channel, Receiver, RecvError, RecvTimeoutError, Sender, SyncSender,
+};
+use std::time::{Duration, Instant};
+
+// Asked for more bytes when a reply is incomplete, with however long is left before the deadline.
+// Returns `None` if nothing arrives in that time.
+type PullBytes = Box<dyn FnMut(Option<Duration>) -> IoResult<Option<Vec<u8>>> + Send>;
+
+// Where the bytes of each reply come from
+enum Source {
+    // sent by whatever is reading the connection, usually on another thread
+    Channel(Receiver<IoResult<Vec<u8>>>),
+    // read on demand, on the thread waiting for the reply
+    Pull(PullBytes),
+}
+
+impl fmt::Debug for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Channel(receiver) => f.debug_tuple("Channel").field(receiver).finish(),
+            Source::Pull(_) => f.write_str("Pull"),
+        }
+    }
+}
+
+// Called with the elements of each push frame that arrives while waiting for a reply
+struct PushHandler(Box<dyn FnMut(Vec<RedisResult>) + Send>);
+
+impl fmt::Debug for PushHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PushHandler")
+    }
+}
 
 #[derive(Debug)]
 pub struct Client {
     has_errored: bool,
     has_finished: bool,
-    receive_bytes: Receiver<IoResult<Vec<u8>>>,
+    receive_bytes: Source,
+    push_handler: Option<PushHandler>,
     parser: ResponseParser,
     count: u128,
+    bytes_received: u64,
 }
 
 impl Client {
     pub fn new() -> (Self, Sender<IoResult<Vec<u8>>>) {
         let (tx_bytes, rx_bytes) = channel();
-        (
-            Self {
-                has_finished: false,
-                has_errored: false,
-                receive_bytes: rx_bytes,
-                parser: ResponseParser::new(),
-                count: 0,
-            },
-            tx_bytes,
-        )
+        (Self::with_parser(ResponseParser::new(), rx_bytes), tx_bytes)
+    }
+
+    // Pre-sizes the response buffer, which avoids repeated reallocations if replies are known to
+    // be large
+    pub fn with_buffer_capacity(capacity: usize) -> (Self, Sender<IoResult<Vec<u8>>>) {
+        let (tx_bytes, rx_bytes) = channel();
+        let parser = ResponseParser::with_capacity(capacity);
+        (Self::with_parser(parser, rx_bytes), tx_bytes)
+    }
+
+    // Only lets `chunks` sends of bytes queue up before sending blocks, so that whatever is
+    // reading from the connection stops when this client falls behind
+    pub fn with_bounded_channel(chunks: usize) -> (Self, SyncSender<IoResult<Vec<u8>>>) {
+        let (tx_bytes, rx_bytes) = sync_channel(chunks);
+        (Self::with_parser(ResponseParser::new(), rx_bytes), tx_bytes)
+    }
+
+    // Reads replies by calling `pull` whenever more bytes are needed, rather than having them sent
+    // over a channel.  This saves a thread hop when the caller can do the reading itself.
+    pub fn with_pull(
+        pull: impl FnMut(Option<Duration>) -> IoResult<Option<Vec<u8>>> + Send + 'static,
+    ) -> Self {
+        Self::with_source(ResponseParser::new(), Source::Pull(Box::new(pull)))
+    }
+
+    fn with_parser(parser: ResponseParser, receive_bytes: Receiver<IoResult<Vec<u8>>>) -> Self {
+        Self::with_source(parser, Source::Channel(receive_bytes))
+    }
+
+    fn with_source(parser: ResponseParser, receive_bytes: Source) -> Self {
+        Self {
+            has_finished: false,
+            has_errored: false,
+            receive_bytes,
+            push_handler: None,
+            parser,
+            count: 0,
+            bytes_received: 0,
+        }
+    }
+
+    // Push frames (e.g. client-side caching invalidations) aren't the reply to any command, so
+    // they're skipped over when waiting for a reply - this is called with each one instead of it
+    // being thrown away
+    pub fn set_push_handler(&mut self, handler: impl FnMut(Vec<RedisResult>) + Send + 'static) {
+        self.push_handler = Some(PushHandler(Box::new(handler)));
+    }
+
+    fn handle_push(&mut self, push: Vec<RedisResult>) {
+        if let Some(PushHandler(handler)) = &mut self.push_handler {
+            handler(push);
+        }
+    }
+
+    // Whether a reply has failed to parse, after which no more replies can be trusted
+    pub fn is_poisoned(&self) -> bool {
+        self.has_errored
+    }
+
+    // The total length of every chunk of bytes fed to the parser so far
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
     }
 
     pub fn issue_command(&self, cmd: &impl StructuredCommand) -> Vec<u8> {
@@ -43,61 +131,357 @@ impl Client {
     where
         T: StructuredCommand,
     {
+        self.get_response_before(converter, None)
+    }
+
+    // As `get_response`, but gives up with `RedisError::Timeout` if the reply hasn't arrived by
+    // `deadline`.  A reply that arrives after that would be mistaken for the next command's reply,
+    // so a timeout poisons the client.
+    pub fn get_response_before<T>(
+        &mut self,
+        converter: T,
+        deadline: Option<Instant>,
+    ) -> Result<<T as StructuredCommand>::Output, RedisError>
+    where
+        T: StructuredCommand,
+    {
+        // push frames can arrive at any point, even between a command and its reply, but they
+        // aren't the reply to anything, so they're passed to the push handler instead
+        let value = loop {
+            match self.next_reply(deadline)? {
+                RedisResult::Push(push) => self.handle_push(push),
+                value => break value,
+            }
+        };
+        converter
+            .convert_redis_result(value)
+            .map_err(RedisError::from_conversion_error)
+    }
+
+    // As `get_response_before`, but push frames are passed on to the converter like any other
+    // reply, for callers that are waiting for pushed data (e.g. pubsub messages)
+    pub fn get_frame_before<T>(
+        &mut self,
+        converter: T,
+        deadline: Option<Instant>,
+    ) -> Result<<T as StructuredCommand>::Output, RedisError>
+    where
+        T: StructuredCommand,
+    {
+        let value = self.next_reply(deadline)?;
+        converter
+            .convert_redis_result(value)
+            .map_err(RedisError::from_conversion_error)
+    }
+
+    // As `get_frame_before`, but returns `None` if nothing has arrived by `deadline`, without
+    // poisoning the client.  This is only safe when no reply is owed - e.g. when waiting for
+    // pushed data with no commands outstanding - as then nothing late can be mistaken for a reply.
+    // Any partial frame is kept, and finished by the next call.
+    pub fn try_get_frame_before<T>(
+        &mut self,
+        converter: T,
+        deadline: Option<Instant>,
+    ) -> Result<Option<<T as StructuredCommand>::Output>, RedisError>
+    where
+        T: StructuredCommand,
+    {
+        match self.wait_for_reply(deadline)? {
+            Some(value) => converter
+                .convert_redis_result(value)
+                .map(Some)
+                .map_err(RedisError::from_conversion_error),
+            None => Ok(None),
+        }
+    }
+
+    fn next_reply(&mut self, deadline: Option<Instant>) -> Result<RedisResult, RedisError> {
+        match self.wait_for_reply(deadline)? {
+            Some(value) => Ok(value),
+            None => {
+                self.has_errored = true;
+                Err(RedisError::Timeout)
+            }
+        }
+    }
+
+    // Returns `None` if `deadline` passes first
+    fn wait_for_reply(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<RedisResult>, RedisError> {
+        if self.has_errored {
+            return Err(RedisError::ConnectionDesynchronized);
+        }
+
         loop {
             match self.parser.get_response() {
-                Ok(Some(value)) => {
-                    return converter
-                        .convert_redis_result(value)
-                        .map_err(|err| match err {
-                            ConversionError::NoConversionTypeMatch { value } => {
-                                RedisError::ConversionError(value)
-                            }
-                            ConversionError::RedisReturnedError { error } => {
-                                RedisError::RedisReturnedError(error)
-                            }
-                            ConversionError::CannotParseStringResponse { error } => {
-                                RedisError::StringParseError(error)
-                            }
-                            ConversionError::InvalidUtf8String(error) => {
-                                RedisError::InvalidUtf8String(error)
-                            }
-                        });
+                Ok(Some(value)) => return Ok(Some(value)),
+                Err(error) => {
+                    self.has_errored = true;
+                    return Err(RedisError::ProtocolParseError(error));
+                }
+                Ok(None) => match self.next_bytes(deadline)? {
+                    Some(bytes) => self.feed(&bytes),
+                    None => return Ok(None),
+                },
+            }
+        }
+    }
+
+    // Returns `None` if `deadline` passes first
+    fn next_bytes(&mut self, deadline: Option<Instant>) -> Result<Option<Vec<u8>>, RedisError> {
+        let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        let bytes = match (&mut self.receive_bytes, remaining) {
+            (Source::Channel(receiver), None) => receiver
+                .recv()
+                .map_err(RedisError::InternalConnectionError)?,
+            (Source::Channel(receiver), Some(remaining)) => {
+                match receiver.recv_timeout(remaining) {
+                    Ok(bytes) => bytes,
+                    Err(RecvTimeoutError::Timeout) => return Ok(None),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        return Err(RedisError::InternalConnectionError(RecvError))
+                    }
                 }
-                Err(error) => return Err(RedisError::ProtocolParseError(error)),
-                Ok(None) => {
-                    let bytes = self
-                        .receive_bytes
-                        .recv()
-                        .map_err(RedisError::InternalConnectionError)?
-                        .map_err(RedisError::ConnectionError)?;
-                    self.count += 1;
-                    self.parser.feed(&bytes);
+            }
+            (Source::Pull(_), Some(remaining)) if remaining == Duration::from_secs(0) => {
+                return Ok(None)
+            }
+            (Source::Pull(pull), remaining) => match pull(remaining) {
+                Ok(Some(bytes)) => Ok(bytes),
+                Ok(None) => return Ok(None),
+                Err(error) => Err(error),
+            },
+        };
+        bytes.map(Some).map_err(RedisError::ConnectionError)
+    }
+
+    // The RESP3 attributes that the server sent along with the last reply, which are otherwise
+    // left out of the reply itself
+    pub fn take_attributes(&mut self) -> Vec<(RedisResult, RedisResult)> {
+        self.parser.take_attributes()
+    }
+
+    // For callers doing their own IO, such as the async client, which feed bytes read from the
+    // connection straight in rather than sending them through the channel
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.count += 1;
+        self.bytes_received += bytes.len() as u64;
+        self.parser.feed(bytes);
+    }
+
+    // Returns the next reply if all of it has already been fed in, or `None` if more bytes are
+    // needed first.  As with `get_response`, push frames go to the push handler.
+    pub fn try_next_reply(&mut self) -> Result<Option<RedisResult>, RedisError> {
+        if self.has_errored {
+            return Err(RedisError::ConnectionDesynchronized);
+        }
+
+        loop {
+            match self.parser.get_response() {
+                Ok(Some(RedisResult::Push(push))) => self.handle_push(push),
+                Ok(reply) => return Ok(reply),
+                Err(error) => {
+                    self.has_errored = true;
+                    return Err(RedisError::ProtocolParseError(error));
                 }
             }
         }
     }
 }
 
-//#[cfg(test)]
-//mod tests {
-//    use super::*;
-//    use crate::{Command, RedisValue};
-//
-//    #[test]
-//    fn bytes_sent_handler_is_called_when_command_is_issed() {
-//        let (client, _) = Client::new();
-//        let bytes = client.issue_command(Command::cmd("GET").with_arg("my_favourite_key"));
-//        assert_eq!("GET my_favourite_key\r\n".as_bytes(), bytes.as_slice());
-//    }
-//
-//    #[test]
-//    fn server_response_received_when_redis_value_is_parsed() {
-//        let (mut client, send_bytes) = Client::new();
-//        send_bytes.send(Ok(":42\r\n".into())).unwrap();
-//
-//        assert_eq!(
-//            Some(RedisValue::Integer(42)),
-//            client.get_response().unwrap()
-//        );
-//    }
-//}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Command, RedisValue};
+    use std::time::Duration;
+
+    #[test]
+    fn bytes_sent_handler_is_called_when_command_is_issued() {
+        let (client, _) = Client::new();
+        let bytes = client.issue_command(&Command::cmd("GET").with_arg("my_favourite_key"));
+        assert_eq!(
+            b"*2\r\n$3\r\nGET\r\n$16\r\nmy_favourite_key\r\n".to_vec(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn server_response_received_when_redis_value_is_parsed() {
+        let (mut client, send_bytes) = Client::new();
+        send_bytes.send(Ok(b":42\r\n".to_vec())).unwrap();
+
+        assert_eq!(
+            Some(RedisValue::Integer(42)),
+            client.get_response(Command::cmd("GET")).unwrap()
+        );
+    }
+
+    #[test]
+    fn received_bytes_are_counted() {
+        let (mut client, send_bytes) = Client::new();
+        send_bytes.send(Ok(b"$5\r\n".to_vec())).unwrap();
+        send_bytes.send(Ok(b"hello\r\n".to_vec())).unwrap();
+
+        client.get_response(Command::cmd("GET")).unwrap();
+        assert_eq!(11, client.bytes_received());
+    }
+
+    #[test]
+    fn fed_bytes_are_parsed_once_a_whole_reply_has_arrived() {
+        let (mut client, _) = Client::new();
+        client.feed(b"$5\r\nhel");
+        assert_eq!(None, client.try_next_reply().unwrap());
+
+        client.feed(b"lo\r\n>2\r\n$4\r\npush\r\n:1\r\n:42\r\n");
+        assert_eq!(
+            Some(RedisResult::String(b"hello".to_vec())),
+            client.try_next_reply().unwrap()
+        );
+        assert_eq!(
+            Some(RedisResult::Integer(42)),
+            client.try_next_reply().unwrap()
+        );
+        assert_eq!(None, client.try_next_reply().unwrap());
+        assert_eq!(34, client.bytes_received());
+    }
+
+    #[test]
+    fn push_frames_are_passed_to_the_push_handler() {
+        let (mut client, send_bytes) = Client::new();
+        let (tx_pushes, rx_pushes) = channel();
+        client.set_push_handler(move |push| tx_pushes.send(push).unwrap());
+        send_bytes
+            .send(Ok(b">2\r\n$10\r\ninvalidate\r\n_\r\n:1\r\n".to_vec()))
+            .unwrap();
+
+        assert_eq!(
+            Some(RedisValue::Integer(1)),
+            client.get_response(Command::cmd("GET")).unwrap()
+        );
+        assert_eq!(
+            vec![
+                RedisResult::String(b"invalidate".to_vec()),
+                RedisResult::Null
+            ],
+            rx_pushes.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn pulled_bytes_are_read_until_a_reply_is_complete() {
+        let mut chunks = vec![b"lo\r\n".to_vec(), b"$5\r\nhel".to_vec()];
+        let mut client = Client::with_pull(move |_| Ok(chunks.pop()));
+
+        assert_eq!(
+            Some(RedisValue::String(b"hello".to_vec())),
+            client.get_response(Command::cmd("GET")).unwrap()
+        );
+        assert_eq!(11, client.bytes_received());
+        // `pull` giving back nothing means that nothing arrived in time
+        let deadline = Instant::now() + Duration::from_secs(1);
+        match client.get_response_before(Command::cmd("GET"), Some(deadline)) {
+            Err(RedisError::Timeout) => {}
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn client_refuses_further_responses_after_a_malformed_reply() {
+        let (mut client, send_bytes) = Client::new();
+        send_bytes.send(Ok(b"?nonsense\r\n".to_vec())).unwrap();
+        send_bytes.send(Ok(b":42\r\n".to_vec())).unwrap();
+
+        match client.get_response(Command::cmd("GET")) {
+            Err(RedisError::ProtocolParseError(_)) => {}
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert!(client.is_poisoned());
+        match client.get_response(Command::cmd("GET")) {
+            Err(RedisError::ConnectionDesynchronized) => {}
+            other => panic!("expected the client to be poisoned, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn replies_that_miss_the_deadline_time_out_and_poison_the_client() {
+        let (mut client, send_bytes) = Client::new();
+
+        let deadline = Instant::now() + Duration::from_millis(10);
+        match client.get_response_before(Command::cmd("GET"), Some(deadline)) {
+            Err(RedisError::Timeout) => {}
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+        assert!(client.is_poisoned());
+
+        send_bytes.send(Ok(b":42\r\n".to_vec())).unwrap();
+        match client.get_response(Command::cmd("GET")) {
+            Err(RedisError::ConnectionDesynchronized) => {}
+            other => panic!("expected the client to be poisoned, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn frames_that_miss_the_deadline_can_still_be_waited_for() {
+        let (mut client, send_bytes) = Client::new();
+        send_bytes.send(Ok(b"$5\r\n".to_vec())).unwrap();
+
+        let deadline = Instant::now() + Duration::from_millis(10);
+        assert_eq!(
+            None,
+            client
+                .try_get_frame_before(Command::cmd("GET"), Some(deadline))
+                .unwrap()
+        );
+        assert!(!client.is_poisoned());
+
+        send_bytes.send(Ok(b"hello\r\n".to_vec())).unwrap();
+        assert_eq!(
+            Some(Some(RedisValue::String(b"hello".to_vec()))),
+            client
+                .try_get_frame_before(Command::cmd("GET"), None)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn replies_within_the_deadline_are_returned() {
+        let (mut client, send_bytes) = Client::new();
+        send_bytes.send(Ok(b":42\r\n".to_vec())).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        assert_eq!(
+            Some(RedisValue::Integer(42)),
+            client
+                .get_response_before(Command::cmd("GET"), Some(deadline))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn push_frames_are_skipped_when_waiting_for_a_reply() {
+        let (mut client, send_bytes) = Client::new();
+        send_bytes
+            .send(Ok(b">2\r\n+invalidate\r\n*1\r\n+key\r\n:42\r\n".to_vec()))
+            .unwrap();
+
+        assert_eq!(
+            Some(RedisValue::Integer(42)),
+            client.get_response(Command::cmd("INCR")).unwrap()
+        );
+    }
+
+    #[test]
+    fn push_frames_are_returned_when_waiting_for_a_frame() {
+        let (mut client, send_bytes) = Client::new();
+        send_bytes.send(Ok(b">1\r\n+hello\r\n".to_vec())).unwrap();
+
+        assert_eq!(
+            Some(RedisValue::Array(vec![Some(RedisValue::String(
+                b"hello".to_vec()
+            ))])),
+            client.get_frame_before(Command::cmd("GET"), None).unwrap()
+        );
+    }
+}
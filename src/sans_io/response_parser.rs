@@ -5,19 +5,144 @@ use crate::types::RedisErrorValue;
 use crate::types::RedisResult;
 use std::cmp;
 use std::iter::empty;
+use std::ops::Range;
+
+#[cfg(feature = "bytes")]
+use crate::types::SharedRedisResult;
+#[cfg(feature = "bytes")]
+use bytes::{Bytes, BytesMut};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ParseError {
     CannotParseInteger(std::num::ParseIntError),
+    CannotParseDouble(std::num::ParseFloatError),
+    InvalidBoolean,
+    InvalidNull,
+    InvalidBigNumber,
     CannotConvertToUtf8(Utf8Error),
     InvalidBulkStringLength(i64),
     InvalidArrayLength(i64),
+    BulkStringLengthMismatch(usize),
+    InvalidVerbatimStringFormat,
     InvalidResponseTypePrefix(char),
     ParserIsInAnErrorState,
 }
 
+// How parsed values are built up into a reply.  The parser is generic over this so that strings
+// can either be copied out of the buffer as soon as they're found, or just have their position
+// recorded, to be sliced out once the whole reply has arrived.
+pub(in crate::sans_io) trait Reply: Sized {
+    fn integer(int: i64) -> Self;
+    fn double(double: f64) -> Self;
+    fn boolean(boolean: bool) -> Self;
+    fn big_number(digits: String) -> Self;
+    fn error(error: RedisErrorValue) -> Self;
+    fn null() -> Self;
+    fn array(elements: Vec<Self>) -> Self;
+    fn map(pairs: Vec<(Self, Self)>) -> Self;
+    fn push(elements: Vec<Self>) -> Self;
+    fn set(elements: Vec<Self>) -> Self;
+    fn string(data: &[u8], range: Range<usize>) -> Self;
+    fn verbatim_string(format: [u8; 3], data: &[u8], range: Range<usize>) -> Self;
+}
+
+impl Reply for RedisResult {
+    fn integer(int: i64) -> Self {
+        RedisResult::Integer(int)
+    }
+
+    fn double(double: f64) -> Self {
+        RedisResult::Double(double)
+    }
+
+    fn boolean(boolean: bool) -> Self {
+        RedisResult::Boolean(boolean)
+    }
+
+    fn big_number(digits: String) -> Self {
+        RedisResult::BigNumber(digits)
+    }
+
+    fn error(error: RedisErrorValue) -> Self {
+        RedisResult::Error(error)
+    }
+
+    fn null() -> Self {
+        RedisResult::Null
+    }
+
+    fn array(elements: Vec<Self>) -> Self {
+        RedisResult::Array(elements)
+    }
+
+    fn map(pairs: Vec<(Self, Self)>) -> Self {
+        RedisResult::Map(pairs)
+    }
+
+    fn push(elements: Vec<Self>) -> Self {
+        RedisResult::Push(elements)
+    }
+
+    fn set(elements: Vec<Self>) -> Self {
+        RedisResult::Set(elements)
+    }
+
+    fn string(data: &[u8], range: Range<usize>) -> Self {
+        RedisResult::String(data[range].to_vec())
+    }
+
+    fn verbatim_string(format: [u8; 3], data: &[u8], range: Range<usize>) -> Self {
+        RedisResult::VerbatimString {
+            format,
+            data: data[range].to_vec(),
+        }
+    }
+}
+
+// The different replies that are framed as a count followed by that many elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    Array,
+    // RESP3 maps have a key and a value for each entry, so twice as many elements as the count
+    Map,
+    // RESP3 out-of-band data, such as pubsub messages, which isn't the reply to any command
+    Push,
+    // RESP3 unordered collections, framed exactly like arrays
+    Set,
+    // RESP3 metadata about the value that follows it, framed like a map.  Attributes aren't
+    // values themselves, so they're put to one side rather than built into the reply.
+    Attribute,
+}
+
+impl Aggregate {
+    fn len(self, count: usize) -> usize {
+        match self {
+            Aggregate::Map | Aggregate::Attribute => count * 2,
+            Aggregate::Array | Aggregate::Push | Aggregate::Set => count,
+        }
+    }
+
+    fn build<R: Reply>(self, elements: Vec<R>) -> R {
+        match self {
+            Aggregate::Array => R::array(elements),
+            Aggregate::Push => R::push(elements),
+            Aggregate::Set => R::set(elements),
+            Aggregate::Map | Aggregate::Attribute => R::map(pairs(elements)),
+        }
+    }
+}
+
+fn pairs<R>(elements: Vec<R>) -> Vec<(R, R)> {
+    let mut elements = elements.into_iter();
+    let mut pairs = Vec::with_capacity(elements.len() / 2);
+    while let (Some(key), Some(value)) = (elements.next(), elements.next()) {
+        pairs.push((key, value));
+    }
+    pairs
+}
+
 #[derive(Debug)]
-enum ResponseParserState {
+enum ResponseParserState<R> {
     Waiting,
     Errored,
     ParsingInteger {
@@ -26,35 +151,56 @@ enum ResponseParserState {
     ParsingSimpleString {
         start: usize,
     },
+    ParsingDouble {
+        start: usize,
+    },
+    ParsingBoolean {
+        start: usize,
+    },
+    ParsingBigNumber {
+        start: usize,
+    },
+    ParsingNull {
+        start: usize,
+    },
     ParsingError {
         start: usize,
     },
+    // verbatim strings (RESP3) are framed exactly like bulk strings, but start with a format
     ParsingBulkStringSize {
         start: usize,
+        verbatim: bool,
     },
     ParsingBulkString {
         start: usize,
         size: usize,
+        verbatim: bool,
     },
     ParsingArraySize {
         start: usize,
+        kind: Aggregate,
     },
     ParsingArray {
-        elements: Vec<RedisResult>,
-        cur_state: Box<ResponseParserState>,
+        elements: Vec<R>,
+        cur_state: Box<ResponseParserState<R>>,
+        kind: Aggregate,
     },
 }
 
-fn max_needed_buffer(state: &ResponseParserState, current: usize) -> usize {
+fn max_needed_buffer<R>(state: &ResponseParserState<R>, current: usize) -> usize {
     match state {
         ResponseParserState::Waiting => 0,
         ResponseParserState::Errored => 0,
         ResponseParserState::ParsingInteger { start } => current - *start,
         ResponseParserState::ParsingSimpleString { start } => current - *start,
+        ResponseParserState::ParsingDouble { start } => current - *start,
+        ResponseParserState::ParsingBoolean { start } => current - *start,
+        ResponseParserState::ParsingBigNumber { start } => current - *start,
+        ResponseParserState::ParsingNull { start } => current - *start,
         ResponseParserState::ParsingError { start } => current - *start,
-        ResponseParserState::ParsingBulkStringSize { start } => current - *start,
+        ResponseParserState::ParsingBulkStringSize { start, .. } => current - *start,
         ResponseParserState::ParsingBulkString { start, .. } => current - *start,
-        ResponseParserState::ParsingArraySize { start } => current - *start,
+        ResponseParserState::ParsingArraySize { start, .. } => current - *start,
         ResponseParserState::ParsingArray { cur_state, .. } => {
             max_needed_buffer(cur_state, current)
         }
@@ -72,27 +218,109 @@ fn parse_integer(data: &[u8], start: usize, ptr: &mut usize) -> Option<Result<i6
     }
 }
 
-fn parse_simple_string(data: &[u8], start: usize, ptr: &mut usize) -> Option<Vec<u8>> {
+// RESP3 doubles include `inf`, `-inf` and `nan`, all of which Rust's float parsing understands
+fn parse_double(data: &[u8], start: usize, ptr: &mut usize) -> Option<Result<f64, ParseError>> {
+    match data[*ptr] as char {
+        '\r' => Some(
+            from_utf8(&data[start..*ptr])
+                .map_err(ParseError::CannotConvertToUtf8)
+                .and_then(|str| str.parse().map_err(ParseError::CannotParseDouble)),
+        ),
+        _ => None,
+    }
+}
+
+// Big numbers are sent as decimal digits, optionally with a leading minus sign
+fn is_big_number(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn parse_simple_string(data: &[u8], start: usize, ptr: &mut usize) -> Option<Range<usize>> {
     match data[*ptr] as char {
-        '\r' => Some((&data[start..*ptr]).to_vec()),
+        '\r' => Some(start..*ptr),
         _ => None,
     }
 }
 
-fn parse_response(
+// When a whole integer or simple string reply has already been buffered, it can be read in one
+// go, rather than stepping through the state machine one byte at a time.  This gives the same
+// results as the byte-by-byte path, including only looking for the `\r` of the terminator.
+fn parse_buffered_line<R: Reply>(data: &[u8], ptr: &mut usize) -> Option<Result<R, ParseError>> {
+    let prefix = data[*ptr];
+    if prefix != b':' && prefix != b'+' {
+        return None;
+    }
+
+    let start = *ptr + 1;
+    let end = start + data[start..].iter().position(|&byte| byte == b'\r')?;
+    *ptr = end + 2;
+
+    Some(match prefix {
+        b':' => from_utf8(&data[start..end])
+            .map_err(ParseError::CannotConvertToUtf8)
+            .and_then(|str| str.parse().map_err(ParseError::CannotParseInteger))
+            .map(R::integer),
+        _ => Ok(R::string(data, start..end)),
+    })
+}
+
+// Any attributes found along the way are added to `attributes`, and parsing carries on with the
+// value that they describe
+fn parse_response<R: Reply>(
     data: &[u8],
     ptr: &mut usize,
-    state: &mut ResponseParserState,
-) -> Result<Option<RedisResult>, ParseError> {
+    state: &mut ResponseParserState<R>,
+    attributes: &mut Vec<(R, R)>,
+) -> Result<Option<R>, ParseError> {
     while *ptr < data.len() {
         match state {
             ResponseParserState::Waiting => {
+                match parse_buffered_line(data, ptr) {
+                    Some(Ok(result)) => return Ok(Some(result)),
+                    Some(Err(err)) => {
+                        *state = ResponseParserState::Errored;
+                        return Err(err);
+                    }
+                    None => {}
+                }
+
                 *state = match data[*ptr] as char {
                     ':' => ResponseParserState::ParsingInteger { start: *ptr + 1 },
                     '+' => ResponseParserState::ParsingSimpleString { start: *ptr + 1 },
+                    ',' => ResponseParserState::ParsingDouble { start: *ptr + 1 },
+                    '#' => ResponseParserState::ParsingBoolean { start: *ptr + 1 },
+                    '(' => ResponseParserState::ParsingBigNumber { start: *ptr + 1 },
+                    '_' => ResponseParserState::ParsingNull { start: *ptr + 1 },
                     '-' => ResponseParserState::ParsingError { start: *ptr + 1 },
-                    '$' => ResponseParserState::ParsingBulkStringSize { start: *ptr + 1 },
-                    '*' => ResponseParserState::ParsingArraySize { start: *ptr + 1 },
+                    '$' => ResponseParserState::ParsingBulkStringSize {
+                        start: *ptr + 1,
+                        verbatim: false,
+                    },
+                    '=' => ResponseParserState::ParsingBulkStringSize {
+                        start: *ptr + 1,
+                        verbatim: true,
+                    },
+                    '*' => ResponseParserState::ParsingArraySize {
+                        start: *ptr + 1,
+                        kind: Aggregate::Array,
+                    },
+                    '%' => ResponseParserState::ParsingArraySize {
+                        start: *ptr + 1,
+                        kind: Aggregate::Map,
+                    },
+                    '>' => ResponseParserState::ParsingArraySize {
+                        start: *ptr + 1,
+                        kind: Aggregate::Push,
+                    },
+                    '~' => ResponseParserState::ParsingArraySize {
+                        start: *ptr + 1,
+                        kind: Aggregate::Set,
+                    },
+                    '|' => ResponseParserState::ParsingArraySize {
+                        start: *ptr + 1,
+                        kind: Aggregate::Attribute,
+                    },
                     any => {
                         *state = ResponseParserState::Errored;
                         return Err(ParseError::InvalidResponseTypePrefix(any));
@@ -105,7 +333,7 @@ fn parse_response(
                     Some(Ok(int)) => {
                         *state = ResponseParserState::Waiting;
                         *ptr += 2;
-                        return Ok(Some(RedisResult::Integer(int)));
+                        return Ok(Some(R::integer(int)));
                     }
                     Some(Err(err)) => {
                         *state = ResponseParserState::Errored;
@@ -118,10 +346,79 @@ fn parse_response(
             }
             ResponseParserState::ParsingSimpleString { start } => {
                 match parse_simple_string(data, *start, ptr) {
-                    Some(vec) => {
+                    Some(range) => {
                         *state = ResponseParserState::Waiting;
                         *ptr += 2;
-                        return Ok(Some(RedisResult::String(vec)));
+                        return Ok(Some(R::string(data, range)));
+                    }
+                    None => {
+                        *ptr += 1;
+                    }
+                }
+            }
+            ResponseParserState::ParsingDouble { start } => match parse_double(data, *start, ptr) {
+                Some(Ok(double)) => {
+                    *state = ResponseParserState::Waiting;
+                    *ptr += 2;
+                    return Ok(Some(R::double(double)));
+                }
+                Some(Err(err)) => {
+                    *state = ResponseParserState::Errored;
+                    return Err(err);
+                }
+                None => {
+                    *ptr += 1;
+                }
+            },
+            ResponseParserState::ParsingBoolean { start } => {
+                match parse_simple_string(data, *start, ptr) {
+                    Some(range) => {
+                        *ptr += 2;
+                        let boolean = match &data[range] {
+                            b"t" => true,
+                            b"f" => false,
+                            _ => {
+                                *state = ResponseParserState::Errored;
+                                return Err(ParseError::InvalidBoolean);
+                            }
+                        };
+                        *state = ResponseParserState::Waiting;
+                        return Ok(Some(R::boolean(boolean)));
+                    }
+                    None => {
+                        *ptr += 1;
+                    }
+                }
+            }
+            ResponseParserState::ParsingBigNumber { start } => {
+                match parse_simple_string(data, *start, ptr) {
+                    Some(range) => {
+                        *ptr += 2;
+                        let digits = match from_utf8(&data[range]) {
+                            Ok(digits) if is_big_number(digits) => digits.to_string(),
+                            _ => {
+                                *state = ResponseParserState::Errored;
+                                return Err(ParseError::InvalidBigNumber);
+                            }
+                        };
+                        *state = ResponseParserState::Waiting;
+                        return Ok(Some(R::big_number(digits)));
+                    }
+                    None => {
+                        *ptr += 1;
+                    }
+                }
+            }
+            ResponseParserState::ParsingNull { start } => {
+                match parse_simple_string(data, *start, ptr) {
+                    Some(range) => {
+                        *ptr += 2;
+                        if !range.is_empty() {
+                            *state = ResponseParserState::Errored;
+                            return Err(ParseError::InvalidNull);
+                        }
+                        *state = ResponseParserState::Waiting;
+                        return Ok(Some(R::null()));
                     }
                     None => {
                         *ptr += 1;
@@ -130,13 +427,13 @@ fn parse_response(
             }
             ResponseParserState::ParsingError { start } => {
                 match parse_simple_string(data, *start, ptr) {
-                    Some(bytes) => {
+                    Some(range) => {
                         *state = ResponseParserState::Waiting;
                         *ptr += 2;
-                        let error_string =
-                            from_utf8(&bytes).map_err(|err| ParseError::CannotConvertToUtf8(err));
+                        let error_string = from_utf8(&data[range])
+                            .map_err(|err| ParseError::CannotConvertToUtf8(err));
                         return match error_string {
-                            Ok(s) => Ok(Some(RedisResult::Error(RedisErrorValue::new(s)))),
+                            Ok(s) => Ok(Some(R::error(RedisErrorValue::new(s)))),
                             Err(error) => {
                                 *state = ResponseParserState::Errored;
                                 Err(error)
@@ -148,24 +445,24 @@ fn parse_response(
                     }
                 }
             }
-            ResponseParserState::ParsingBulkStringSize { start } => {
+            ResponseParserState::ParsingBulkStringSize { start, verbatim } => {
+                // verbatim strings must have room for at least the format and its separator
+                let min_size = if *verbatim { 4 } else { 0 };
                 match parse_integer(data, *start, ptr) {
-                    Some(Ok(int @ 1...std::i64::MAX)) => {
+                    // an empty string still has its own trailing CRLF, so it needs to go through
+                    // the same path as any other bulk string to make sure that it has arrived
+                    Some(Ok(int @ 0...std::i64::MAX)) if int >= min_size => {
                         *ptr += 2;
                         *state = ResponseParserState::ParsingBulkString {
                             start: *ptr,
                             size: int as usize,
+                            verbatim: *verbatim,
                         };
                     }
-                    Some(Ok(0)) => {
-                        *ptr += 4;
-                        *state = ResponseParserState::Waiting;
-                        return Ok(Some(RedisResult::String(Vec::new())));
-                    }
-                    Some(Ok(-1)) => {
+                    Some(Ok(-1)) if !*verbatim => {
                         *ptr += 2;
                         *state = ResponseParserState::Waiting;
-                        return Ok(Some(RedisResult::Null));
+                        return Ok(Some(R::null()));
                     }
                     Some(Ok(int)) => {
                         *state = ResponseParserState::Errored;
@@ -180,40 +477,61 @@ fn parse_response(
                     }
                 }
             }
-            ResponseParserState::ParsingBulkString { start, size } => {
-                if *start + *size == *ptr {
-                    *ptr += 2;
+            ResponseParserState::ParsingBulkString { start, size, .. } => {
+                let end = *start + *size;
+                if data.len() < end + 2 {
+                    // wait until the whole string and its terminator have arrived
+                    *ptr = data.len();
+                } else if &data[end..end + 2] != b"\r\n" {
+                    let size = *size;
+                    *state = ResponseParserState::Errored;
+                    return Err(ParseError::BulkStringLengthMismatch(size));
+                } else {
+                    *ptr = end + 2;
 
-                    if let ResponseParserState::ParsingBulkString { start, .. } =
-                        replace(state, ResponseParserState::Waiting)
+                    if let ResponseParserState::ParsingBulkString {
+                        start, verbatim, ..
+                    } = replace(state, ResponseParserState::Waiting)
                     {
-                        return Ok(Some(RedisResult::String(data[start..(*ptr - 2)].to_vec())));
+                        if !verbatim {
+                            return Ok(Some(R::string(data, start..end)));
+                        } else if data[start + 3] != b':' {
+                            *state = ResponseParserState::Errored;
+                            return Err(ParseError::InvalidVerbatimStringFormat);
+                        } else {
+                            let mut format = [0; 3];
+                            format.copy_from_slice(&data[start..start + 3]);
+                            return Ok(Some(R::verbatim_string(format, data, start + 4..end)));
+                        }
                     } else {
                         panic!("This point should be unreachable");
                     }
-                } else if *start + *size < *ptr {
-                    unreachable!("The current position pointer is beyond the end of the string we are trying to parser.  This is Bad News.")
                 }
-                *ptr += 1;
             }
-            ResponseParserState::ParsingArraySize { start } => {
+            ResponseParserState::ParsingArraySize { start, kind } => {
                 match parse_integer(data, *start, ptr) {
                     Some(Ok(int @ 1...std::i64::MAX)) => {
                         *ptr += 2;
                         *state = ResponseParserState::ParsingArray {
-                            elements: Vec::with_capacity(int as usize),
+                            elements: Vec::with_capacity(kind.len(int as usize)),
                             cur_state: Box::new(ResponseParserState::Waiting),
+                            kind: *kind,
                         };
                     }
+                    Some(Ok(0)) if *kind == Aggregate::Attribute => {
+                        *ptr += 2;
+                        *state = ResponseParserState::Waiting;
+                    }
                     Some(Ok(0)) => {
                         *ptr += 2;
+                        let empty = kind.build(Vec::new());
                         *state = ResponseParserState::Waiting;
-                        return Ok(Some(RedisResult::Array(Vec::new())));
+                        return Ok(Some(empty));
                     }
-                    Some(Ok(-1)) => {
+                    Some(Ok(-1)) if *kind == Aggregate::Array => {
                         *ptr += 2;
                         *state = ResponseParserState::Waiting;
-                        return Ok(Some(RedisResult::Null));
+                        return Ok(Some(R::null()));
                     }
                     Some(Ok(int)) => {
                         *state = ResponseParserState::Errored;
@@ -231,14 +549,19 @@ fn parse_response(
             ResponseParserState::ParsingArray {
                 elements,
                 cur_state,
-            } => match parse_response(data, ptr, cur_state) {
+                ..
+            } => match parse_response(data, ptr, cur_state, attributes) {
                 Ok(Some(element)) => {
                     elements.push(element);
                     if elements.len() == elements.capacity() {
-                        if let ResponseParserState::ParsingArray { elements, .. } =
+                        if let ResponseParserState::ParsingArray { elements, kind, .. } =
                             replace(state, ResponseParserState::Waiting)
                         {
-                            return Ok(Some(RedisResult::Array(elements)));
+                            if kind == Aggregate::Attribute {
+                                attributes.extend(pairs(elements));
+                                continue;
+                            }
+                            return Ok(Some(kind.build(elements)));
                         } else {
                             panic!("This point should be unreachable");
                         }
@@ -263,30 +586,49 @@ fn parse_response(
 pub(in crate::sans_io) struct ResponseParser {
     buffer: Vec<u8>,
     ptr: usize,
-    state: ResponseParserState,
+    state: ResponseParserState<RedisResult>,
+    // the attributes sent with the reply currently being parsed
+    attributes: Vec<(RedisResult, RedisResult)>,
+    // the attributes sent with the last reply to be returned
+    last_attributes: Vec<(RedisResult, RedisResult)>,
 }
 
 impl ResponseParser {
+    pub(in crate::sans_io) fn feed(&mut self, response: &[u8]) {
+        self.buffer.extend_from_slice(response)
+    }
+
     pub(in crate::sans_io) fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    // Pre-sizes the buffer so that replies up to `capacity` bytes don't need to reallocate.  The
+    // buffer is only ever drained, never shrunk, so the capacity is kept between replies.
+    pub(in crate::sans_io) fn with_capacity(capacity: usize) -> Self {
         Self {
-            buffer: Vec::new(),
+            buffer: Vec::with_capacity(capacity),
             ptr: 0,
             state: ResponseParserState::Waiting,
+            attributes: Vec::new(),
+            last_attributes: Vec::new(),
         }
     }
 
-    pub(in crate::sans_io) fn feed(&mut self, response: &[u8]) {
-        self.buffer.extend_from_slice(response)
-    }
-
     pub(in crate::sans_io) fn get_response(&mut self) -> Result<Option<RedisResult>, ParseError> {
-        let Self { buffer, ptr, state } = self;
-        let response = parse_response(&buffer, ptr, state);
+        let Self {
+            buffer,
+            ptr,
+            state,
+            attributes,
+            last_attributes,
+        } = self;
+        let response = parse_response(&buffer, ptr, state, attributes);
         if let Ok(Some(response)) = response {
-            let needed_buffer_start = *ptr - max_needed_buffer(state, *ptr);
+            *last_attributes = std::mem::take(attributes);
+            let needed_buffer_start = ptr.saturating_sub(max_needed_buffer(state, *ptr));
             // occasionally the ptr ends up further beyond the buffer size - that's okay,
             // we'll just delete what is available, and worry about the rest later
-            let needed_buffer_start = cmp::min(needed_buffer_start, buffer.len() - 1);
+            let needed_buffer_start = cmp::min(needed_buffer_start, buffer.len());
             buffer.splice(0..needed_buffer_start, empty());
             *ptr -= needed_buffer_start;
             Ok(Some(response))
@@ -294,13 +636,286 @@ impl ResponseParser {
             response
         }
     }
+
+    // The attributes (RESP3 metadata, e.g. `|1\r\n+key-popularity\r\n...`) that were sent with
+    // the last reply returned by `get_response`, including any attached to its elements
+    pub(in crate::sans_io) fn take_attributes(&mut self) -> Vec<(RedisResult, RedisResult)> {
+        std::mem::take(&mut self.last_attributes)
+    }
+}
+
+// Strings are recorded as positions in the buffer until the whole reply has arrived, at which
+// point the buffer is handed over to a `Bytes` and the strings are sliced out of that
+#[cfg(feature = "bytes")]
+#[derive(Debug)]
+pub(in crate::sans_io) enum RangedReply {
+    Value(SharedRedisResult),
+    String(Range<usize>),
+    VerbatimString {
+        format: [u8; 3],
+        range: Range<usize>,
+    },
+    Array(Vec<RangedReply>),
+    Map(Vec<(RangedReply, RangedReply)>),
+    Push(Vec<RangedReply>),
+    Set(Vec<RangedReply>),
+}
+
+#[cfg(feature = "bytes")]
+impl RangedReply {
+    fn resolve(self, buffer: &Bytes) -> SharedRedisResult {
+        match self {
+            RangedReply::Value(value) => value,
+            RangedReply::String(range) => SharedRedisResult::String(buffer.slice(range)),
+            RangedReply::VerbatimString { format, range } => SharedRedisResult::VerbatimString {
+                format,
+                data: buffer.slice(range),
+            },
+            RangedReply::Array(elements) => SharedRedisResult::Array(
+                elements
+                    .into_iter()
+                    .map(|element| element.resolve(buffer))
+                    .collect(),
+            ),
+            RangedReply::Map(pairs) => SharedRedisResult::Map(
+                pairs
+                    .into_iter()
+                    .map(|(key, value)| (key.resolve(buffer), value.resolve(buffer)))
+                    .collect(),
+            ),
+            RangedReply::Push(elements) => SharedRedisResult::Push(
+                elements
+                    .into_iter()
+                    .map(|element| element.resolve(buffer))
+                    .collect(),
+            ),
+            RangedReply::Set(elements) => SharedRedisResult::Set(
+                elements
+                    .into_iter()
+                    .map(|element| element.resolve(buffer))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Reply for RangedReply {
+    fn integer(int: i64) -> Self {
+        RangedReply::Value(SharedRedisResult::Integer(int))
+    }
+
+    fn double(double: f64) -> Self {
+        RangedReply::Value(SharedRedisResult::Double(double))
+    }
+
+    fn boolean(boolean: bool) -> Self {
+        RangedReply::Value(SharedRedisResult::Boolean(boolean))
+    }
+
+    fn big_number(digits: String) -> Self {
+        RangedReply::Value(SharedRedisResult::BigNumber(digits))
+    }
+
+    fn error(error: RedisErrorValue) -> Self {
+        RangedReply::Value(SharedRedisResult::Error(error))
+    }
+
+    fn null() -> Self {
+        RangedReply::Value(SharedRedisResult::Null)
+    }
+
+    fn array(elements: Vec<Self>) -> Self {
+        RangedReply::Array(elements)
+    }
+
+    fn map(pairs: Vec<(Self, Self)>) -> Self {
+        RangedReply::Map(pairs)
+    }
+
+    fn push(elements: Vec<Self>) -> Self {
+        RangedReply::Push(elements)
+    }
+
+    fn set(elements: Vec<Self>) -> Self {
+        RangedReply::Set(elements)
+    }
+
+    fn string(_: &[u8], range: Range<usize>) -> Self {
+        RangedReply::String(range)
+    }
+
+    fn verbatim_string(format: [u8; 3], _: &[u8], range: Range<usize>) -> Self {
+        RangedReply::VerbatimString { format, range }
+    }
+}
+
+// A parser for callers doing their own IO, which avoids copying every string out of the read
+// buffer.  Each reply keeps its part of the buffer alive for as long as any of its strings are.
+#[cfg(feature = "bytes")]
+#[derive(Debug)]
+pub struct SharedResponseParser {
+    buffer: BytesMut,
+    ptr: usize,
+    state: ResponseParserState<RangedReply>,
+    // attributes aren't handed out by this parser, so they're only collected to be skipped over
+    attributes: Vec<(RangedReply, RangedReply)>,
+}
+
+#[cfg(feature = "bytes")]
+impl SharedResponseParser {
+    pub fn new() -> Self {
+        SharedResponseParser {
+            buffer: BytesMut::new(),
+            ptr: 0,
+            state: ResponseParserState::Waiting,
+            attributes: Vec::new(),
+        }
+    }
+
+    pub fn feed(&mut self, response: &[u8]) {
+        self.buffer.extend_from_slice(response)
+    }
+
+    pub fn get_response(&mut self) -> Result<Option<SharedRedisResult>, ParseError> {
+        self.attributes.clear();
+        let response = parse_response(
+            &self.buffer,
+            &mut self.ptr,
+            &mut self.state,
+            &mut self.attributes,
+        )?;
+        Ok(response.map(|response| {
+            // the finished reply is split off the front of the buffer and handed over as it is,
+            // so nothing is copied - the rest of the buffer stays where it is for the next reply
+            let consumed = cmp::min(self.ptr, self.buffer.len());
+            let reply = self.buffer.split_to(consumed).freeze();
+            self.ptr -= consumed;
+            response.resolve(&reply)
+        }))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Default for SharedResponseParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// What `StreamingResponseParser` hands out: either a whole reply, or one element of an array
+#[derive(Debug, PartialEq, Clone)]
+pub enum StreamedReply {
+    // any reply other than a non-empty array, in full
+    Whole(RedisResult),
+    // element number `index` (counting from 0) of an array reply with `total` elements
+    Element {
+        index: usize,
+        total: usize,
+        element: RedisResult,
+    },
+}
+
+// A parser for callers doing their own IO that hands out the elements of an array reply as each
+// one arrives, rather than once the whole array has been read, so that very large replies (e.g.
+// a full LRANGE of a huge list) can be processed without holding them in memory.  Only the
+// top-level array is streamed - elements that are themselves arrays are returned whole.
+#[derive(Debug)]
+pub struct StreamingResponseParser {
+    parser: ResponseParser,
+    // the next index and total size of the array currently being streamed
+    array: Option<(usize, usize)>,
+}
+
+impl StreamingResponseParser {
+    pub fn new() -> Self {
+        Self {
+            parser: ResponseParser::new(),
+            array: None,
+        }
+    }
+
+    pub fn feed(&mut self, response: &[u8]) {
+        self.parser.feed(response)
+    }
+
+    pub fn get_next(&mut self) -> Result<Option<StreamedReply>, ParseError> {
+        if self.array.is_none() {
+            match self.array_header() {
+                ArrayHeader::Array(total) => self.array = Some((0, total)),
+                ArrayHeader::Incomplete => return Ok(None),
+                ArrayHeader::Other => {
+                    return Ok(self.parser.get_response()?.map(StreamedReply::Whole));
+                }
+            }
+        }
+
+        let element = match self.parser.get_response()? {
+            Some(element) => element,
+            None => return Ok(None),
+        };
+        let (index, total) = self.array.take().expect("an array is being streamed");
+        if index + 1 < total {
+            self.array = Some((index + 1, total));
+        }
+        Ok(Some(StreamedReply::Element {
+            index,
+            total,
+            element,
+        }))
+    }
+
+    // If the next reply is a non-empty array, consumes its header and returns the array's size.
+    // Everything else, including malformed headers, is left for the normal parser to deal with.
+    fn array_header(&mut self) -> ArrayHeader {
+        let ResponseParser {
+            buffer, ptr, state, ..
+        } = &mut self.parser;
+        if !matches!(state, ResponseParserState::Waiting) || buffer.get(*ptr) != Some(&b'*') {
+            return ArrayHeader::Other;
+        }
+
+        let start = *ptr + 1;
+        let end = match buffer[start..].iter().position(|&byte| byte == b'\r') {
+            Some(len) if buffer.len() >= start + len + 2 => start + len,
+            _ => return ArrayHeader::Incomplete,
+        };
+        let total = from_utf8(&buffer[start..end])
+            .ok()
+            .and_then(|total| total.parse().ok());
+        match total {
+            Some(total) if total > 0 => {
+                buffer.drain(..end + 2);
+                *ptr = 0;
+                ArrayHeader::Array(total)
+            }
+            _ => ArrayHeader::Other,
+        }
+    }
+}
+
+enum ArrayHeader {
+    Array(usize),
+    // the header hasn't fully arrived yet, so it isn't known whether the array can be streamed
+    Incomplete,
+    Other,
+}
+
+impl Default for StreamingResponseParser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use quickcheck::TestResult;
+    use crate::types::{encode_result, encode_value};
+    use crate::{redis_value, RedisValue};
+    use quickcheck::{Arbitrary, Gen, TestResult};
     use quickcheck_macros::quickcheck;
+    use rand::Rng;
+    use std::convert::TryInto;
 
     #[test]
     fn can_parse_numbers_from_redis_response() {
@@ -309,6 +924,20 @@ mod tests {
         assert_eq!(Ok(Some(RedisResult::Integer(42))), parser.get_response());
     }
 
+    #[test]
+    fn presized_parser_keeps_its_capacity_between_replies() {
+        let mut parser = ResponseParser::with_capacity(1024);
+        assert!(parser.buffer.capacity() >= 1024);
+
+        parser.feed(b":42\r\n$5\r\nhello\r\n");
+        assert_eq!(Ok(Some(RedisResult::Integer(42))), parser.get_response());
+        assert_eq!(
+            Ok(Some(RedisResult::String(b"hello".to_vec()))),
+            parser.get_response()
+        );
+        assert!(parser.buffer.capacity() >= 1024);
+    }
+
     #[quickcheck]
     fn qc_can_parse_any_number_from_redis_response(num: i64) {
         let mut parser = ResponseParser::new();
@@ -316,6 +945,112 @@ mod tests {
         assert_eq!(Ok(Some(RedisResult::Integer(num))), parser.get_response());
     }
 
+    // Feeding one byte at a time means that no line is ever completely buffered, so this never
+    // takes the fast path
+    fn parse_byte_by_byte(input: &[u8]) -> Vec<Result<Option<RedisResult>, ParseError>> {
+        let mut parser = ResponseParser::new();
+        let mut results = Vec::new();
+        for byte in input {
+            parser.feed(&[*byte]);
+            match parser.get_response() {
+                Ok(None) => {}
+                Err(err) => {
+                    results.push(Err(err));
+                    return results;
+                }
+                result => results.push(result),
+            }
+        }
+        results
+    }
+
+    fn parse_all_at_once(input: &[u8]) -> Vec<Result<Option<RedisResult>, ParseError>> {
+        let mut parser = ResponseParser::new();
+        parser.feed(input);
+        let mut results = Vec::new();
+        loop {
+            match parser.get_response() {
+                Ok(None) => return results,
+                Err(err) => {
+                    results.push(Err(err));
+                    return results;
+                }
+                result => results.push(result),
+            }
+        }
+    }
+
+    #[test]
+    fn fast_path_matches_byte_by_byte_parsing() {
+        let input = b":42\r\n+OK\r\n*3\r\n:-7\r\n+PONG\r\n$5\r\nhello\r\n+\r\n:nope\r\n";
+        let results = parse_all_at_once(input);
+        assert_eq!(parse_byte_by_byte(input), results);
+        assert_eq!(5, results.len());
+        assert!(results[4].is_err());
+    }
+
+    #[quickcheck]
+    fn qc_fast_path_matches_byte_by_byte_parsing(ints: Vec<i64>, strings: Vec<String>) {
+        let mut input = Vec::new();
+        for (int, string) in ints.iter().zip(strings.iter()) {
+            input.extend_from_slice(format!(":{}\r\n", int).as_bytes());
+            let string = string.replace(['\r', '\n'], "");
+            input.extend_from_slice(format!("+{}\r\n", string).as_bytes());
+        }
+        assert_eq!(parse_byte_by_byte(&input), parse_all_at_once(&input));
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn shared_strings_are_slices_of_the_same_buffer() {
+        let mut parser = SharedResponseParser::new();
+        parser.feed(b"*2\r\n$5\r\nhello\r\n$5\r\nworld\r\n:1");
+
+        let elements = match parser.get_response() {
+            Ok(Some(SharedRedisResult::Array(elements))) => elements,
+            other => panic!("expected an array, got {:?}", other),
+        };
+        match (&elements[0], &elements[1]) {
+            (SharedRedisResult::String(hello), SharedRedisResult::String(world)) => {
+                assert_eq!(&b"hello"[..], hello);
+                assert_eq!(&b"world"[..], world);
+                assert_eq!(hello.as_ptr() as usize + 11, world.as_ptr() as usize);
+            }
+            other => panic!("expected two strings, got {:?}", other),
+        }
+
+        // the start of the next reply is carried over
+        assert_eq!(Ok(None), parser.get_response());
+        parser.feed(b"\r\n");
+        assert_eq!(
+            Ok(Some(SharedRedisResult::Integer(1))),
+            parser.get_response()
+        );
+    }
+
+    #[cfg(feature = "bytes")]
+    #[quickcheck]
+    fn qc_shared_parsing_matches_copied_parsing(strings: Vec<String>) {
+        let mut input = format!("*{}\r\n", strings.len()).into_bytes();
+        for string in &strings {
+            input.extend_from_slice(format!("${}\r\n{}\r\n", string.len(), string).as_bytes());
+        }
+        input.extend_from_slice(b"=7\r\ntxt:abc\r\n");
+
+        let mut copied = ResponseParser::new();
+        let mut shared = SharedResponseParser::new();
+        copied.feed(&input);
+        shared.feed(&input);
+        for _ in 0..2 {
+            assert_eq!(
+                copied.get_response(),
+                shared
+                    .get_response()
+                    .map(|result| result.map(RedisResult::from))
+            );
+        }
+    }
+
     #[test]
     fn can_parse_multiple_numbers_in_a_row() {
         let mut parser = ResponseParser::new();
@@ -418,8 +1153,7 @@ mod tests {
     #[test]
     fn can_parse_an_empty_bulk_string() {
         // probably caught already in the quickcheck test, but we might as well
-        // double check here that it's recognised.  This is currently special cased
-        // in the parser.
+        // double check here that it's recognised.
         let mut parser = ResponseParser::new();
         parser.feed("$0\r\n\r\n".as_bytes());
         assert_eq!(
@@ -428,6 +1162,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn empty_bulk_string_waits_for_its_trailing_crlf() {
+        let mut parser = ResponseParser::new();
+        parser.feed("$0\r\n".as_bytes());
+        assert_eq!(Ok(None), parser.get_response());
+
+        parser.feed("\r\n:1\r\n".as_bytes());
+        assert_eq!(
+            Ok(Some(RedisResult::String(b"".to_vec()))),
+            parser.get_response()
+        );
+        assert_eq!(Ok(Some(RedisResult::Integer(1))), parser.get_response());
+    }
+
     #[test]
     fn doesnt_fail_on_a_negative_bulk_string_size() {
         let mut parser = ResponseParser::new();
@@ -438,6 +1186,253 @@ mod tests {
         )
     }
 
+    #[test]
+    fn bulk_string_longer_than_its_declared_size_is_an_error() {
+        let mut parser = ResponseParser::new();
+        parser.feed("$2\r\nOK".as_bytes());
+        assert_eq!(Ok(None), parser.get_response());
+
+        parser.feed("K\r\n".as_bytes());
+        assert_eq!(
+            Err(ParseError::BulkStringLengthMismatch(2)),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn bulk_string_can_be_resumed_across_feeds() {
+        let mut parser = ResponseParser::new();
+        parser.feed("$5\r\nhel".as_bytes());
+        assert_eq!(Ok(None), parser.get_response());
+
+        parser.feed("lo\r".as_bytes());
+        assert_eq!(Ok(None), parser.get_response());
+
+        parser.feed("\n".as_bytes());
+        assert_eq!(
+            Ok(Some(RedisResult::String(b"hello".to_vec()))),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn buffer_is_emptied_when_a_reply_ends_exactly_at_its_end() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"$5\r\nhello\r\n");
+        assert_eq!(
+            Ok(Some(RedisResult::String(b"hello".to_vec()))),
+            parser.get_response()
+        );
+        assert!(parser.buffer.is_empty());
+        assert_eq!(0, parser.ptr);
+
+        parser.feed(b":42\r\n");
+        assert_eq!(Ok(Some(RedisResult::Integer(42))), parser.get_response());
+        assert!(parser.buffer.is_empty());
+        assert_eq!(Ok(None), parser.get_response());
+    }
+
+    #[test]
+    fn can_parse_doubles_including_infinities() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b",2.75\r\n,-2\r\n,inf\r\n,-inf\r\n");
+        assert_eq!(Ok(Some(RedisResult::Double(2.75))), parser.get_response());
+        assert_eq!(Ok(Some(RedisResult::Double(-2.0))), parser.get_response());
+        assert_eq!(
+            Ok(Some(RedisResult::Double(f64::INFINITY))),
+            parser.get_response()
+        );
+        assert_eq!(
+            Ok(Some(RedisResult::Double(f64::NEG_INFINITY))),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn double_can_be_resumed_across_feeds() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b",1.");
+        assert_eq!(Ok(None), parser.get_response());
+        parser.feed(b"5\r\n");
+        assert_eq!(Ok(Some(RedisResult::Double(1.5))), parser.get_response());
+    }
+
+    #[test]
+    fn can_parse_the_resp3_null() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"_\r\n*2\r\n_\r\n:1\r\n");
+        assert_eq!(Ok(Some(RedisResult::Null)), parser.get_response());
+        assert_eq!(
+            Ok(Some(RedisResult::Array(vec![
+                RedisResult::Null,
+                RedisResult::Integer(1)
+            ]))),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn resp3_null_with_contents_is_an_error() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"_x\r\n");
+        assert_eq!(Err(ParseError::InvalidNull), parser.get_response());
+    }
+
+    #[test]
+    fn can_parse_a_push() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b">3\r\n+message\r\n+channel\r\n$5\r\nhello\r\n");
+        assert_eq!(
+            Ok(Some(RedisResult::Push(vec![
+                RedisResult::String(b"message".to_vec()),
+                RedisResult::String(b"channel".to_vec()),
+                RedisResult::String(b"hello".to_vec()),
+            ]))),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn can_parse_a_set() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"~2\r\n+orange\r\n:1\r\n~0\r\n");
+        assert_eq!(
+            Ok(Some(RedisResult::Set(vec![
+                RedisResult::String(b"orange".to_vec()),
+                RedisResult::Integer(1),
+            ]))),
+            parser.get_response()
+        );
+        assert_eq!(
+            Ok(Some(RedisResult::Set(Vec::new()))),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn can_parse_big_numbers() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"(3492890328409238509324850943850943825024385\r\n(-12\r\n");
+        assert_eq!(
+            Ok(Some(RedisResult::BigNumber(
+                "3492890328409238509324850943850943825024385".to_string()
+            ))),
+            parser.get_response()
+        );
+        assert_eq!(
+            Ok(Some(RedisResult::BigNumber("-12".to_string()))),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn big_numbers_must_be_digits() {
+        for invalid in &[&b"(\r\n"[..], b"(-\r\n", b"(1.5\r\n", b"(12a\r\n"] {
+            let mut parser = ResponseParser::new();
+            parser.feed(invalid);
+            assert_eq!(Err(ParseError::InvalidBigNumber), parser.get_response());
+        }
+    }
+
+    #[test]
+    fn can_parse_a_map() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"%2\r\n+server\r\n+redis\r\n+proto\r\n:3\r\n%0\r\n");
+        assert_eq!(
+            Ok(Some(RedisResult::Map(vec![
+                (
+                    RedisResult::String(b"server".to_vec()),
+                    RedisResult::String(b"redis".to_vec())
+                ),
+                (
+                    RedisResult::String(b"proto".to_vec()),
+                    RedisResult::Integer(3)
+                ),
+            ]))),
+            parser.get_response()
+        );
+        assert_eq!(
+            Ok(Some(RedisResult::Map(Vec::new()))),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn map_can_be_resumed_across_feeds() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"%1\r\n$3\r\nkey\r\n");
+        assert_eq!(Ok(None), parser.get_response());
+        parser.feed(b"*1\r\n:1\r\n");
+        assert_eq!(
+            Ok(Some(RedisResult::Map(vec![(
+                RedisResult::String(b"key".to_vec()),
+                RedisResult::Array(vec![RedisResult::Integer(1)])
+            )]))),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn can_parse_booleans() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"#t\r\n#f\r\n");
+        assert_eq!(Ok(Some(RedisResult::Boolean(true))), parser.get_response());
+        assert_eq!(Ok(Some(RedisResult::Boolean(false))), parser.get_response());
+    }
+
+    #[test]
+    fn booleans_other_than_t_and_f_are_an_error() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"#x\r\n");
+        assert_eq!(Err(ParseError::InvalidBoolean), parser.get_response());
+    }
+
+    #[test]
+    fn verbatim_string_format_is_separated_from_the_data() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"=15\r\ntxt:Some string\r\n");
+        assert_eq!(
+            Ok(Some(RedisResult::VerbatimString {
+                format: *b"txt",
+                data: b"Some string".to_vec(),
+            })),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn verbatim_string_can_be_resumed_across_feeds() {
+        let mut parser = ResponseParser::new();
+        for chunk in &["=1", "5\r", "\nmk", "d:Some", " string\r", "\n"] {
+            assert_eq!(Ok(None), parser.get_response());
+            parser.feed(chunk.as_bytes());
+        }
+        assert_eq!(
+            Ok(Some(RedisResult::VerbatimString {
+                format: *b"mkd",
+                data: b"Some string".to_vec(),
+            })),
+            parser.get_response()
+        );
+    }
+
+    #[test]
+    fn verbatim_string_without_a_format_is_an_error() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"=5\r\nhello\r\n");
+        assert_eq!(
+            Err(ParseError::InvalidVerbatimStringFormat),
+            parser.get_response()
+        );
+
+        let mut parser = ResponseParser::new();
+        parser.feed(b"=2\r\nhi\r\n");
+        assert_eq!(
+            Err(ParseError::InvalidBulkStringLength(2)),
+            parser.get_response()
+        );
+    }
+
     #[quickcheck]
     fn qc_can_parse_any_bulk_string(text: String) {
         let mut parser = ResponseParser::new();
@@ -470,21 +1465,119 @@ mod tests {
                 .as_bytes(),
         );
         assert_eq!(
-            Ok(Some(RedisResult::Array(vec![
-                RedisResult::Array(vec![
-                    RedisResult::Integer(1),
-                    RedisResult::Integer(2),
-                    RedisResult::Integer(3),
-                ]),
-                RedisResult::Array(vec![
-                    RedisResult::String(b"Foo".to_vec()),
-                    RedisResult::Error(RedisErrorValue::new("Bar")),
-                ])
+            Ok(Some(redis_value!([
+                [1, 2, 3],
+                ["Foo", RedisErrorValue::new("Bar")]
             ]))),
             parser.get_response()
         );
     }
 
+    #[test]
+    fn attributes_are_captured_separately_from_the_value_they_describe() {
+        let reply = b"|1\r\n+key-popularity\r\n%1\r\n$1\r\na\r\n,0.5\r\n:42\r\n";
+        let attributes = vec![(
+            redis_value!("key-popularity"),
+            RedisResult::Map(vec![(redis_value!("a"), redis_value!(0.5))]),
+        )];
+
+        let mut parser = ResponseParser::new();
+        parser.feed(reply);
+        assert_eq!(Ok(Some(redis_value!(42))), parser.get_response());
+        assert_eq!(attributes, parser.take_attributes());
+
+        // the attribute can arrive in pieces, like any other reply
+        let mut parser = ResponseParser::new();
+        let mut responses = Vec::new();
+        for byte in reply.iter() {
+            parser.feed(&[*byte]);
+            responses.extend(parser.get_response().unwrap());
+        }
+        assert_eq!(vec![redis_value!(42)], responses);
+        assert_eq!(attributes, parser.take_attributes());
+    }
+
+    #[test]
+    fn attributes_can_describe_array_elements() {
+        let mut parser = ResponseParser::new();
+        parser.feed(b"*2\r\n:1\r\n|1\r\n+ttl\r\n:3600\r\n:2\r\n|0\r\n:3\r\n");
+
+        assert_eq!(Ok(Some(redis_value!([1, 2]))), parser.get_response());
+        assert_eq!(
+            vec![(redis_value!("ttl"), redis_value!(3600))],
+            parser.take_attributes()
+        );
+        assert_eq!(Ok(Some(redis_value!(3))), parser.get_response());
+        assert_eq!(
+            Vec::<(RedisResult, RedisResult)>::new(),
+            parser.take_attributes()
+        );
+    }
+
+    #[test]
+    fn large_arrays_can_be_streamed_an_element_at_a_time() {
+        let total = 10_000;
+        let mut reply = format!("*{}\r\n", total).into_bytes();
+        for index in 0..total {
+            reply.extend(encode_result(&RedisResult::Integer(index as i64)));
+        }
+
+        let mut parser = StreamingResponseParser::new();
+        let mut streamed = Vec::new();
+        let (first_half, second_half) = reply.split_at(reply.len() / 2);
+        for half in &[first_half, second_half] {
+            for chunk in half.chunks(7) {
+                parser.feed(chunk);
+                while let Some(reply) = parser.get_next().unwrap() {
+                    streamed.push(reply);
+                }
+            }
+            // elements come out as soon as they're complete, not once the whole array is in
+            assert!(!streamed.is_empty());
+        }
+
+        assert_eq!(total, streamed.len());
+        for (index, reply) in streamed.into_iter().enumerate() {
+            assert_eq!(
+                StreamedReply::Element {
+                    index,
+                    total,
+                    element: RedisResult::Integer(index as i64),
+                },
+                reply
+            );
+        }
+    }
+
+    #[test]
+    fn only_top_level_non_empty_arrays_are_streamed() {
+        let mut parser = StreamingResponseParser::new();
+        parser.feed(b"*0\r\n:1\r\n*2\r\n*1\r\n:2\r\n$-1\r\n:3\r\n");
+
+        let mut streamed = Vec::new();
+        while let Some(reply) = parser.get_next().unwrap() {
+            streamed.push(reply);
+        }
+        assert_eq!(
+            vec![
+                StreamedReply::Whole(redis_value!([])),
+                StreamedReply::Whole(redis_value!(1)),
+                StreamedReply::Element {
+                    index: 0,
+                    total: 2,
+                    element: redis_value!([2]),
+                },
+                StreamedReply::Element {
+                    index: 1,
+                    total: 2,
+                    element: redis_value!(null),
+                },
+                StreamedReply::Whole(redis_value!(3)),
+            ],
+            streamed
+        );
+    }
+
     #[test]
     fn can_parse_an_empty_array() {
         let mut parser = ResponseParser::new();
@@ -553,6 +1646,38 @@ mod tests {
         );
     }
 
+    #[quickcheck]
+    fn qc_can_parse_any_map_of_integers(pairs: Vec<(i64, i64)>) {
+        let mut text = String::new();
+        text.push_str(&format!("%{}\r\n", pairs.len()));
+        for (key, value) in &pairs {
+            text.push_str(&format!(":{}\r\n:{}\r\n", key, value));
+        }
+
+        let redis_map = pairs
+            .iter()
+            .map(|(key, value)| (RedisResult::Integer(*key), RedisResult::Integer(*value)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![Ok(Some(RedisResult::Map(redis_map)))],
+            parse_byte_by_byte(text.as_bytes())
+        );
+    }
+
+    #[quickcheck]
+    fn qc_can_parse_any_double(double: f64) -> TestResult {
+        // NaN never compares equal to itself
+        if double.is_nan() {
+            return TestResult::discard();
+        }
+
+        let text = format!(",{}\r\n", double);
+        TestResult::from_bool(
+            vec![Ok(Some(RedisResult::Double(double)))] == parse_byte_by_byte(text.as_bytes()),
+        )
+    }
+
     #[quickcheck]
     fn qc_can_attempt_to_parse_anything_without_panicking(input: String) {
         let mut parser = ResponseParser::new();
@@ -689,4 +1814,125 @@ mod tests {
             parser.get_response().unwrap()
         )
     }
+
+    #[derive(Debug, Clone)]
+    struct NestedValue(RedisValue);
+
+    fn arbitrary_value<G: Gen>(g: &mut G, depth: usize) -> RedisValue {
+        let kinds = if depth == 0 { 2 } else { 3 };
+        match g.gen_range(0, kinds) {
+            0 => RedisValue::String(Vec::arbitrary(g)),
+            1 => RedisValue::Integer(i64::arbitrary(g)),
+            _ => {
+                let len = g.gen_range(0, 5);
+                RedisValue::Array(
+                    (0..len)
+                        .map(|_| {
+                            if g.gen_range(0, 5) == 0 {
+                                None
+                            } else {
+                                Some(arbitrary_value(g, depth - 1))
+                            }
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    impl Arbitrary for NestedValue {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            NestedValue(arbitrary_value(g, 3))
+        }
+    }
+
+    #[quickcheck]
+    fn qc_encoded_values_parse_back_to_the_same_value(value: NestedValue) {
+        let mut parser = ResponseParser::new();
+        parser.feed(&encode_value(&value.0));
+
+        let parsed: Option<RedisValue> =
+            parser.get_response().unwrap().unwrap().try_into().unwrap();
+        assert_eq!(Some(value.0), parsed);
+    }
+
+    fn arbitrary_line<G: Gen>(g: &mut G) -> String {
+        String::arbitrary(g).replace(['\r', '\n'], "")
+    }
+
+    fn arbitrary_result<G: Gen>(g: &mut G, depth: usize) -> RedisResult {
+        let kinds = if depth == 0 { 8 } else { 12 };
+        match g.gen_range(0, kinds) {
+            0 => RedisResult::String(Vec::arbitrary(g)),
+            1 => RedisResult::Integer(i64::arbitrary(g)),
+            2 => RedisResult::Error(RedisErrorValue::new(arbitrary_line(g))),
+            3 => RedisResult::Null,
+            4 => {
+                let mut format = [0; 3];
+                g.fill(&mut format);
+                RedisResult::VerbatimString {
+                    format,
+                    data: Vec::arbitrary(g),
+                }
+            }
+            // NaN is left out, as it never compares equal to itself
+            5 => RedisResult::Double(match g.gen_range(0, 10) {
+                0 => f64::INFINITY,
+                1 => f64::NEG_INFINITY,
+                _ => f64::arbitrary(g),
+            }),
+            6 => RedisResult::Boolean(bool::arbitrary(g)),
+            7 => RedisResult::BigNumber(i128::arbitrary(g).to_string()),
+            8 => RedisResult::Array(
+                (0..g.gen_range(0, 5))
+                    .map(|_| arbitrary_result(g, depth - 1))
+                    .collect(),
+            ),
+            9 => RedisResult::Push(
+                (0..g.gen_range(0, 5))
+                    .map(|_| arbitrary_result(g, depth - 1))
+                    .collect(),
+            ),
+            10 => RedisResult::Set(
+                (0..g.gen_range(0, 5))
+                    .map(|_| arbitrary_result(g, depth - 1))
+                    .collect(),
+            ),
+            _ => RedisResult::Map(
+                (0..g.gen_range(0, 3))
+                    .map(|_| {
+                        (
+                            arbitrary_result(g, depth - 1),
+                            arbitrary_result(g, depth - 1),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    impl Arbitrary for RedisResult {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            arbitrary_result(g, 3)
+        }
+    }
+
+    #[quickcheck]
+    fn qc_encoded_results_parse_back_byte_by_byte(result: RedisResult) {
+        let parsed = parse_byte_by_byte(&encode_result(&result));
+        assert_eq!(vec![Ok(Some(result))], parsed);
+    }
+
+    #[quickcheck]
+    fn qc_encoded_results_parse_back_all_at_once(results: Vec<RedisResult>) {
+        let encoded: Vec<u8> = results.iter().flat_map(encode_result).collect();
+        let parsed = parse_all_at_once(&encoded);
+        assert_eq!(
+            results
+                .into_iter()
+                .map(|result| Ok(Some(result)))
+                .collect::<Vec<_>>(),
+            parsed
+        );
+    }
 }
@@ -0,0 +1,834 @@
+use crate::commands::pubsub_commands::subscription_command;
+use crate::commands::Invalidation;
+use crate::sync_client::{Client, InvalidationHandler};
+use crate::types::redis_values::ConversionError;
+use crate::{
+    encode_result, RBytes, RedisError, RedisResult, RedisValue, SansIoClient, StructuredCommand,
+};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, VecDeque};
+use std::convert::TryFrom;
+use std::io::{Error as IoError, Result as IoResult};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long to wait between attempts to reconnect a dropped subscription
+const RECONNECT_DELAY: Duration = Duration::from_millis(100);
+
+// How often a full, blocked buffer checks whether its subscription has been dropped
+const BUFFER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Where redis sends client-side caching invalidations when tracking is redirected to a client
+const INVALIDATE_CHANNEL: &[u8] = b"__redis__:invalidate";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Message {
+    // the pattern that the channel matched, for messages received through `psubscribe`
+    pub pattern: Option<Vec<u8>>,
+    pub channel: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+impl Message {
+    // Channel names can be arbitrary bytes, so any invalid UTF-8 is replaced here
+    pub fn channel_str(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.channel)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Frame {
+    // confirmations carry the total number of channels subscribed to after the (un)subscribe
+    Subscribe { count: usize },
+    Unsubscribe { count: usize },
+    // shard channels are counted separately from other channels and patterns
+    ShardSubscribe { count: usize },
+    ShardUnsubscribe { count: usize },
+    Message(Message),
+    // messages on the invalidation channel, which go to the client's invalidation handler
+    Invalidation(Invalidation),
+}
+
+fn subscription_count(result: RedisResult) -> Result<usize, ConversionError> {
+    match result {
+        RedisResult::Integer(count @ 0..=std::i64::MAX) => Ok(count as usize),
+        _ => Err(ConversionError::NoConversionTypeMatch {
+            value: Option::try_from(result)?,
+        }),
+    }
+}
+
+fn bulk_bytes(result: RedisResult) -> Result<Vec<u8>, ConversionError> {
+    Option::try_from(result)?.ok_or(ConversionError::NoConversionTypeMatch { value: None })
+}
+
+// What to do with new messages when a subscription's buffer is full
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BufferPolicy {
+    // stop reading from the connection until there's room, which leaves the messages queued up
+    // on the server instead (where they count towards `client-output-buffer-limit`)
+    Block,
+    // make room by throwing away the oldest buffered message
+    DropOldest,
+}
+
+// Messages that have been read from the connection but not yet handed out by `next_message`
+#[derive(Debug)]
+struct MessageBuffer {
+    state: Mutex<BufferState>,
+    changed: Condvar,
+    capacity: usize,
+    policy: BufferPolicy,
+}
+
+#[derive(Debug)]
+struct BufferState {
+    messages: VecDeque<Message>,
+    // set once nothing more will be added, e.g. because the connection dropped
+    closed: bool,
+}
+
+impl MessageBuffer {
+    // Returns false if the subscription has gone away while waiting for room
+    fn push(buffer: &Arc<MessageBuffer>, message: Message) -> bool {
+        let mut state = buffer.state.lock().unwrap();
+        while state.messages.len() >= buffer.capacity {
+            match buffer.policy {
+                BufferPolicy::DropOldest => {
+                    state.messages.pop_front();
+                }
+                BufferPolicy::Block => {
+                    if Arc::strong_count(buffer) == 1 {
+                        return false;
+                    }
+                    state = buffer
+                        .changed
+                        .wait_timeout(state, BUFFER_POLL_INTERVAL)
+                        .unwrap()
+                        .0;
+                }
+            }
+        }
+        state.messages.push_back(message);
+        buffer.changed.notify_all();
+        true
+    }
+
+    // Blocks until there's a message, or returns `None` once the buffer is closed and empty or
+    // the deadline has passed
+    fn pop_before(&self, deadline: Option<Instant>) -> Option<Message> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(message) = state.messages.pop_front() {
+                self.changed.notify_all();
+                return Some(message);
+            }
+            if state.closed {
+                return None;
+            }
+            state = match deadline {
+                None => self.changed.wait(state).unwrap(),
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining == Duration::from_secs(0) {
+                        return None;
+                    }
+                    self.changed.wait_timeout(state, remaining).unwrap().0
+                }
+            };
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.changed.notify_all();
+    }
+}
+
+// Reads every frame from the connection, keeping the messages in `buffer` and passing the rest
+// (confirmations, errors, and normal replies once the subscription has ended) on to the client.
+// Connection errors are passed on too, after which the thread stops.
+fn pump_messages(
+    mut connection: SansIoClient,
+    client: Sender<IoResult<Vec<u8>>>,
+    buffer: Arc<MessageBuffer>,
+    invalidation_handler: Option<InvalidationHandler>,
+    mut count: usize,
+    mut shard_count: usize,
+) {
+    // the connection is in subscribed mode while either count is above zero.  Confirmations are
+    // followed even when it isn't, as a SUBSCRIBE may be what's about to change that.
+    loop {
+        let frame = match connection.get_frame_before(RawFrame, None) {
+            Ok(frame) => frame,
+            Err(error) => {
+                buffer.close();
+                let error = match error {
+                    RedisError::ConnectionError(error) => error,
+                    other => IoError::other(format!("{:?}", other)),
+                };
+                let _ = client.send(Err(error));
+                return;
+            }
+        };
+
+        let subscribed = count + shard_count > 0;
+        match ReadFrame.convert_redis_result(frame.clone()) {
+            Ok(Frame::Message(message)) if subscribed => {
+                if !MessageBuffer::push(&buffer, message) {
+                    return;
+                }
+                continue;
+            }
+            Ok(Frame::Invalidation(invalidation)) if subscribed => {
+                if let Some(handler) = &invalidation_handler {
+                    handler.invalidated(invalidation);
+                }
+                continue;
+            }
+            Ok(Frame::Subscribe { count: new }) | Ok(Frame::Unsubscribe { count: new }) => {
+                count = new
+            }
+            Ok(Frame::ShardSubscribe { count: new })
+            | Ok(Frame::ShardUnsubscribe { count: new }) => shard_count = new,
+            _ => {}
+        }
+
+        if client.send(Ok(encode_result(&frame))).is_err() {
+            buffer.close();
+            return;
+        }
+    }
+}
+
+// Hands over each frame untouched, for `pump_messages` to sort through
+struct RawFrame;
+
+impl StructuredCommand for RawFrame {
+    type Output = RedisResult;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        Ok(result)
+    }
+}
+
+// Once a connection is subscribed, redis pushes frames to it rather than replying to commands, so
+// this stands in for the command that each frame is a "reply" to.
+struct ReadFrame;
+
+impl StructuredCommand for ReadFrame {
+    type Output = Frame;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            // RESP3 connections send these as push frames, RESP2 connections as plain arrays
+            RedisResult::Array(parts) | RedisResult::Push(parts)
+                if parts.len() == 3 || parts.len() == 4 =>
+            {
+                let mut parts = parts.into_iter();
+                let kind = match parts.next().unwrap() {
+                    RedisResult::String(kind) => kind,
+                    other => {
+                        return Err(ConversionError::NoConversionTypeMatch {
+                            value: Option::try_from(other)?,
+                        })
+                    }
+                };
+                let remaining = parts.len();
+                let mut next = move || parts.next().unwrap();
+
+                match (kind.as_slice(), remaining) {
+                    (b"subscribe", 2) | (b"psubscribe", 2) => {
+                        next();
+                        Ok(Frame::Subscribe {
+                            count: subscription_count(next())?,
+                        })
+                    }
+                    (b"unsubscribe", 2) | (b"punsubscribe", 2) => {
+                        next();
+                        Ok(Frame::Unsubscribe {
+                            count: subscription_count(next())?,
+                        })
+                    }
+                    (b"ssubscribe", 2) => {
+                        next();
+                        Ok(Frame::ShardSubscribe {
+                            count: subscription_count(next())?,
+                        })
+                    }
+                    (b"sunsubscribe", 2) => {
+                        next();
+                        Ok(Frame::ShardUnsubscribe {
+                            count: subscription_count(next())?,
+                        })
+                    }
+                    (b"message", 2) | (b"smessage", 2) => {
+                        let channel = bulk_bytes(next())?;
+                        if channel == INVALIDATE_CHANNEL {
+                            // the payload is the array of keys, rather than a string
+                            return Ok(Frame::Invalidation(Invalidation::try_from(next())?));
+                        }
+                        Ok(Frame::Message(Message {
+                            pattern: None,
+                            channel,
+                            payload: bulk_bytes(next())?,
+                        }))
+                    }
+                    // pattern messages also say which pattern matched, before the channel
+                    (b"pmessage", 3) => Ok(Frame::Message(Message {
+                        pattern: Some(bulk_bytes(next())?),
+                        channel: bulk_bytes(next())?,
+                        payload: bulk_bytes(next())?,
+                    })),
+                    _ => Err(ConversionError::NoConversionTypeMatch {
+                        value: Option::try_from(RedisResult::String(kind))?,
+                    }),
+                }
+            }
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: Option::<RedisValue>::try_from(result)?,
+            }),
+        }
+    }
+}
+
+// A connection that has subscribed to one or more channels.  While subscribed, redis won't accept
+// normal commands on the connection, so the client is held here until it's needed again.
+#[derive(Debug)]
+pub struct Subscription {
+    client: Client,
+    // messages that arrived while waiting for subscription confirmations
+    pending: VecDeque<Message>,
+    subscription_count: usize,
+    shard_subscription_count: usize,
+    // every channel and pattern asked for, so that they can be subscribed to again after a
+    // reconnect
+    channels: BTreeSet<Vec<u8>>,
+    patterns: BTreeSet<Vec<u8>>,
+    shard_channels: BTreeSet<Vec<u8>>,
+    reconnect_attempts: u32,
+    buffer: Option<Arc<MessageBuffer>>,
+}
+
+impl Subscription {
+    pub(crate) fn new(client: Client) -> Self {
+        Self {
+            client,
+            pending: VecDeque::new(),
+            subscription_count: 0,
+            shard_subscription_count: 0,
+            channels: BTreeSet::new(),
+            patterns: BTreeSet::new(),
+            shard_channels: BTreeSet::new(),
+            reconnect_attempts: 0,
+            buffer: None,
+        }
+    }
+
+    // If the connection drops while waiting for messages, tries to reconnect (up to
+    // `max_attempts` times) and subscribe to the same channels again.  Anything published while
+    // the connection was down is lost.
+    pub fn with_reconnect(mut self, max_attempts: u32) -> Self {
+        self.reconnect_attempts = max_attempts;
+        self
+    }
+
+    // Reads messages off the connection in the background as they arrive, holding up to `capacity`
+    // of them until `next_message` is called.  `policy` decides what happens when a slow consumer
+    // lets the buffer fill up.  Without a buffer, messages are only read from the connection as
+    // `next_message` asks for them.
+    pub fn with_buffer(mut self, capacity: usize, policy: BufferPolicy) -> Self {
+        self.start_buffering(capacity, policy);
+        self
+    }
+
+    fn start_buffering(&mut self, capacity: usize, policy: BufferPolicy) {
+        let buffer = Arc::new(MessageBuffer {
+            state: Mutex::new(BufferState {
+                messages: VecDeque::new(),
+                closed: false,
+            }),
+            changed: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+        });
+        let (connection, client) = self.client.intercept_replies();
+        let pump_buffer = buffer.clone();
+        let invalidation_handler = self.client.invalidation_handler().cloned();
+        let (count, shard_count) = (self.subscription_count, self.shard_subscription_count);
+        thread::spawn(move || {
+            pump_messages(
+                connection,
+                client,
+                pump_buffer,
+                invalidation_handler,
+                count,
+                shard_count,
+            )
+        });
+
+        self.buffer = Some(buffer);
+    }
+
+    // Subscribes to all of the given channels with a single SUBSCRIBE command, and waits for
+    // every channel to be confirmed before returning.
+    pub fn subscribe<'a>(
+        &mut self,
+        channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        let channels: Vec<RBytes<'a>> = channels.into_iter().map(Into::into).collect();
+        if channels.is_empty() {
+            return Ok(());
+        }
+
+        self.channels
+            .extend(channels.iter().map(|channel| channel.as_bytes().to_vec()));
+        self.client
+            .send_unchecked(&subscription_command("SUBSCRIBE", &channels))?;
+        self.read_confirmations(channels.len())
+    }
+
+    pub fn unsubscribe<'a>(
+        &mut self,
+        channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        let channels: Vec<RBytes<'a>> = channels.into_iter().map(Into::into).collect();
+        if channels.is_empty() {
+            return Ok(());
+        }
+
+        for channel in &channels {
+            self.channels.remove(channel.as_bytes());
+        }
+        self.client
+            .send_unchecked(&subscription_command("UNSUBSCRIBE", &channels))?;
+        self.read_confirmations(channels.len())
+    }
+
+    pub fn unsubscribe_all(&mut self) -> Result<(), RedisError> {
+        self.channels.clear();
+        self.client
+            .send_unchecked(&subscription_command("UNSUBSCRIBE", &[]))?;
+
+        // redis confirms each channel that was unsubscribed from, or sends a single confirmation
+        // if there were none.  The count includes patterns, which are left subscribed.
+        self.read_confirmations(1)?;
+        while self.subscription_count > self.patterns.len() {
+            self.read_confirmations(1)?;
+        }
+        Ok(())
+    }
+
+    // Subscribes to every channel matching any of the given glob-style patterns, e.g. `news.*`.
+    // Messages received this way have their `pattern` set.
+    pub fn psubscribe<'a>(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        let patterns: Vec<RBytes<'a>> = patterns.into_iter().map(Into::into).collect();
+        if patterns.is_empty() {
+            return Ok(());
+        }
+
+        self.patterns
+            .extend(patterns.iter().map(|pattern| pattern.as_bytes().to_vec()));
+        self.client
+            .send_unchecked(&subscription_command("PSUBSCRIBE", &patterns))?;
+        self.read_confirmations(patterns.len())
+    }
+
+    pub fn punsubscribe<'a>(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        let patterns: Vec<RBytes<'a>> = patterns.into_iter().map(Into::into).collect();
+        if patterns.is_empty() {
+            return Ok(());
+        }
+
+        for pattern in &patterns {
+            self.patterns.remove(pattern.as_bytes());
+        }
+        self.client
+            .send_unchecked(&subscription_command("PUNSUBSCRIBE", &patterns))?;
+        self.read_confirmations(patterns.len())
+    }
+
+    pub fn punsubscribe_all(&mut self) -> Result<(), RedisError> {
+        self.patterns.clear();
+        self.client
+            .send_unchecked(&subscription_command("PUNSUBSCRIBE", &[]))?;
+
+        self.read_confirmations(1)?;
+        while self.subscription_count > self.channels.len() {
+            self.read_confirmations(1)?;
+        }
+        Ok(())
+    }
+
+    // Subscribes to shard channels (redis 7+), which receive messages sent with SPUBLISH rather
+    // than PUBLISH.  In a cluster, every channel given here must hash to the same slot, and the
+    // connection has to be to the node that owns it.
+    pub fn ssubscribe<'a>(
+        &mut self,
+        channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        let channels: Vec<RBytes<'a>> = channels.into_iter().map(Into::into).collect();
+        if channels.is_empty() {
+            return Ok(());
+        }
+
+        self.shard_channels
+            .extend(channels.iter().map(|channel| channel.as_bytes().to_vec()));
+        self.client
+            .send_unchecked(&subscription_command("SSUBSCRIBE", &channels))?;
+        self.read_confirmations(channels.len())
+    }
+
+    pub fn sunsubscribe<'a>(
+        &mut self,
+        channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<(), RedisError> {
+        let channels: Vec<RBytes<'a>> = channels.into_iter().map(Into::into).collect();
+        if channels.is_empty() {
+            return Ok(());
+        }
+
+        for channel in &channels {
+            self.shard_channels.remove(channel.as_bytes());
+        }
+        self.client
+            .send_unchecked(&subscription_command("SUNSUBSCRIBE", &channels))?;
+        self.read_confirmations(channels.len())
+    }
+
+    // The number of channels, patterns and shard channels this connection is subscribed to, as
+    // last reported by redis
+    pub fn subscription_count(&self) -> usize {
+        self.subscription_count + self.shard_subscription_count
+    }
+
+    // Gets the client back so that it can issue normal commands again.  This is only possible
+    // once every channel has been unsubscribed from - otherwise the subscription is returned.
+    #[allow(clippy::result_large_err)]
+    pub fn into_client(self) -> Result<Client, Self> {
+        if self.subscription_count() == 0 {
+            Ok(self.client)
+        } else {
+            Err(self)
+        }
+    }
+
+    fn read_confirmations(&mut self, expected: usize) -> Result<(), RedisError> {
+        let mut confirmations = 0;
+        while confirmations < expected {
+            match self.client.receive_frame(ReadFrame)? {
+                Frame::Subscribe { count } | Frame::Unsubscribe { count } => {
+                    self.subscription_count = count;
+                    confirmations += 1;
+                }
+                Frame::ShardSubscribe { count } | Frame::ShardUnsubscribe { count } => {
+                    self.shard_subscription_count = count;
+                    confirmations += 1;
+                }
+                Frame::Message(message) => self.pending.push_back(message),
+                Frame::Invalidation(invalidation) => self.invalidated(invalidation),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Blocks until the next message arrives on any subscribed channel
+    pub fn next_message(&mut self) -> Result<Message, RedisError> {
+        loop {
+            if let Some(message) = self.next_message_before(None)? {
+                return Ok(message);
+            }
+        }
+    }
+
+    // Like `next_message`, but gives up and returns `None` if no message arrives within `timeout`,
+    // so that the caller can do other work in between messages.  Unlike a command timing out,
+    // this leaves the subscription usable.
+    pub fn next_message_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Message>, RedisError> {
+        self.next_message_before(Some(Instant::now() + timeout))
+    }
+
+    // Blocks until a message arrives on `channel`.  Messages for other channels that arrive in the
+    // meantime are kept, in order, and handed out by later calls to `next_message` (or to this
+    // with their channel) - so they're held in memory until then.
+    pub fn next_message_on<'a>(
+        &mut self,
+        channel: impl Into<RBytes<'a>>,
+    ) -> Result<Message, RedisError> {
+        let channel = channel.into();
+        let waiting = self
+            .pending
+            .iter()
+            .position(|message| message.channel == channel.as_bytes());
+        if let Some(message) = waiting.and_then(|index| self.pending.remove(index)) {
+            return Ok(message);
+        }
+
+        loop {
+            match self.receive_message_before(None)? {
+                Some(message) if message.channel == channel.as_bytes() => return Ok(message),
+                Some(message) => self.pending.push_back(message),
+                None => {}
+            }
+        }
+    }
+
+    fn next_message_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<Message>, RedisError> {
+        match self.pending.pop_front() {
+            Some(message) => Ok(Some(message)),
+            None => self.receive_message_before(deadline),
+        }
+    }
+
+    // Waits for a new message, skipping over any that are already pending
+    fn receive_message_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<Message>, RedisError> {
+        if let Some(buffer) = &self.buffer {
+            if let Some(message) = buffer.pop_before(deadline) {
+                return Ok(Some(message));
+            }
+            // once the buffer is closed, the reason why is waiting on the connection
+            if !buffer.is_closed() {
+                return Ok(None);
+            }
+        }
+
+        loop {
+            // messages can take any amount of time to arrive, so the client's timeout is ignored
+            match self.client.try_receive_frame_before(ReadFrame, deadline) {
+                Ok(None) => return Ok(None),
+                Ok(Some(Frame::Subscribe { count })) | Ok(Some(Frame::Unsubscribe { count })) => {
+                    self.subscription_count = count
+                }
+                Ok(Some(Frame::ShardSubscribe { count }))
+                | Ok(Some(Frame::ShardUnsubscribe { count })) => {
+                    self.shard_subscription_count = count
+                }
+                Ok(Some(Frame::Message(message))) => return Ok(Some(message)),
+                Ok(Some(Frame::Invalidation(invalidation))) => self.invalidated(invalidation),
+                Err(ref error) if error.is_connection_error() && self.reconnect_attempts > 0 => {
+                    self.reconnect()?
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn invalidated(&self, invalidation: Invalidation) {
+        if let Some(handler) = self.client.invalidation_handler() {
+            handler.invalidated(invalidation);
+        }
+    }
+
+    // Rebuilds the subscription on a new connection to the same server and subscribes to the same
+    // channels again.  `next_message` does this itself when `with_reconnect` is set, but otherwise
+    // it can be called after `next_message` fails with a connection error (see
+    // `RedisError::is_connection_error`), and the subscription can then be used as before.
+    // Messages published while disconnected are lost.
+    pub fn reconnect(&mut self) -> Result<(), RedisError> {
+        let mut attempts = 1;
+        loop {
+            match self.resubscribe() {
+                Err(ref error)
+                    if error.is_connection_error() && attempts < self.reconnect_attempts =>
+                {
+                    attempts += 1;
+                    thread::sleep(RECONNECT_DELAY);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn resubscribe(&mut self) -> Result<(), RedisError> {
+        self.client = self.client.connect_again()?;
+        self.subscription_count = 0;
+        self.shard_subscription_count = 0;
+        if let Some(buffer) = self.buffer.take() {
+            // anything already read off the old connection can still be handed out
+            let mut state = buffer.state.lock().unwrap();
+            self.pending.extend(state.messages.drain(..));
+            drop(state);
+            self.start_buffering(buffer.capacity, buffer.policy);
+        }
+        // subscribing adds to these sets, so they're copied out first
+        let channels: Vec<Vec<u8>> = self.channels.iter().cloned().collect();
+        let patterns: Vec<Vec<u8>> = self.patterns.iter().cloned().collect();
+        let shard_channels: Vec<Vec<u8>> = self.shard_channels.iter().cloned().collect();
+        self.subscribe(channels)?;
+        self.psubscribe(patterns)?;
+        self.ssubscribe(shard_channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(parts: Vec<RedisResult>) -> Result<Frame, ConversionError> {
+        ReadFrame.convert_redis_result(RedisResult::Array(parts))
+    }
+
+    #[test]
+    fn subscribe_confirmations_are_parsed() {
+        assert_eq!(
+            Frame::Subscribe { count: 1 },
+            frame(vec![
+                RedisResult::String(b"subscribe".to_vec()),
+                RedisResult::String(b"news".to_vec()),
+                RedisResult::Integer(1),
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn unsubscribe_confirmations_are_parsed_even_without_a_channel() {
+        assert_eq!(
+            Frame::Unsubscribe { count: 0 },
+            frame(vec![
+                RedisResult::String(b"unsubscribe".to_vec()),
+                RedisResult::Null,
+                RedisResult::Integer(0),
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn messages_are_parsed() {
+        assert_eq!(
+            Frame::Message(Message {
+                pattern: None,
+                channel: b"news".to_vec(),
+                payload: b"hello".to_vec(),
+            }),
+            frame(vec![
+                RedisResult::String(b"message".to_vec()),
+                RedisResult::String(b"news".to_vec()),
+                RedisResult::String(b"hello".to_vec()),
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn invalidation_messages_are_parsed_with_their_keys() {
+        assert_eq!(
+            Frame::Invalidation(Invalidation::Keys(vec![b"key".to_vec()])),
+            frame(vec![
+                RedisResult::String(b"message".to_vec()),
+                RedisResult::String(b"__redis__:invalidate".to_vec()),
+                RedisResult::Array(vec![RedisResult::String(b"key".to_vec())]),
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn pattern_messages_are_parsed_with_their_pattern() {
+        assert_eq!(
+            Frame::Message(Message {
+                pattern: Some(b"news.*".to_vec()),
+                channel: b"news.tech".to_vec(),
+                payload: b"hello".to_vec(),
+            }),
+            frame(vec![
+                RedisResult::String(b"pmessage".to_vec()),
+                RedisResult::String(b"news.*".to_vec()),
+                RedisResult::String(b"news.tech".to_vec()),
+                RedisResult::String(b"hello".to_vec()),
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn pattern_confirmations_are_parsed() {
+        assert_eq!(
+            Frame::Subscribe { count: 2 },
+            frame(vec![
+                RedisResult::String(b"psubscribe".to_vec()),
+                RedisResult::String(b"news.*".to_vec()),
+                RedisResult::Integer(2),
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn shard_confirmations_and_messages_are_parsed() {
+        assert_eq!(
+            Frame::ShardSubscribe { count: 1 },
+            frame(vec![
+                RedisResult::String(b"ssubscribe".to_vec()),
+                RedisResult::String(b"news".to_vec()),
+                RedisResult::Integer(1),
+            ])
+            .unwrap()
+        );
+        assert_eq!(
+            Frame::Message(Message {
+                pattern: None,
+                channel: b"news".to_vec(),
+                payload: b"hello".to_vec(),
+            }),
+            frame(vec![
+                RedisResult::String(b"smessage".to_vec()),
+                RedisResult::String(b"news".to_vec()),
+                RedisResult::String(b"hello".to_vec()),
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn unknown_frames_are_an_error() {
+        assert!(frame(vec![
+            RedisResult::String(b"mystery".to_vec()),
+            RedisResult::String(b"news".to_vec()),
+            RedisResult::Integer(1),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn channel_names_are_converted_lossily() {
+        let message = Message {
+            pattern: None,
+            channel: b"news\xFF".to_vec(),
+            payload: Vec::new(),
+        };
+        assert_eq!("news\u{FFFD}", message.channel_str());
+    }
+}
@@ -1,20 +1,165 @@
+use crate::commands::util_commands::Auth;
+use crate::commands::{
+    auth, command_info, get, getdel, hello, hgetall, key_type, lrange, mget, pexpire, ping, pttl,
+    scan, set, smembers, unlink, wait, zrange, CommandInfo, Invalidation, KeyType, TtlResult,
+};
+use crate::lock::{self, LockGuard};
+use crate::pipeline::{Pipeline, PipelineCommands};
+use crate::rate_limit;
 use crate::sans_io::Client as SansIoClient;
-use crate::{Command, RBytes, RedisError, StructuredCommand};
-use std::io::{BufRead, BufReader, BufWriter, Result as IoResult, Write};
-use std::net::{TcpStream, ToSocketAddrs};
+use crate::subscription::Subscription;
+use crate::types::redis_values::ConversionError;
+use crate::{
+    Command, PreparedCommand, RBytes, RedisError, RedisErrorValue, RedisResult, RedisValue,
+    StructuredCommand, ValidationError,
+};
+use std::collections::{HashMap, VecDeque};
+use std::convert::{TryFrom, TryInto};
+use std::io::{
+    BufRead, BufReader, BufWriter, Error as IoError, ErrorKind as IoErrorKind, Read,
+    Result as IoResult, Write,
+};
+use std::mem::replace;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
+
+// How many lines the connection's reader thread will read ahead of the replies being used.  Past
+// that, it stops reading, so a slow consumer pushes back on the server rather than queueing up
+// replies in memory without limit.
+const READ_AHEAD: usize = 1024;
+
+// How much a single-threaded client reads from the connection at a time
+const READ_CHUNK: usize = 8 * 1024;
+
+// How long `issue_with_retry` waits before reconnecting
+const RETRY_DELAY: Duration = Duration::from_millis(50);
+
+// How many keys `delete_matching` scans for, and removes, with each command
+const DELETE_BATCH: usize = 100;
+
+#[derive(Debug)]
+pub enum HealthStatus {
+    // the server replied to a PING, which took this long to round-trip
+    Ok(Duration),
+    Timeout,
+    Error(RedisError),
+}
+
+// A key's value, read by `get_dynamic` according to the key's type
+#[derive(Debug, PartialEq, Clone)]
+pub enum DynamicValue {
+    Missing,
+    String(Vec<u8>),
+    List(Vec<Vec<u8>>),
+    Set(Vec<Vec<u8>>),
+    Hash(Vec<(Vec<u8>, Vec<u8>)>),
+    // ordered by score, lowest first
+    SortedSet(Vec<(Vec<u8>, f64)>),
+    // a type that `get_dynamic` can't read, e.g. a stream
+    Unsupported(KeyType),
+}
 
 #[derive(Debug)]
 pub struct Client {
     writer: BufWriter<TcpStream>,
     parser: SansIoClient,
+    address: SocketAddr,
+    // 2 unless RESP3 was successfully negotiated with HELLO
+    protocol: u8,
+    // kept so that new connections to the same server can log in again
+    credentials: Option<Credentials>,
+    // how long to wait for each reply, unless overridden for a single command
+    timeout: Option<Duration>,
+    // the details of every command checked by `issue_validated` or `require_utf8_keys`, keyed by
+    // lowercase command name
+    arity_cache: HashMap<Vec<u8>, Option<CommandInfo>>,
+    require_utf8_keys: bool,
+    metrics_sink: Option<MetricsSink>,
+    invalidation_handler: Option<InvalidationHandler>,
+    bytes_sent: u64,
+    // bytes received by parsers that have since been replaced, e.g. by reconnecting
+    earlier_bytes_received: u64,
+    // replies are read on the thread waiting for them, rather than by a reader thread
+    single_threaded: bool,
+}
+
+// What `with_auth` or `with_user_auth` logged in with
+struct Credentials {
+    username: Option<Vec<u8>>,
+    password: Vec<u8>,
+}
+
+impl Credentials {
+    fn auth_command(&self) -> Auth<'_> {
+        let command = auth(self.password.as_slice());
+        match &self.username {
+            Some(username) => command.with_username(username.as_slice()),
+            None => command,
+        }
+    }
+}
+
+// The password is left out, so that debug-printing a client doesn't leak it
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+// Wraps the user's metrics callback so that the client can still be debug-printed
+struct MetricsSink(MetricsCallback);
+
+type MetricsCallback = Box<dyn Fn(&str, Duration) + Send>;
+
+impl std::fmt::Debug for MetricsSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MetricsSink")
+    }
+}
+
+// Shared between every parser that reads from the connection, so that it survives reconnects and
+// subscriptions
+#[derive(Clone)]
+pub(crate) struct InvalidationHandler(Arc<dyn Fn(Invalidation) + Send + Sync>);
+
+impl InvalidationHandler {
+    pub(crate) fn invalidated(&self, invalidation: Invalidation) {
+        (self.0)(invalidation)
+    }
+
+    // RESP3 connections are sent invalidations as `["invalidate", keys]` push frames
+    fn install(&self, parser: &mut SansIoClient) {
+        let handler = self.clone();
+        parser.set_push_handler(move |push| {
+            let mut parts = push.into_iter();
+            if let (Some(RedisResult::String(kind)), Some(keys)) = (parts.next(), parts.next()) {
+                if kind == b"invalidate" {
+                    if let Ok(invalidation) = Invalidation::try_from(keys) {
+                        handler.invalidated(invalidation);
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl std::fmt::Debug for InvalidationHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("InvalidationHandler")
+    }
 }
 
 impl Client {
     pub fn new(address: impl ToSocketAddrs) -> IoResult<Self> {
         let stream = TcpStream::connect(address)?;
+        let address = stream.peer_addr()?;
         let writer = BufWriter::new(stream.try_clone()?);
-        let (parser, tx_bytes) = SansIoClient::new();
+        let (parser, tx_bytes) = SansIoClient::with_bounded_channel(READ_AHEAD);
 
         thread::spawn(move || {
             let mut reader = BufReader::new(stream);
@@ -22,6 +167,12 @@ impl Client {
 
             loop {
                 match reader.read_until(b'\n', &mut buffer) {
+                    Ok(0) => {
+                        // the server has closed the connection, so no more replies will arrive
+                        let closed = IoError::new(IoErrorKind::UnexpectedEof, "connection closed");
+                        let _ = tx_bytes.send(Err(closed));
+                        break;
+                    }
                     Ok(_) => {
                         let result = tx_bytes.send(Ok(buffer.clone().into()));
                         if result.is_err() {
@@ -40,7 +191,110 @@ impl Client {
             //   Or just generally figure out cleanup
         });
 
-        Ok(Self { parser, writer })
+        Ok(Self::from_parts(writer, parser, address, false))
+    }
+
+    // Reads each reply on the thread that is waiting for it, rather than having a background
+    // thread read ahead.  This saves passing every reply between threads, which lowers latency for
+    // simple one-command-at-a-time use.  Nothing is read while no command is waiting, though, so
+    // e.g. a subscription made from this client still uses a thread to keep up with messages.
+    pub fn new_single_threaded(address: impl ToSocketAddrs) -> IoResult<Self> {
+        let mut reader = TcpStream::connect(address)?;
+        let address = reader.peer_addr()?;
+        let writer = BufWriter::new(reader.try_clone()?);
+
+        let mut chunk = vec![0; READ_CHUNK];
+        let parser = SansIoClient::with_pull(move |timeout| {
+            reader.set_read_timeout(timeout)?;
+            match reader.read(&mut chunk) {
+                Ok(0) => Err(IoError::new(
+                    IoErrorKind::UnexpectedEof,
+                    "connection closed",
+                )),
+                Ok(read) => Ok(Some(chunk[..read].to_vec())),
+                Err(ref error)
+                    if matches!(
+                        error.kind(),
+                        IoErrorKind::WouldBlock | IoErrorKind::TimedOut
+                    ) =>
+                {
+                    Ok(None)
+                }
+                Err(error) => Err(error),
+            }
+        });
+
+        Ok(Self::from_parts(writer, parser, address, true))
+    }
+
+    fn from_parts(
+        writer: BufWriter<TcpStream>,
+        parser: SansIoClient,
+        address: SocketAddr,
+        single_threaded: bool,
+    ) -> Self {
+        Self {
+            parser,
+            writer,
+            address,
+            protocol: 2,
+            credentials: None,
+            timeout: None,
+            arity_cache: HashMap::new(),
+            require_utf8_keys: false,
+            metrics_sink: None,
+            invalidation_handler: None,
+            bytes_sent: 0,
+            earlier_bytes_received: 0,
+            single_threaded,
+        }
+    }
+
+    // Opens a new connection to the same server, in the same threading mode and with the same
+    // invalidation handler.  The new connection logs in with the same credentials, and switches
+    // to RESP3 if this one did.
+    pub(crate) fn connect_again(&self) -> Result<Self, RedisError> {
+        let mut client = if self.single_threaded {
+            Self::new_single_threaded(self.address)
+        } else {
+            Self::new(self.address)
+        }
+        .map_err(RedisError::ConnectionError)?;
+        if let Some(handler) = &self.invalidation_handler {
+            handler.install(&mut client.parser);
+            client.invalidation_handler = Some(handler.clone());
+        }
+        if let Some(credentials) = &self.credentials {
+            client.issue(credentials.auth_command())?;
+        }
+        if self.protocol == 3 {
+            client.issue(hello(3))?;
+            client.protocol = 3;
+        }
+        Ok(client)
+    }
+
+    // Asks the server to use RESP3.  Servers older than redis 6 don't understand HELLO, in which
+    // case the connection quietly stays on RESP2 - check `protocol_version` to see which was used.
+    pub fn new_resp3(address: impl ToSocketAddrs) -> Result<Self, RedisError> {
+        let mut client = Self::new(address).map_err(RedisError::ConnectionError)?;
+        match client.issue(hello(3)) {
+            Ok(()) => client.protocol = 3,
+            Err(RedisError::RedisReturnedError(ref error)) if is_unsupported_hello(error) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    address = %client.address,
+                    ?error,
+                    "server does not support RESP3, falling back to RESP2"
+                );
+            }
+            Err(error) => return Err(error),
+        }
+        Ok(client)
+    }
+
+    pub fn protocol_version(&self) -> u8 {
+        self.protocol
     }
 
     pub fn with_auth<'a>(
@@ -48,7 +302,28 @@ impl Client {
         pass: impl Into<RBytes<'a>>,
     ) -> Result<Self, RedisError> {
         let mut client = Self::new(address).map_err(RedisError::ConnectionError)?;
-        client.issue(Command::cmd("AUTH").with_arg(pass))?;
+        let credentials = Credentials {
+            username: None,
+            password: pass.into().as_bytes().to_vec(),
+        };
+        client.issue(credentials.auth_command())?;
+        client.credentials = Some(credentials);
+        Ok(client)
+    }
+
+    // Logs in as a named ACL user, rather than the default user that `with_auth` uses
+    pub fn with_user_auth<'a>(
+        address: impl ToSocketAddrs,
+        username: impl Into<RBytes<'a>>,
+        pass: impl Into<RBytes<'a>>,
+    ) -> Result<Self, RedisError> {
+        let mut client = Self::new(address).map_err(RedisError::ConnectionError)?;
+        let credentials = Credentials {
+            username: Some(username.into().as_bytes().to_vec()),
+            password: pass.into().as_bytes().to_vec(),
+        };
+        client.issue(credentials.auth_command())?;
+        client.credentials = Some(credentials);
         Ok(client)
     }
 
@@ -56,11 +331,864 @@ impl Client {
     where
         Cmd: StructuredCommand,
     {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        self.issue_before(cmd, deadline)
+    }
+
+    // Issues a command that was encoded ahead of time, which saves encoding it again on every call
+    pub fn issue_prepared<T>(&mut self, prepared: &PreparedCommand<T>) -> Result<T, RedisError> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        self.issue_encoded(prepared.bytes(), Prepared(prepared), deadline)
+    }
+
+    // Calls `sink` after every command issued with `issue` or `issue_with_timeout`, with the
+    // command's name (e.g. "GET") and how long it took to get a reply.  Failed commands are
+    // reported too.
+    pub fn set_metrics_sink(&mut self, sink: impl Fn(&str, Duration) + Send + 'static) {
+        self.metrics_sink = Some(MetricsSink(Box::new(sink)));
+    }
+
+    // Calls `handler` with every invalidation the server sends once `client_tracking` is turned
+    // on.  On a RESP3 connection, these arrive mixed in with replies, so they're only noticed while
+    // waiting for a reply - issue a command (even just a PING) to pick up any that are waiting.
+    // With tracking redirected, set the handler on the other client before it subscribes to
+    // `__redis__:invalidate`, and the invalidations will be read along with its messages.
+    pub fn set_invalidation_handler(
+        &mut self,
+        handler: impl Fn(Invalidation) + Send + Sync + 'static,
+    ) {
+        let handler = InvalidationHandler(Arc::new(handler));
+        handler.install(&mut self.parser);
+        self.invalidation_handler = Some(handler);
+    }
+
+    pub(crate) fn invalidation_handler(&self) -> Option<&InvalidationHandler> {
+        self.invalidation_handler.as_ref()
+    }
+
+    // The total number of bytes written to the server by this client, including any commands
+    // sent again after reconnecting
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    // The total number of bytes read from the server by this client
+    pub fn bytes_received(&self) -> u64 {
+        self.earlier_bytes_received + self.parser.bytes_received()
+    }
+
+    // Sends several commands in a single write, e.g. `client.pipeline((incr("a"), get("b")))`,
+    // and reads back a reply for each of them
+    pub fn pipeline<C: PipelineCommands>(&mut self, commands: C) -> Pipeline<'_, C> {
+        Pipeline::new(self, commands)
+    }
+
+    // Issues a command, and if the connection fails before the reply arrives, reconnects and sends
+    // it again, up to `max_attempts` times in total.  The command may have run before the
+    // connection dropped, so this should only be used for commands that are safe to repeat.
+    pub fn issue_with_retry<Cmd>(
+        &mut self,
+        cmd: Cmd,
+        max_attempts: u32,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        // converting the reply consumes the command, so the bytes are kept to send again, and
+        // the reply is only converted once one has actually arrived
         let bytes = self.parser.issue_command(&cmd);
+        let mut attempts = 1;
+        let reply = loop {
+            let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+            match self.issue_encoded(&bytes, RawReply, deadline) {
+                Err(ref error) if error.is_connection_error() && attempts < max_attempts => {
+                    attempts += 1;
+                    thread::sleep(RETRY_DELAY);
+                    // a failed reconnection is just another failed attempt
+                    let _ = self.reconnect();
+                }
+                result => break result?,
+            }
+        };
+
+        cmd.convert_redis_result(reply)
+            .map_err(RedisError::from_conversion_error)
+    }
+
+    // Replaces the connection with a new one to the same server, keeping the client's settings
+    fn reconnect(&mut self) -> Result<(), RedisError> {
+        #[cfg(feature = "tracing")]
+        tracing::info!(address = %self.address, "reconnecting to redis");
+        let fresh = self.connect_again()?;
+        self.writer = fresh.writer;
+        self.bytes_sent += fresh.bytes_sent;
+        self.earlier_bytes_received += self.parser.bytes_received();
+        self.parser = fresh.parser;
+        Ok(())
+    }
+
+    // Sets how long every command waits for its reply before failing with `RedisError::Timeout`.
+    // After a timeout the connection can't be used any more, because the late reply would be read
+    // as the reply to the next command.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    // Issues a single command with a different timeout to the client's default, e.g. for a
+    // command that is known to be slow
+    pub fn issue_with_timeout<Cmd>(
+        &mut self,
+        cmd: Cmd,
+        timeout: Duration,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        self.issue_before(cmd, Some(Instant::now() + timeout))
+    }
+
+    fn issue_before<Cmd>(
+        &mut self,
+        cmd: Cmd,
+        deadline: Option<Instant>,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        let bytes = self.parser.issue_command(&cmd);
+        self.issue_encoded(&bytes, cmd, deadline)
+    }
+
+    // Sends a command that has already been encoded, then converts the reply with `cmd`
+    fn issue_encoded<Cmd>(
+        &mut self,
+        bytes: &[u8],
+        cmd: Cmd,
+        deadline: Option<Instant>,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        self.round_trip(bytes, |client| client.receive_before(cmd, deadline))
+    }
+
+    // Sends one or more encoded commands, then reads their replies with `receive`, inside a
+    // tracing span and with the time taken reported to the metrics sink.  Several commands sent
+    // together are reported under the first one's name.
+    pub(crate) fn round_trip<T>(
+        &mut self,
+        bytes: &[u8],
+        receive: impl FnOnce(&mut Self) -> Result<T, RedisError>,
+    ) -> Result<T, RedisError> {
+        let start = Instant::now();
+        #[cfg(feature = "tracing")]
+        let (span, received_before) = (
+            tracing::info_span!(
+                "redis_command",
+                command = %command_verb(bytes),
+                latency_us = tracing::field::Empty,
+                reply_bytes = tracing::field::Empty,
+            ),
+            self.bytes_received(),
+        );
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+
+        let result = self.send(bytes).and_then(|()| receive(self));
+
+        #[cfg(feature = "tracing")]
+        {
+            span.record("latency_us", start.elapsed().as_micros() as u64);
+            span.record("reply_bytes", self.bytes_received() - received_before);
+            if let Err(error) = &result {
+                tracing::warn!(?error, "redis command failed");
+            }
+        }
+        if let Some(MetricsSink(sink)) = &self.metrics_sink {
+            sink(&command_verb(bytes), start.elapsed());
+        }
+        result
+    }
+
+    // PINGs the server, for liveness probes and the like.  As with any other timeout, the client
+    // can't be used again after a `HealthStatus::Timeout`.
+    pub fn health_check(&mut self, timeout: Duration) -> HealthStatus {
+        let start = Instant::now();
+        match self.issue_with_timeout(ping(), timeout) {
+            Ok(()) => HealthStatus::Ok(start.elapsed()),
+            Err(RedisError::Timeout) => HealthStatus::Timeout,
+            Err(error) => HealthStatus::Error(error),
+        }
+    }
+
+    // Checks the number of arguments against the arity that redis reports for the command, so
+    // that mistakes in hand-built commands are caught before they're sent.  The arity is fetched
+    // with COMMAND INFO the first time each command is used, and cached after that.  Commands
+    // that redis doesn't know about are sent unchecked.
+    pub fn issue_validated(&mut self, cmd: Command) -> Result<Option<RedisValue>, RedisError> {
+        if let Some(info) = self.cached_command_info(cmd.name().as_bytes())? {
+            if !info.accepts(cmd.args().len()) {
+                return Err(RedisError::ValidationError(ValidationError::WrongArity {
+                    command: info.name.clone(),
+                    arity: info.arity,
+                    args: cmd.args().len(),
+                }));
+            }
+        }
+
+        self.issue(cmd)
+    }
+
+    fn cached_command_info(&mut self, name: &[u8]) -> Result<Option<CommandInfo>, RedisError> {
+        let name = name.to_ascii_lowercase();
+        if !self.arity_cache.contains_key(&name) {
+            // sent unchecked, as checking COMMAND's own keys would need its info in turn
+            let cmd = command_info(name.as_slice());
+            self.send_unchecked(&cmd.get_bytes())?;
+            let info = self.receive(cmd)?;
+            self.arity_cache.insert(name.clone(), info);
+        }
+        Ok(self.arity_cache[&name].clone())
+    }
+
+    // When set, every command's keys are checked to be valid UTF-8 before it's sent, and commands
+    // with any other keys fail with `ValidationError::NonUtf8Key`.  Values aren't checked.  The
+    // keys are found from COMMAND INFO (fetched once per command, as with `issue_validated`), so
+    // commands redis doesn't know about, and commands like EVAL whose keys can't be found that
+    // way, are sent unchecked.
+    pub fn require_utf8_keys(&mut self, required: bool) {
+        self.require_utf8_keys = required;
+    }
+
+    fn check_utf8_keys(&mut self, bytes: &[u8]) -> Result<(), RedisError> {
+        for arguments in encoded_commands(bytes) {
+            let (name, arguments) = match arguments.split_first() {
+                Some(split) => split,
+                None => continue,
+            };
+
+            if let Some(info) = self.cached_command_info(name)? {
+                for position in info.key_positions(arguments.len()) {
+                    let key = arguments[position];
+                    if std::str::from_utf8(key).is_err() {
+                        return Err(RedisError::ValidationError(ValidationError::NonUtf8Key {
+                            key: key.to_vec(),
+                        }));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Puts a new reply channel in place, and hands back the parser that reads from the connection,
+    // so that something else can sit between the connection and this client and decide which
+    // replies to pass on
+    pub(crate) fn intercept_replies(&mut self) -> (SansIoClient, Sender<IoResult<Vec<u8>>>) {
+        let (mut parser, tx_bytes) = SansIoClient::new();
+        if let Some(handler) = &self.invalidation_handler {
+            handler.install(&mut parser);
+        }
+        self.earlier_bytes_received += self.parser.bytes_received();
+        (replace(&mut self.parser, parser), tx_bytes)
+    }
+
+    // Every command goes through here, so that the keys of each one are checked when
+    // `require_utf8_keys` is set
+    pub(crate) fn send(&mut self, bytes: &[u8]) -> Result<(), RedisError> {
+        if self.require_utf8_keys {
+            self.check_utf8_keys(bytes)?;
+        }
+        self.send_unchecked(bytes)
+    }
+
+    // For the commands that the key check can't be run on: COMMAND INFO, which the check itself
+    // uses, and subscription commands, which have no keys and can't be followed by COMMAND INFO
+    // once the connection is subscribed
+    pub(crate) fn send_unchecked(&mut self, bytes: &[u8]) -> Result<(), RedisError> {
+        // there's no point sending anything once the replies can't be matched up to commands
+        if self.parser.is_poisoned() {
+            return Err(RedisError::ConnectionDesynchronized);
+        }
+
         self.writer
-            .write(&bytes)
+            .write_all(bytes)
             .map_err(RedisError::ConnectionError)?;
         self.writer.flush().map_err(RedisError::ConnectionError)?;
-        self.parser.get_response(cmd)
+        self.bytes_sent += bytes.len() as u64;
+        Ok(())
+    }
+
+    pub(crate) fn receive<Cmd>(
+        &mut self,
+        cmd: Cmd,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        self.receive_before(cmd, deadline)
+    }
+
+    pub(crate) fn receive_before<Cmd>(
+        &mut self,
+        cmd: Cmd,
+        deadline: Option<Instant>,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        self.parser.get_response_before(cmd, deadline)
+    }
+
+    // Like `receive`, but RESP3 push frames are returned instead of being skipped
+    pub(crate) fn receive_frame<Cmd>(
+        &mut self,
+        cmd: Cmd,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        self.receive_frame_before(cmd, deadline)
+    }
+
+    // Like `receive_before`, but RESP3 push frames are returned instead of being skipped
+    pub(crate) fn receive_frame_before<Cmd>(
+        &mut self,
+        cmd: Cmd,
+        deadline: Option<Instant>,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        self.parser.get_frame_before(cmd, deadline)
+    }
+
+    // Like `receive_frame_before`, but returns `None` at the deadline instead of poisoning the
+    // connection - see `SansIoClient::try_get_frame_before`
+    pub(crate) fn try_receive_frame_before<Cmd>(
+        &mut self,
+        cmd: Cmd,
+        deadline: Option<Instant>,
+    ) -> Result<Option<<Cmd as StructuredCommand>::Output>, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        self.parser.try_get_frame_before(cmd, deadline)
+    }
+
+    // Puts the connection into subscribed mode.  No other commands can be issued until the client
+    // is taken back out of the subscription.
+    pub fn subscribe<'a>(
+        self,
+        channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<Subscription, RedisError> {
+        let mut subscription = Subscription::new(self);
+        subscription.subscribe(channels)?;
+        Ok(subscription)
+    }
+
+    // Like `subscribe`, but for shard channels - see `Subscription::ssubscribe`
+    pub fn ssubscribe<'a>(
+        self,
+        channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<Subscription, RedisError> {
+        let mut subscription = Subscription::new(self);
+        subscription.ssubscribe(channels)?;
+        Ok(subscription)
+    }
+
+    // Like `subscribe`, but for every channel matching any of the given patterns
+    pub fn psubscribe<'a>(
+        self,
+        patterns: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+    ) -> Result<Subscription, RedisError> {
+        let mut subscription = Subscription::new(self);
+        subscription.psubscribe(patterns)?;
+        Ok(subscription)
+    }
+
+    // Issues a write, then blocks until at least `numreplicas` replicas have acknowledged it.  If
+    // they don't within `timeout`, this fails with `InsufficientReplicas` - although note that the
+    // write itself has still happened, and may yet reach the replicas later.
+    pub fn issue_durable<Cmd>(
+        &mut self,
+        cmd: Cmd,
+        numreplicas: u32,
+        timeout: Duration,
+    ) -> Result<<Cmd as StructuredCommand>::Output, RedisError>
+    where
+        Cmd: StructuredCommand,
+    {
+        let wait_command = wait(numreplicas, timeout);
+
+        let mut bytes = self.parser.issue_command(&cmd);
+        bytes.extend(self.parser.issue_command(&wait_command));
+        let (value, acknowledged) = self.round_trip(&bytes, |client| {
+            // as with `get_with_ttl`, the WAIT reply must be read even if the command itself
+            // failed
+            let value = client.receive(cmd);
+            Ok((value, client.receive(wait_command)?))
+        })?;
+        let value = value?;
+
+        if acknowledged < numreplicas {
+            return Err(RedisError::InsufficientReplicas {
+                acknowledged,
+                requested: numreplicas,
+            });
+        }
+
+        Ok(value)
+    }
+
+    pub fn acquire_lock<'a>(
+        &mut self,
+        key: impl Into<RBytes<'a>>,
+        ttl: Duration,
+    ) -> Result<Option<LockGuard>, RedisError> {
+        self.issue(lock::acquire(key, ttl).map_err(RedisError::ValidationError)?)
+    }
+
+    // Expires a key (almost) immediately, which is useful in tests to avoid having to sleep until
+    // a real expiry has passed.  Returns whether the key existed.
+    pub fn expire_now<'a>(&mut self, key: impl Into<RBytes<'a>>) -> Result<bool, RedisError> {
+        self.issue(pexpire(key, Duration::from_millis(1)).map_err(RedisError::ValidationError)?)
+    }
+
+    // Writes a key and (re)sets its time-to-live in one command, so that keys that are written
+    // regularly (e.g. sessions) only expire once they've been left alone for `ttl`
+    pub fn set_with_refresh<'a>(
+        &mut self,
+        key: impl Into<RBytes<'a>>,
+        value: impl Into<RBytes<'a>>,
+        ttl: Duration,
+    ) -> Result<(), RedisError> {
+        self.issue(
+            set(key, value)
+                .with_expiry(ttl)
+                .map_err(RedisError::ValidationError)?,
+        )
+    }
+
+    // The read-side counterpart to `set_with_refresh`, which pushes back a key's expiry without
+    // touching its value.  Returns whether the key existed.  As with `pexpire`, a `ttl` under a
+    // millisecond is rejected rather than deleting the key.
+    pub fn touch_ttl<'a>(
+        &mut self,
+        key: impl Into<RBytes<'a>>,
+        ttl: Duration,
+    ) -> Result<bool, RedisError> {
+        self.issue(pexpire(key, ttl).map_err(RedisError::ValidationError)?)
+    }
+
+    // Reads a key, filling it in from `compute` first if it's missing.  If another client sets the
+    // key between our GET and SET, their value wins and is returned instead, so every caller sees
+    // the same value.
+    pub fn get_or_set_with<'a, T, V>(
+        &mut self,
+        key: impl Into<RBytes<'a>>,
+        ttl: Duration,
+        compute: impl FnOnce() -> V,
+    ) -> Result<T, RedisError>
+    where
+        RedisResult: TryInto<Option<T>, Error = ConversionError>,
+        V: Into<RBytes<'a>>,
+    {
+        let key = key.into();
+        if let Some(value) = self.issue(get::<T, _>(key.as_bytes()))? {
+            return Ok(value);
+        }
+
+        let value = compute().into();
+        loop {
+            let was_set = self.issue(
+                set(key.as_bytes(), value.as_bytes())
+                    .if_not_exists()
+                    .with_expiry(ttl)
+                    .map_err(RedisError::ValidationError)?,
+            )?;
+            if was_set {
+                let value = RedisResult::String(value.as_bytes().to_vec());
+                return get::<T, _>(key.as_bytes())
+                    .convert_redis_result(value)
+                    .map_err(RedisError::from_conversion_error)?
+                    .ok_or(RedisError::ConversionError(None));
+            }
+
+            // someone else got there first, although their key may already have expired again,
+            // in which case it's worth trying to set ours again
+            if let Some(value) = self.issue(get::<T, _>(key.as_bytes()))? {
+                return Ok(value);
+            }
+        }
+    }
+
+    // Fetches a key's value and remaining time-to-live together in a single round-trip.  The two
+    // reads aren't atomic, so a key that expires in between is reported with `TtlResult::NoKey`.
+    pub fn get_with_ttl<'a, T>(
+        &mut self,
+        key: impl Into<RBytes<'a>>,
+    ) -> Result<Option<(T, TtlResult)>, RedisError>
+    where
+        RedisResult: TryInto<Option<T>, Error = ConversionError>,
+    {
+        let key = key.into();
+        let get_command = get::<T, _>(key.as_bytes());
+        let ttl_command = pttl(key.as_bytes());
+
+        let mut bytes = self.parser.issue_command(&get_command);
+        bytes.extend(self.parser.issue_command(&ttl_command));
+        self.round_trip(&bytes, |client| {
+            // both replies need to be read, even if the first one fails, so that the next command
+            // doesn't receive the TTL as its response
+            let value = client.receive(get_command);
+            let ttl = client.receive(ttl_command)?;
+            Ok(value?.map(|value| (value, ttl)))
+        })
+    }
+
+    // Counts a hit against `key`, returning the number of hits so far in the current window.  The
+    // count resets once `window` has passed since the first hit.
+    pub fn rate_limit<'a>(
+        &mut self,
+        key: impl Into<RBytes<'a>>,
+        window: Duration,
+    ) -> Result<u64, RedisError> {
+        self.issue(rate_limit::increment(key, window).map_err(RedisError::ValidationError)?)
+    }
+
+    // Lazily fetches the value of every key, `batch_size` keys at a time, pairing each key with
+    // its value.  Keys are only taken from the iterator as each batch is needed.
+    pub fn mget_stream<'a, I, T>(
+        &mut self,
+        keys: I,
+        batch_size: usize,
+    ) -> MGetStream<'_, I::IntoIter, T>
+    where
+        I: IntoIterator,
+        I::Item: Into<RBytes<'a>>,
+        RedisResult: TryInto<Option<T>, Error = ConversionError>,
+    {
+        MGetStream {
+            client: self,
+            keys: keys.into_iter(),
+            batch_size: batch_size.max(1),
+            buffer: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    // Iterates over every key matching `pattern` with SCAN, fetching roughly `batch_size` keys at
+    // a time.  Keys added or removed while the scan is running may or may not be seen, and a key
+    // can occasionally be returned more than once.
+    pub fn scan_iter<'a>(
+        &mut self,
+        pattern: impl Into<RBytes<'a>>,
+        batch_size: usize,
+    ) -> ScanIter<'_, 'a> {
+        ScanIter {
+            client: self,
+            pattern: pattern.into(),
+            batch_size: batch_size.max(1),
+            cursor: 0,
+            buffer: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    // Removes every key matching `pattern`, returning how many were removed.  Unlike KEYS and DEL,
+    // this doesn't block the server for long, as keys are found with SCAN and removed with
+    // UNLINK a batch at a time - but it isn't atomic either, so keys added while it runs may be
+    // left behind.
+    pub fn delete_matching<'a>(
+        &mut self,
+        pattern: impl Into<RBytes<'a>>,
+    ) -> Result<u64, RedisError> {
+        let pattern = pattern.into();
+        let mut cursor = 0;
+        let mut deleted = 0;
+        loop {
+            let page = self.issue(
+                scan(cursor)
+                    .matching(pattern.as_bytes())
+                    .count(DELETE_BATCH),
+            )?;
+            // COUNT is only a hint, so a page can still hold more keys than one batch
+            for keys in page.keys.chunks(DELETE_BATCH) {
+                deleted += self.issue(unlink(keys.iter().map(Vec::as_slice)))?;
+            }
+
+            if page.is_complete() {
+                return Ok(deleted);
+            }
+            cursor = page.cursor;
+        }
+    }
+
+    // Reads a key's value and deletes the key in one atomic step, e.g. for one-time tokens that
+    // must only be used once.  Needs redis 6.2 or later.
+    pub fn take<'a, T>(&mut self, key: impl Into<RBytes<'a>>) -> Result<Option<T>, RedisError>
+    where
+        RedisResult: TryInto<Option<T>, Error = ConversionError>,
+    {
+        self.issue(getdel(key))
+    }
+
+    // Reads a key whatever type of value it holds, for tools that need to inspect arbitrary keys.
+    // The type is checked first and then the value read with a second command, so if the key is
+    // replaced in between, the value can come back as `Missing` or as a `RedisReturnedError`.
+    pub fn get_dynamic<'a>(
+        &mut self,
+        key: impl Into<RBytes<'a>>,
+    ) -> Result<DynamicValue, RedisError> {
+        let key = key.into();
+        let key_type = match self.issue(key_type(key.as_bytes()))? {
+            Some(key_type) => key_type,
+            None => return Ok(DynamicValue::Missing),
+        };
+
+        Ok(match key_type {
+            KeyType::String => match self.issue(get(key.as_bytes()))? {
+                Some(value) => DynamicValue::String(value),
+                None => DynamicValue::Missing,
+            },
+            KeyType::List => DynamicValue::List(self.issue(lrange(key.as_bytes(), 0, -1))?),
+            KeyType::Set => DynamicValue::Set(self.issue(smembers(key.as_bytes()))?),
+            KeyType::Hash => DynamicValue::Hash(self.issue(hgetall(key.as_bytes()))?),
+            KeyType::SortedSet => {
+                DynamicValue::SortedSet(self.issue(zrange(key.as_bytes(), 0, -1).with_scores())?)
+            }
+            other => DynamicValue::Unsupported(other),
+        })
+    }
+
+    // Lazily pages through a list, `batch_size` elements at a time.  Pages are fetched by index,
+    // so elements pushed onto the end of the list while iterating will also be returned, but
+    // elements removed from the front will cause later elements to be skipped.
+    pub fn lrange_iter<'a, T>(
+        &mut self,
+        key: impl Into<RBytes<'a>>,
+        batch_size: usize,
+    ) -> LRangeIter<'_, 'a, T>
+    where
+        RedisResult: TryInto<Option<T>, Error = ConversionError>,
+    {
+        LRangeIter {
+            client: self,
+            key: key.into(),
+            batch_size: batch_size.max(1),
+            next_index: 0,
+            buffer: VecDeque::new(),
+            finished: false,
+        }
+    }
+}
+
+// Converts the reply to a prepared command, which has already been sent
+struct Prepared<'p, T>(&'p PreparedCommand<T>);
+
+impl<'p, T> StructuredCommand for Prepared<'p, T> {
+    type Output = T;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.0.bytes().to_vec()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        self.0.convert(result)
+    }
+}
+
+// Reads a reply without converting it, for when the command itself needs to be kept around
+struct RawReply;
+
+impl StructuredCommand for RawReply {
+    type Output = RedisResult;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        Ok(result)
+    }
+}
+
+// Pulls the command name out of an encoded command, which always starts with the name as its
+// first bulk string, e.g. `*2\r\n$3\r\nGET\r\n...`
+fn command_verb(bytes: &[u8]) -> String {
+    let verb = bytes
+        .split(|&b| b == b'\n')
+        .nth(2)
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .unwrap_or_default();
+    String::from_utf8_lossy(verb).to_ascii_uppercase()
+}
+
+// Splits encoded commands (each an array of bulk strings) back into their arguments, starting with
+// the command name.  Several commands can be sent in one write, e.g. by a pipeline, so each one's
+// arguments are returned in turn.
+fn encoded_commands(mut bytes: &[u8]) -> Vec<Vec<&[u8]>> {
+    let mut commands = Vec::new();
+    while let Some((count, rest)) = encoded_header(bytes, b'*') {
+        bytes = rest;
+        let mut arguments = Vec::with_capacity(count);
+        for _ in 0..count {
+            match encoded_header(bytes, b'$') {
+                Some((len, rest)) if rest.len() >= len + 2 => {
+                    arguments.push(&rest[..len]);
+                    bytes = &rest[len + 2..];
+                }
+                _ => break,
+            }
+        }
+        commands.push(arguments);
+    }
+    commands
+}
+
+// Reads a header line such as `$5\r\n`, returning the number in it and everything after the line
+fn encoded_header(bytes: &[u8], prefix: u8) -> Option<(usize, &[u8])> {
+    if bytes.first() != Some(&prefix) {
+        return None;
+    }
+    let end = bytes.iter().position(|&b| b == b'\n')?;
+    let number = std::str::from_utf8(&bytes[1..end])
+        .ok()?
+        .trim_end()
+        .parse()
+        .ok()?;
+    Some((number, &bytes[end + 1..]))
+}
+
+// Redis 5 and earlier reject HELLO as an unknown command, while builds that can't speak the
+// requested version reply with NOPROTO
+fn is_unsupported_hello(error: &RedisErrorValue) -> bool {
+    error.kind() == Some("NOPROTO")
+        || error
+            .message()
+            .is_some_and(|message| message.starts_with("unknown command"))
+}
+
+pub struct MGetStream<'c, I, T> {
+    client: &'c mut Client,
+    keys: I,
+    batch_size: usize,
+    buffer: VecDeque<(Vec<u8>, Option<T>)>,
+    finished: bool,
+}
+
+impl<'c, 'a, I, K, T> Iterator for MGetStream<'c, I, T>
+where
+    I: Iterator<Item = K>,
+    K: Into<RBytes<'a>>,
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    type Item = Result<(Vec<u8>, Option<T>), RedisError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() && !self.finished {
+            let keys: Vec<Vec<u8>> = self
+                .keys
+                .by_ref()
+                .take(self.batch_size)
+                .map(|key| key.into().as_bytes().to_vec())
+                .collect();
+            if keys.is_empty() {
+                self.finished = true;
+                return None;
+            }
+
+            let command = mget::<T>().with_keys(keys.iter().map(Vec::as_slice));
+            match self.client.issue(command) {
+                Ok(values) => self.buffer.extend(keys.into_iter().zip(values)),
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+pub struct ScanIter<'c, 'a> {
+    client: &'c mut Client,
+    pattern: RBytes<'a>,
+    batch_size: usize,
+    cursor: u64,
+    buffer: VecDeque<Vec<u8>>,
+    finished: bool,
+}
+
+impl<'c, 'a> Iterator for ScanIter<'c, 'a> {
+    type Item = Result<Vec<u8>, RedisError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // pages can be empty without the scan being complete, so keep going until there's
+        // something to return
+        while self.buffer.is_empty() && !self.finished {
+            let command = scan(self.cursor)
+                .matching(self.pattern.as_bytes())
+                .count(self.batch_size);
+
+            match self.client.issue(command) {
+                Ok(page) => {
+                    self.finished = page.is_complete();
+                    self.cursor = page.cursor;
+                    self.buffer.extend(page.keys);
+                }
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+pub struct LRangeIter<'c, 'a, T> {
+    client: &'c mut Client,
+    key: RBytes<'a>,
+    batch_size: usize,
+    next_index: usize,
+    buffer: VecDeque<T>,
+    finished: bool,
+}
+
+impl<'c, 'a, T> Iterator for LRangeIter<'c, 'a, T>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    type Item = Result<T, RedisError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() && !self.finished {
+            let start = self.next_index as i64;
+            let stop = (self.next_index + self.batch_size - 1) as i64;
+
+            match self
+                .client
+                .issue(lrange::<T>(self.key.as_bytes(), start, stop))
+            {
+                Ok(page) => {
+                    // a short page means we've reached the end of the list
+                    self.finished = page.len() < self.batch_size;
+                    self.next_index += page.len();
+                    self.buffer.extend(page);
+                }
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+
+        self.buffer.pop_front().map(Ok)
     }
 }
@@ -0,0 +1,169 @@
+use crate::sans_io::parse_inline_command;
+use crate::{encode_result, RBytes, RedisErrorValue, RedisResult};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Result as IoResult, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+type Script = Arc<Mutex<HashMap<Vec<Vec<u8>>, RedisResult>>>;
+
+// A stand-in for redis that replies to each command from a fixed script, for tests that need a
+// server without running `redis-server`.  It doesn't store anything, so e.g. a GET only returns
+// the value that was scripted for it, whether or not a SET was sent first.  Commands that aren't
+// in the script get an error reply.
+#[derive(Debug)]
+pub struct TestServer {
+    address: SocketAddr,
+    script: Script,
+}
+
+impl TestServer {
+    // Listens on a free port on localhost until the process exits
+    pub fn start() -> IoResult<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let script = Script::default();
+
+        let connection_script = script.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let script = connection_script.clone();
+                        thread::spawn(move || serve(stream, script));
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(Self { address, script })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    // Sets the reply to a command, e.g. `server.reply(vec!["GET", "key"], ...)`.  The command
+    // has to match exactly, including the case of the command name.
+    pub fn reply<'a>(
+        &self,
+        command: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+        reply: RedisResult,
+    ) -> &Self {
+        let command = command
+            .into_iter()
+            .map(|arg| arg.into().as_bytes().to_vec())
+            .collect();
+        self.script.lock().unwrap().insert(command, reply);
+        self
+    }
+}
+
+fn serve(stream: TcpStream, script: Script) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    let mut reader = BufReader::new(stream);
+
+    while let Some(command) = read_command(&mut reader) {
+        let reply = script
+            .lock()
+            .unwrap()
+            .get(&command)
+            .cloned()
+            .unwrap_or_else(|| {
+                let name = command.first().map(|name| String::from_utf8_lossy(name));
+                RedisResult::Error(RedisErrorValue::new(format!(
+                    "ERR unscripted command '{}'",
+                    name.unwrap_or_default()
+                )))
+            });
+        if writer.write_all(&encode_result(&reply)).is_err() {
+            break;
+        }
+    }
+}
+
+// Reads the next command a client sent, as its list of arguments, or `None` once the connection
+// is closed.  Clients send commands as arrays of bulk strings, but anything else is treated as an
+// inline command, as redis itself does.  This is public for other scripted servers to build on.
+pub fn read_command(reader: &mut impl BufRead) -> Option<Vec<Vec<u8>>> {
+    let mut line = Vec::new();
+    if reader.read_until(b'\n', &mut line).ok()? == 0 {
+        return None;
+    }
+    if line[0] != b'*' {
+        return parse_inline_command(&line);
+    }
+
+    let count = parse_length(&line[1..])?;
+    (0..count)
+        .map(|_| {
+            line.clear();
+            reader.read_until(b'\n', &mut line).ok()?;
+            if line.first() != Some(&b'$') {
+                return None;
+            }
+            let len = parse_length(&line[1..])?;
+            let mut arg = vec![0; len + 2];
+            reader.read_exact(&mut arg).ok()?;
+            arg.truncate(len);
+            Some(arg)
+        })
+        .collect()
+}
+
+fn parse_length(line: &[u8]) -> Option<usize> {
+    std::str::from_utf8(line).ok()?.trim_end().parse().ok()
+}
+
+#[cfg(all(test, feature = "sync-client"))]
+mod tests {
+    use super::*;
+    use crate::commands::{get, set};
+    use crate::{RedisError, SyncClient};
+    use std::io::Read;
+
+    #[test]
+    fn clients_can_set_and_get_against_the_script() {
+        let server = TestServer::start().unwrap();
+        server
+            .reply(
+                vec!["SET", "key", "value"],
+                RedisResult::String(b"OK".to_vec()),
+            )
+            .reply(vec!["GET", "key"], RedisResult::String(b"value".to_vec()));
+
+        let mut client = SyncClient::new(server.address()).unwrap();
+        client.issue(set("key", "value")).unwrap();
+        assert_eq!(Some("value".to_string()), client.issue(get("key")).unwrap());
+    }
+
+    #[test]
+    fn unscripted_commands_get_an_error_reply() {
+        let server = TestServer::start().unwrap();
+
+        let mut client = SyncClient::new(server.address()).unwrap();
+        match client.issue(get::<String, _>("key")) {
+            Err(RedisError::RedisReturnedError(error)) => {
+                assert_eq!("ERR unscripted command 'GET'", error.to_string())
+            }
+            other => panic!("expected an error reply, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn inline_commands_are_understood() {
+        let server = TestServer::start().unwrap();
+        server.reply(vec!["PING"], RedisResult::String(b"PONG".to_vec()));
+
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+        stream.write_all(b"PING\r\n").unwrap();
+        let mut reply = [0; 10];
+        stream.read_exact(&mut reply).unwrap();
+        assert_eq!(b"$4\r\nPONG\r\n", &reply);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::types::redis_values::{ConversionError, RedisResult};
-use crate::{RBytes, RedisValue};
+use crate::{RBytes, RedisValue, SimpleString};
 use std::convert::TryInto;
 
 pub trait StructuredCommand {
@@ -37,6 +37,23 @@ impl<'a> Command<'a> {
         self.args.push(argument.into());
         self
     }
+
+    // Sugar for the many (mostly admin) commands that only ever reply `+OK`
+    pub fn expecting_ok(self) -> ExpectingOk<'a> {
+        ExpectingOk { command: self }
+    }
+
+    pub fn expecting_simple_string(self) -> ExpectingSimpleString<'a> {
+        ExpectingSimpleString { command: self }
+    }
+
+    pub(crate) fn name(&self) -> &RBytes<'a> {
+        &self.name
+    }
+
+    pub(crate) fn args(&self) -> &[RBytes<'a>] {
+        &self.args
+    }
 }
 
 impl<'a> StructuredCommand for Command<'a> {
@@ -62,9 +79,82 @@ impl<'a> StructuredCommand for Command<'a> {
     }
 }
 
+pub struct ExpectingOk<'a> {
+    command: Command<'a>,
+}
+
+impl<'a> StructuredCommand for ExpectingOk<'a> {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.command.get_bytes()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.expect_ok()
+    }
+}
+
+pub struct ExpectingSimpleString<'a> {
+    command: Command<'a>,
+}
+
+impl<'a> StructuredCommand for ExpectingSimpleString<'a> {
+    type Output = SimpleString;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.command.get_bytes()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+type Converter<T> = Box<dyn Fn(RedisResult) -> Result<T, ConversionError> + Send + Sync>;
+
+// A command that is encoded once up front, for hot paths that send the same command over and over
+// (e.g. a heartbeat PING) - see `SyncClient::issue_prepared`.  Converting a reply consumes the
+// command, so `make_command` is kept to build a fresh one for each reply, but it's never encoded
+// again.
+pub struct PreparedCommand<T> {
+    bytes: Vec<u8>,
+    converter: Converter<T>,
+}
+
+impl<T> PreparedCommand<T> {
+    pub fn new<Cmd>(make_command: impl Fn() -> Cmd + Send + Sync + 'static) -> Self
+    where
+        Cmd: StructuredCommand<Output = T>,
+    {
+        Self {
+            bytes: make_command().get_bytes(),
+            converter: Box::new(move |result| make_command().convert_redis_result(result)),
+        }
+    }
+
+    // The command as it is sent to the server
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub(crate) fn convert(&self, result: RedisResult) -> Result<T, ConversionError> {
+        (self.converter)(result)
+    }
+}
+
+impl<T> std::fmt::Debug for PreparedCommand<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("PreparedCommand")
+            .field("bytes", &String::from_utf8_lossy(&self.bytes))
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::RedisErrorValue;
 
     #[test]
     fn can_create_a_new_command_with_just_a_name() {
@@ -113,4 +203,45 @@ mod tests {
             cmd.get_bytes()
         );
     }
+
+    #[test]
+    fn expecting_ok_only_accepts_ok() {
+        let cmd = || Command::cmd("FLUSHALL").expecting_ok();
+
+        assert!(cmd()
+            .convert_redis_result(RedisResult::String(b"OK".to_vec()))
+            .is_ok());
+        assert!(cmd()
+            .convert_redis_result(RedisResult::Error(RedisErrorValue::new("ERR nope")))
+            .is_err());
+        assert!(cmd()
+            .convert_redis_result(RedisResult::String(b"QUEUED".to_vec()))
+            .is_err());
+        assert_eq!(resp_bytes!("FLUSHALL"), cmd().get_bytes());
+    }
+
+    #[test]
+    fn prepared_commands_match_fresh_ones() {
+        let fresh = || Command::cmd("GET").with_arg("key");
+        let prepared = PreparedCommand::new(fresh);
+
+        assert_eq!(fresh().get_bytes(), prepared.bytes());
+        for reply in [RedisResult::String(b"value".to_vec()), RedisResult::Null] {
+            assert_eq!(
+                fresh().convert_redis_result(reply.clone()).unwrap(),
+                prepared.convert(reply).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn expecting_simple_string_returns_the_status() {
+        assert_eq!(
+            SimpleString("PONG".to_string()),
+            Command::cmd("PING")
+                .expecting_simple_string()
+                .convert_redis_result(RedisResult::String(b"PONG".to_vec()))
+                .unwrap()
+        );
+    }
 }
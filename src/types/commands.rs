@@ -1,14 +1,56 @@
 pub mod get;
-pub use get::{get, mget};
+pub use get::{get, get_lossy, getdel, getdel_lossy, getex, getex_lossy, mget, try_get};
 
 pub mod set;
-pub use set::{getset, mset, set};
+pub use set::{getset, mset, set, setex, try_set};
 
 pub mod increment;
 pub use increment::{decr, decr_by, decr_by_float, incr, incr_by, incr_by_float};
 
+pub mod ranges;
+pub use ranges::{RangeWithBounds, RangeWithLowerBound};
+
 pub mod bit_commands;
 pub use bit_commands::{bitcount, bitop, bitpos, getbit, setbit};
 
+mod fields;
+mod members;
+
 pub mod util_commands;
-pub use util_commands::{ping, echo};
+pub use util_commands::{auth, echo, hello, ping};
+
+pub mod server_commands;
+pub use server_commands::{
+    command_count, command_getkeys, command_info, command_list, config_resetstat, config_rewrite,
+    debug_change_repl_id, debug_object, debug_reload, failover, lolwut, swapdb, time, wait,
+    waitaof, CommandInfo, DebugObject,
+};
+
+pub mod scripting;
+pub use scripting::eval;
+
+pub mod list_commands;
+pub use list_commands::{lpush, lrange, rpush};
+
+pub mod key_commands;
+pub use key_commands::{
+    copy, copy_to_db, del, dump, exists, expire, key_type, keys, object_encoding, object_idletime,
+    pexpire, pttl, restore, scan, ttl, unlink, KeyType, ScanPage, TtlResult,
+};
+
+pub mod client_commands;
+pub use client_commands::{
+    client_id, client_info, client_list, client_pause, client_tracking, ClientInfo, Invalidation,
+};
+
+pub mod pubsub_commands;
+pub use pubsub_commands::{publish, pubsub_channels, pubsub_numpat, pubsub_numsub, spublish};
+
+pub mod set_commands;
+pub use set_commands::{sintercard, smembers};
+
+pub mod sorted_set_commands;
+pub use sorted_set_commands::{zadd, zcount, zdiff, zinter, zrange, zunion, Aggregate, ScoreBound};
+
+pub mod hash_commands;
+pub use hash_commands::{hgetall, hsetnx};
@@ -1,8 +1,9 @@
 use crate::types::redis_values::ConversionError;
 use crate::types::{RedisResult, StructuredCommand};
-use crate::RBytes;
+use crate::{RBytes, ValidationError};
 use std::convert::TryInto;
-use std::ops::{Range, RangeFrom, RangeInclusive};
+
+pub use super::ranges::{RangeWithBounds, RangeWithLowerBound};
 
 #[derive(Debug)]
 pub struct SetBit<'a> {
@@ -11,6 +12,21 @@ pub struct SetBit<'a> {
     value: bool,
 }
 
+impl<'a> SetBit<'a> {
+    // Setting a bit far into a string makes redis allocate the whole string up to that point, so
+    // this can be used to guard against accidentally huge offsets before anything is sent.
+    pub fn with_max_offset(self, max: u32) -> Result<Self, ValidationError> {
+        if self.offset > max {
+            Err(ValidationError::BitOffsetTooLarge {
+                offset: self.offset,
+                max,
+            })
+        } else {
+            Ok(self)
+        }
+    }
+}
+
 impl<'a> StructuredCommand for SetBit<'a> {
     type Output = bool;
 
@@ -75,43 +91,6 @@ pub fn getbit<'a>(key: impl Into<RBytes<'a>>, offset: u32) -> GetBit<'a> {
     }
 }
 
-pub trait RangeWithBounds {
-    fn into_bounds(self) -> (i64, i64);
-}
-
-impl RangeWithBounds for Range<i64> {
-    fn into_bounds(self) -> (i64, i64) {
-        (self.start, self.end)
-    }
-}
-
-impl RangeWithBounds for RangeInclusive<i64> {
-    fn into_bounds(self) -> (i64, i64) {
-        let (start, end) = self.into_inner();
-        (start, end - 1)
-    }
-}
-
-pub trait RangeWithLowerBound {
-    fn into_bounds(self) -> (i64, Option<i64>);
-}
-
-impl<T> RangeWithLowerBound for T
-where
-    T: RangeWithBounds,
-{
-    fn into_bounds(self) -> (i64, Option<i64>) {
-        let (lower, upper) = self.into_bounds();
-        (lower, Some(upper))
-    }
-}
-
-impl RangeWithLowerBound for RangeFrom<i64> {
-    fn into_bounds(self) -> (i64, Option<i64>) {
-        (self.start, None)
-    }
-}
-
 #[derive(Debug)]
 pub struct BitCount<'a> {
     key: RBytes<'a>,
@@ -328,3 +307,37 @@ pub mod bitop {
 }
 
 // TODO: BITFIELD
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setbit_with_an_offset_within_the_limit_is_allowed() {
+        let cmd = setbit("my-key", 100, true).with_max_offset(100).unwrap();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*4\r\n\
+             $6\r\nSETBIT\r\n\
+             $6\r\nmy-key\r\n\
+             $3\r\n100\r\n\
+             $1\r\n1\r\n"
+        );
+    }
+
+    #[test]
+    fn setbit_with_an_offset_over_the_limit_is_rejected() {
+        let error = setbit("my-key", 1 << 20, true)
+            .with_max_offset(1024)
+            .unwrap_err();
+
+        assert_eq!(
+            ValidationError::BitOffsetTooLarge {
+                offset: 1 << 20,
+                max: 1024
+            },
+            error
+        );
+    }
+}
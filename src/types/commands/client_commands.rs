@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::fields::split_fields;
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use crate::RBytes;
+
+pub struct ClientId;
+
+impl StructuredCommand for ClientId {
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("CLIENT", "ID")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Integer(id @ 0..=std::i64::MAX) => Ok(id as u64),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+pub fn client_id() -> ClientId {
+    ClientId
+}
+
+pub struct ClientTracking<'a> {
+    on: bool,
+    redirect: Option<u64>,
+    prefixes: Vec<RBytes<'a>>,
+    bcast: bool,
+}
+
+impl<'a> ClientTracking<'a> {
+    // Sends invalidation messages to another client (usually one subscribed to the
+    // `__redis__:invalidate` channel), rather than to this connection.
+    pub fn redirect(mut self, client_id: u64) -> Self {
+        self.redirect.replace(client_id);
+        self
+    }
+
+    pub fn prefix(mut self, prefix: impl Into<RBytes<'a>>) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    pub fn bcast(mut self) -> Self {
+        self.bcast = true;
+        self
+    }
+}
+
+impl<'a> StructuredCommand for ClientTracking<'a> {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        if !self.on {
+            return resp_bytes!("CLIENT", "TRACKING", "OFF");
+        }
+
+        let redirect = self.redirect.map(|id| id.to_string());
+
+        let mut count = 3 + self.prefixes.len() * 2;
+        if redirect.is_some() {
+            count += 2;
+        }
+        if self.bcast {
+            count += 1;
+        }
+
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice(count.to_string().as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "CLIENT");
+        insert_bytes_into_vec!(bytes, "TRACKING");
+        insert_bytes_into_vec!(bytes, "ON");
+        if let Some(redirect) = redirect {
+            insert_bytes_into_vec!(bytes, "REDIRECT");
+            insert_bytes_into_vec!(bytes, redirect);
+        }
+        for prefix in &self.prefixes {
+            insert_bytes_into_vec!(bytes, "PREFIX");
+            insert_bytes_into_vec!(bytes, prefix);
+        }
+        if self.bcast {
+            insert_bytes_into_vec!(bytes, "BCAST");
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// Invalidations are only delivered to a client with a handler - see
+// `SyncClient::set_invalidation_handler`
+pub fn client_tracking<'a>(on: bool) -> ClientTracking<'a> {
+    ClientTracking {
+        on,
+        redirect: None,
+        prefixes: Vec::new(),
+        bcast: false,
+    }
+}
+
+// What a server with tracking turned on reports as changed, so that cached copies can be dropped
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Invalidation {
+    // these keys were modified, expired or evicted
+    Keys(Vec<Vec<u8>>),
+    // the database was flushed, so every cached key is stale
+    All,
+}
+
+// Converts the payload of an invalidation, which is the same whether it arrived as a RESP3 push
+// or as a message on the `__redis__:invalidate` channel
+impl TryFrom<RedisResult> for Invalidation {
+    type Error = ConversionError;
+
+    fn try_from(result: RedisResult) -> Result<Self, Self::Error> {
+        match result {
+            RedisResult::Null => Ok(Invalidation::All),
+            RedisResult::Array(keys) => keys
+                .into_iter()
+                .map(|key| {
+                    Option::<Vec<u8>>::try_from(key)?
+                        .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+                })
+                .collect::<Result<_, _>>()
+                .map(Invalidation::Keys),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PauseMode {
+    Write,
+    All,
+}
+
+pub struct ClientPause {
+    duration: Duration,
+    mode: Option<PauseMode>,
+}
+
+impl ClientPause {
+    // Only pauses clients that try to write (redis 6.2+)
+    pub fn write_only(mut self) -> Self {
+        self.mode.replace(PauseMode::Write);
+        self
+    }
+
+    // Pauses every command, which is also the default if no mode is given
+    pub fn all(mut self) -> Self {
+        self.mode.replace(PauseMode::All);
+        self
+    }
+}
+
+impl StructuredCommand for ClientPause {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let millis = self.duration.as_millis().to_string();
+        match self.mode {
+            Some(PauseMode::Write) => resp_bytes!("CLIENT", "PAUSE", millis, "WRITE"),
+            Some(PauseMode::All) => resp_bytes!("CLIENT", "PAUSE", millis, "ALL"),
+            None => resp_bytes!("CLIENT", "PAUSE", millis),
+        }
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+pub fn client_pause(duration: Duration) -> ClientPause {
+    ClientPause {
+        duration,
+        mode: None,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ClientInfoParseError {
+    field: String,
+}
+
+impl fmt::Display for ClientInfoParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "missing or invalid client info field `{}`", self.field)
+    }
+}
+
+impl Error for ClientInfoParseError {}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ClientInfo {
+    pub id: u64,
+    pub addr: String,
+    pub name: Option<String>,
+    pub age: Duration,
+    pub idle: Duration,
+    pub flags: String,
+    pub db: u32,
+    fields: HashMap<String, String>,
+}
+
+impl ClientInfo {
+    // Any field from the raw info line, including the ones that aren't parsed into the struct
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+impl FromStr for ClientInfo {
+    type Err = ClientInfoParseError;
+
+    // Parses a single line of space-separated `key=value` pairs, as returned by CLIENT INFO
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields = split_fields(line, '=');
+
+        fn required<'f>(
+            fields: &'f HashMap<String, String>,
+            field: &str,
+        ) -> Result<&'f str, ClientInfoParseError> {
+            fields
+                .get(field)
+                .map(String::as_str)
+                .ok_or_else(|| ClientInfoParseError {
+                    field: field.to_string(),
+                })
+        }
+
+        fn number<N: FromStr>(
+            fields: &HashMap<String, String>,
+            field: &str,
+        ) -> Result<N, ClientInfoParseError> {
+            required(fields, field)?
+                .parse()
+                .map_err(|_| ClientInfoParseError {
+                    field: field.to_string(),
+                })
+        }
+
+        Ok(ClientInfo {
+            id: number(&fields, "id")?,
+            addr: required(&fields, "addr")?.to_string(),
+            name: fields.get("name").filter(|name| !name.is_empty()).cloned(),
+            age: Duration::from_secs(number(&fields, "age")?),
+            idle: Duration::from_secs(number(&fields, "idle")?),
+            flags: required(&fields, "flags")?.to_string(),
+            db: number(&fields, "db")?,
+            fields,
+        })
+    }
+}
+
+fn parse_client_info_lines(result: RedisResult) -> Result<Vec<ClientInfo>, ConversionError> {
+    let text: String = result.try_into()?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.parse()
+                .map_err(|err| ConversionError::CannotParseStringResponse {
+                    error: Box::new(err),
+                    text: line.to_string(),
+                })
+        })
+        .collect()
+}
+
+pub struct ClientInfoCommand;
+
+impl StructuredCommand for ClientInfoCommand {
+    type Output = ClientInfo;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("CLIENT", "INFO")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        parse_client_info_lines(result)?
+            .pop()
+            .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+    }
+}
+
+pub fn client_info() -> ClientInfoCommand {
+    ClientInfoCommand
+}
+
+pub struct ClientList;
+
+impl StructuredCommand for ClientList {
+    type Output = Vec<ClientInfo>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("CLIENT", "LIST")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        parse_client_info_lines(result)
+    }
+}
+
+pub fn client_list() -> ClientList {
+    ClientList
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidations_list_their_keys_or_cover_everything() {
+        assert_eq!(
+            Invalidation::Keys(vec![b"a".to_vec(), b"b".to_vec()]),
+            Invalidation::try_from(RedisResult::Array(vec![
+                RedisResult::String(b"a".to_vec()),
+                RedisResult::String(b"b".to_vec()),
+            ]))
+            .unwrap()
+        );
+        assert_eq!(
+            Invalidation::All,
+            Invalidation::try_from(RedisResult::Null).unwrap()
+        );
+    }
+
+    #[test]
+    fn client_tracking_can_be_turned_off() {
+        assert_eq!(
+            String::from_utf8(client_tracking(false).get_bytes()).unwrap(),
+            "*3\r\n\
+             $6\r\nCLIENT\r\n\
+             $8\r\nTRACKING\r\n\
+             $3\r\nOFF\r\n"
+        );
+    }
+
+    #[test]
+    fn client_tracking_includes_all_given_options() {
+        let cmd = client_tracking(true)
+            .redirect(12)
+            .prefix("user:")
+            .prefix("session:")
+            .bcast();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*10\r\n\
+             $6\r\nCLIENT\r\n\
+             $8\r\nTRACKING\r\n\
+             $2\r\nON\r\n\
+             $8\r\nREDIRECT\r\n\
+             $2\r\n12\r\n\
+             $6\r\nPREFIX\r\n\
+             $5\r\nuser:\r\n\
+             $6\r\nPREFIX\r\n\
+             $8\r\nsession:\r\n\
+             $5\r\nBCAST\r\n"
+        );
+    }
+
+    #[test]
+    fn client_pause_includes_the_mode_if_given() {
+        assert_eq!(
+            String::from_utf8(client_pause(Duration::from_millis(500)).get_bytes()).unwrap(),
+            "*3\r\n\
+             $6\r\nCLIENT\r\n\
+             $5\r\nPAUSE\r\n\
+             $3\r\n500\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(
+                client_pause(Duration::from_secs(2))
+                    .write_only()
+                    .get_bytes()
+            )
+            .unwrap(),
+            "*4\r\n\
+             $6\r\nCLIENT\r\n\
+             $5\r\nPAUSE\r\n\
+             $4\r\n2000\r\n\
+             $5\r\nWRITE\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(client_pause(Duration::from_secs(2)).all().get_bytes()).unwrap(),
+            "*4\r\n\
+             $6\r\nCLIENT\r\n\
+             $5\r\nPAUSE\r\n\
+             $4\r\n2000\r\n\
+             $3\r\nALL\r\n"
+        );
+    }
+
+    #[test]
+    fn client_info_line_is_parsed_into_typed_fields() {
+        let info: ClientInfo = "id=3 addr=127.0.0.1:51234 laddr=127.0.0.1:6379 fd=8 name= \
+                                age=12 idle=4 flags=N db=2 sub=0 cmd=client|info"
+            .parse()
+            .unwrap();
+
+        assert_eq!(3, info.id);
+        assert_eq!("127.0.0.1:51234", info.addr);
+        assert_eq!(None, info.name);
+        assert_eq!(Duration::from_secs(12), info.age);
+        assert_eq!(Duration::from_secs(4), info.idle);
+        assert_eq!("N", info.flags);
+        assert_eq!(2, info.db);
+        assert_eq!(Some("client|info"), info.field("cmd"));
+    }
+
+    #[test]
+    fn client_info_line_with_missing_fields_is_an_error() {
+        let error = "id=3 addr=127.0.0.1:51234"
+            .parse::<ClientInfo>()
+            .unwrap_err();
+        assert_eq!("age", error.field);
+    }
+
+    #[test]
+    fn client_list_parses_every_line() {
+        let result = RedisResult::String(
+            b"id=3 addr=a:1 name=first age=1 idle=1 flags=N db=0\n\
+              id=4 addr=b:2 name= age=2 idle=2 flags=N db=0\n"
+                .to_vec(),
+        );
+
+        let clients = client_list().convert_redis_result(result).unwrap();
+        assert_eq!(
+            vec![(3, Some("first".to_string())), (4, None)],
+            clients
+                .into_iter()
+                .map(|client| (client.id, client.name))
+                .collect::<Vec<_>>()
+        );
+    }
+}
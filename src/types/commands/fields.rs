@@ -0,0 +1,11 @@
+use std::collections::HashMap;
+
+// Splits the single-line `key<separator>value` listings that some commands reply with (e.g.
+// `id=3 addr=...` from CLIENT INFO, or `refcount:1 encoding:...` from DEBUG OBJECT).  Anything
+// without a separator is skipped.
+pub(crate) fn split_fields(line: &str, separator: char) -> HashMap<String, String> {
+    line.split_whitespace()
+        .filter_map(|pair| pair.split_once(separator))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
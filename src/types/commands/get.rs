@@ -1,9 +1,11 @@
 use std::convert::{TryFrom, TryInto};
 use std::marker::PhantomData;
+use std::time::Duration;
 
+use crate::types::commands::set::check_millisecond_expiry;
 use crate::types::redis_values::{ConversionError, RedisResult};
 use crate::types::StructuredCommand;
-use crate::RBytes;
+use crate::{RBytes, ValidationError};
 
 pub struct Get<'a, T> {
     key: RBytes<'a>,
@@ -19,13 +21,16 @@ impl<'a, T> Get<'a, T> {
     }
 
     pub fn with_default(self, default: T) -> GetWithDefault<'a, T> {
-        GetWithDefault {
-            get_command: self,
+        WithDefault {
+            command: self,
             default,
         }
     }
 }
 
+// `get` with a fallback for missing keys, kept under its own name for existing callers
+pub type GetWithDefault<'a, T> = WithDefault<Get<'a, T>, T>;
+
 impl<'a, T> StructuredCommand for Get<'a, T>
 where
     RedisResult: TryInto<Option<T>, Error = ConversionError>,
@@ -41,32 +46,219 @@ where
     }
 }
 
-pub struct GetWithDefault<'a, T> {
-    get_command: Get<'a, T>,
+impl<'a, T: Default> Get<'a, T> {
+    // Treats a missing key as the type's default value, e.g. zero for numbers
+    pub fn or_default(self) -> GetWithDefault<'a, T> {
+        self.with_default(T::default())
+    }
+}
+
+impl<'a> Get<'a, Vec<u8>> {
+    pub fn lossy(self) -> Lossy<Self> {
+        Lossy { command: self }
+    }
+}
+
+pub fn get<'a, T, B>(key: B) -> Get<'a, T>
+where
+    B: Into<RBytes<'a>>,
+{
+    Get::new(key.into())
+}
+
+// Like `get::<String>`, but replaces any invalid UTF-8 rather than failing
+pub fn get_lossy<'a>(key: impl Into<RBytes<'a>>) -> Lossy<Get<'a, Vec<u8>>> {
+    get(key).lossy()
+}
+
+// Like `get`, but checks the key's size before the command is built
+pub fn try_get<'a, T>(key: impl Into<RBytes<'a>>) -> Result<Get<'a, T>, ValidationError> {
+    Ok(Get::new(RBytes::try_from_key(key)?))
+}
+
+// The defaulting and lossy versions of GET, GETDEL and GETEX, which all read a key's value the same
+// way
+pub struct WithDefault<C, T> {
+    command: C,
     default: T,
 }
 
-impl<'a, T> StructuredCommand for GetWithDefault<'a, T>
+impl<C, T> StructuredCommand for WithDefault<C, T>
 where
-    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+    C: StructuredCommand<Output = Option<T>>,
 {
     type Output = T;
 
     fn get_bytes(&self) -> Vec<u8> {
-        self.get_command.get_bytes()
+        self.command.get_bytes()
     }
 
     fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
-        let intermediate: Result<Option<Self::Output>, ConversionError> = result.try_into();
-        intermediate.map(|option| option.unwrap_or(self.default))
+        let value = self.command.convert_redis_result(result)?;
+        Ok(value.unwrap_or(self.default))
     }
 }
 
-pub fn get<'a, T, B>(key: B) -> Get<'a, T>
+pub struct Lossy<C> {
+    command: C,
+}
+
+impl<C> StructuredCommand for Lossy<C>
+where
+    C: StructuredCommand<Output = Option<Vec<u8>>>,
+{
+    type Output = Option<String>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.command.get_bytes()
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        let bytes = self.command.convert_redis_result(result)?;
+        Ok(bytes.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+    }
+}
+
+pub struct GetDel<'a, T> {
+    key: RBytes<'a>,
+    _t: PhantomData<T>,
+}
+
+impl<'a, T> GetDel<'a, T> {
+    pub fn with_default(self, default: T) -> WithDefault<Self, T> {
+        WithDefault {
+            command: self,
+            default,
+        }
+    }
+}
+
+impl<'a, T: Default> GetDel<'a, T> {
+    pub fn or_default(self) -> WithDefault<Self, T> {
+        self.with_default(T::default())
+    }
+}
+
+impl<'a> GetDel<'a, Vec<u8>> {
+    pub fn lossy(self) -> Lossy<Self> {
+        Lossy { command: self }
+    }
+}
+
+impl<'a, T> StructuredCommand for GetDel<'a, T>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    // the value the key had before it was deleted
+    type Output = Option<T>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("GETDEL", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// Gets a key and deletes it in one step.  Needs redis 6.2 or later.
+pub fn getdel<'a, T, B>(key: B) -> GetDel<'a, T>
 where
     B: Into<RBytes<'a>>,
 {
-    Get::new(key.into())
+    GetDel {
+        key: key.into(),
+        _t: PhantomData,
+    }
+}
+
+// Like `getdel::<String>`, but replaces any invalid UTF-8 rather than failing
+pub fn getdel_lossy<'a>(key: impl Into<RBytes<'a>>) -> Lossy<GetDel<'a, Vec<u8>>> {
+    getdel(key).lossy()
+}
+
+enum GetExExpiry {
+    After(Duration),
+    Persist,
+}
+
+pub struct GetEx<'a, T> {
+    key: RBytes<'a>,
+    expiry: Option<GetExExpiry>,
+    _t: PhantomData<T>,
+}
+
+impl<'a, T> GetEx<'a, T> {
+    // Sets the key to expire after `duration`, with millisecond precision
+    pub fn with_expiry(mut self, duration: Duration) -> Result<Self, ValidationError> {
+        check_millisecond_expiry(duration, "GETEX")?;
+        self.expiry = Some(GetExExpiry::After(duration));
+        Ok(self)
+    }
+
+    // Removes any expiry from the key
+    pub fn persist(mut self) -> Self {
+        self.expiry = Some(GetExExpiry::Persist);
+        self
+    }
+
+    pub fn with_default(self, default: T) -> WithDefault<Self, T> {
+        WithDefault {
+            command: self,
+            default,
+        }
+    }
+}
+
+impl<'a, T: Default> GetEx<'a, T> {
+    pub fn or_default(self) -> WithDefault<Self, T> {
+        self.with_default(T::default())
+    }
+}
+
+impl<'a> GetEx<'a, Vec<u8>> {
+    pub fn lossy(self) -> Lossy<Self> {
+        Lossy { command: self }
+    }
+}
+
+impl<'a, T> StructuredCommand for GetEx<'a, T>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    type Output = Option<T>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        match &self.expiry {
+            Some(GetExExpiry::After(duration)) => {
+                resp_bytes!("GETEX", &self.key, "PX", duration.as_millis().to_string())
+            }
+            Some(GetExExpiry::Persist) => resp_bytes!("GETEX", &self.key, "PERSIST"),
+            None => resp_bytes!("GETEX", &self.key),
+        }
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// Gets a key, optionally changing its expiry at the same time.  Without `with_expiry` or
+// `persist`, this is the same as GET.  Needs redis 6.2 or later.
+pub fn getex<'a, T, B>(key: B) -> GetEx<'a, T>
+where
+    B: Into<RBytes<'a>>,
+{
+    GetEx {
+        key: key.into(),
+        expiry: None,
+        _t: PhantomData,
+    }
+}
+
+// Like `getex::<String>`, but replaces any invalid UTF-8 rather than failing
+pub fn getex_lossy<'a>(key: impl Into<RBytes<'a>>) -> Lossy<GetEx<'a, Vec<u8>>> {
+    getex(key).lossy()
 }
 
 pub struct GetMultipleList<'a, T> {
@@ -106,13 +298,7 @@ where
     }
 
     fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
-        match result {
-            RedisResult::Array(results) => results.into_iter().map(|r| r.try_into()).collect(),
-            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
-            _ => Err(ConversionError::NoConversionTypeMatch {
-                value: Option::try_from(result)?,
-            }),
-        }
+        Vec::try_from(result)
     }
 }
 
@@ -138,4 +324,102 @@ mod tests {
              $4\r\ntest\r\n"
         )
     }
+
+    #[test]
+    fn get_or_default_converts_null_to_the_default() {
+        assert_eq!(
+            0,
+            get::<i64, _>("missing")
+                .or_default()
+                .convert_redis_result(RedisResult::Null)
+                .unwrap()
+        );
+        assert_eq!(
+            5,
+            get::<i64, _>("present")
+                .or_default()
+                .convert_redis_result(RedisResult::String(b"5".to_vec()))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn get_lossy_replaces_invalid_utf8() {
+        assert_eq!(
+            Some("a\u{FFFD}b".to_string()),
+            get_lossy("key")
+                .convert_redis_result(RedisResult::String(b"a\xFFb".to_vec()))
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            get_lossy("key")
+                .convert_redis_result(RedisResult::Null)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn getdel_offers_the_same_conversions_as_get() {
+        assert_eq!(
+            String::from_utf8(getdel::<i64, _>("key").get_bytes()).unwrap(),
+            "*2\r\n$6\r\nGETDEL\r\n$3\r\nkey\r\n"
+        );
+        assert_eq!(
+            7,
+            getdel::<i64, _>("key")
+                .with_default(7)
+                .convert_redis_result(RedisResult::Null)
+                .unwrap()
+        );
+        assert_eq!(
+            0,
+            getdel::<i64, _>("key")
+                .or_default()
+                .convert_redis_result(RedisResult::Null)
+                .unwrap()
+        );
+        assert_eq!(
+            Some("a\u{FFFD}b".to_string()),
+            getdel_lossy("key")
+                .convert_redis_result(RedisResult::String(b"a\xFFb".to_vec()))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn getex_sends_the_chosen_expiry() {
+        assert_eq!(
+            String::from_utf8(getex::<String, _>("key").get_bytes()).unwrap(),
+            "*2\r\n$5\r\nGETEX\r\n$3\r\nkey\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(
+                getex::<String, _>("key")
+                    .with_expiry(Duration::from_secs(2))
+                    .unwrap()
+                    .get_bytes()
+            )
+            .unwrap(),
+            "*4\r\n$5\r\nGETEX\r\n$3\r\nkey\r\n$2\r\nPX\r\n$4\r\n2000\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(getex_lossy("key").get_bytes()).unwrap(),
+            "*2\r\n$5\r\nGETEX\r\n$3\r\nkey\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(getex::<String, _>("key").persist().get_bytes()).unwrap(),
+            "*3\r\n$5\r\nGETEX\r\n$3\r\nkey\r\n$7\r\nPERSIST\r\n"
+        );
+    }
+
+    #[test]
+    fn getex_rejects_a_zero_expiry() {
+        assert_eq!(
+            Some(ValidationError::ZeroExpiry { command: "GETEX" }),
+            getex::<String, _>("key")
+                .with_expiry(Duration::from_millis(0))
+                .err()
+        );
+    }
 }
@@ -0,0 +1,143 @@
+use std::convert::{TryFrom, TryInto};
+use std::marker::PhantomData;
+
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use crate::RBytes;
+
+pub struct HSetNx<'a> {
+    key: RBytes<'a>,
+    field: RBytes<'a>,
+    value: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for HSetNx<'a> {
+    // whether the field was set - false if it already existed, in which case it's left alone
+    type Output = bool;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("HSETNX", &self.key, &self.field, &self.value)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+pub fn hsetnx<'a>(
+    key: impl Into<RBytes<'a>>,
+    field: impl Into<RBytes<'a>>,
+    value: impl Into<RBytes<'a>>,
+) -> HSetNx<'a> {
+    HSetNx {
+        key: key.into(),
+        field: field.into(),
+        value: value.into(),
+    }
+}
+
+pub struct HGetAll<'a, T> {
+    key: RBytes<'a>,
+    _t: PhantomData<T>,
+}
+
+fn hash_element<T>(element: RedisResult) -> Result<T, ConversionError>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    element
+        .try_into()?
+        .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+}
+
+impl<'a, T> StructuredCommand for HGetAll<'a, T>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    // each field with its value, and empty if the key doesn't exist
+    type Output = Vec<(T, T)>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("HGETALL", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            // RESP2 sends the fields and values as one flat array, RESP3 as a map
+            RedisResult::Array(elements) => {
+                if elements.len() % 2 != 0 {
+                    return Err(ConversionError::NoConversionTypeMatch { value: None });
+                }
+                let mut elements = elements.into_iter();
+                let mut pairs = Vec::with_capacity(elements.len() / 2);
+                while let (Some(field), Some(value)) = (elements.next(), elements.next()) {
+                    pairs.push((hash_element(field)?, hash_element(value)?));
+                }
+                Ok(pairs)
+            }
+            RedisResult::Map(pairs) => pairs
+                .into_iter()
+                .map(|(field, value)| Ok((hash_element(field)?, hash_element(value)?)))
+                .collect(),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: Option::try_from(result)?,
+            }),
+        }
+    }
+}
+
+pub fn hgetall<'a, T>(key: impl Into<RBytes<'a>>) -> HGetAll<'a, T> {
+    HGetAll {
+        key: key.into(),
+        _t: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hsetnx_converts_to_bytes() {
+        assert_eq!(
+            String::from_utf8(hsetnx("hash", "field", "value").get_bytes()).unwrap(),
+            "*4\r\n$6\r\nHSETNX\r\n$4\r\nhash\r\n$5\r\nfield\r\n$5\r\nvalue\r\n"
+        );
+    }
+
+    #[test]
+    fn hsetnx_reports_whether_the_field_was_set() {
+        assert!(hsetnx("hash", "field", "value")
+            .convert_redis_result(RedisResult::Integer(1))
+            .unwrap());
+        assert!(!hsetnx("hash", "field", "value")
+            .convert_redis_result(RedisResult::Integer(0))
+            .unwrap());
+    }
+
+    #[test]
+    fn hgetall_reads_flat_pairs_and_maps() {
+        let expected = vec![("field".to_string(), "value".to_string())];
+
+        let flat = RedisResult::Array(vec![
+            RedisResult::String(b"field".to_vec()),
+            RedisResult::String(b"value".to_vec()),
+        ]);
+        assert_eq!(
+            expected,
+            hgetall::<String>("hash")
+                .convert_redis_result(flat)
+                .unwrap()
+        );
+
+        let map = RedisResult::Map(vec![(
+            RedisResult::String(b"field".to_vec()),
+            RedisResult::String(b"value".to_vec()),
+        )]);
+        assert_eq!(
+            expected,
+            hgetall::<String>("hash").convert_redis_result(map).unwrap()
+        );
+    }
+}
@@ -0,0 +1,922 @@
+use std::convert::{TryFrom, TryInto};
+use std::time::Duration;
+
+use super::members::members;
+use super::set::check_millisecond_expiry;
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use crate::{RBytes, ValidationError};
+
+pub struct PExpire<'a> {
+    key: RBytes<'a>,
+    duration: Duration,
+}
+
+impl<'a> StructuredCommand for PExpire<'a> {
+    // whether the key existed to have its expiry set
+    type Output = bool;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("PEXPIRE", &self.key, self.duration.as_millis().to_string())
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// As with `expire`, a duration that would be sent as zero deletes the key on the spot, so anything
+// shorter than a millisecond is rejected
+pub fn pexpire<'a>(
+    key: impl Into<RBytes<'a>>,
+    duration: Duration,
+) -> Result<PExpire<'a>, ValidationError> {
+    check_millisecond_expiry(duration, "PEXPIRE")?;
+    Ok(PExpire {
+        key: key.into(),
+        duration,
+    })
+}
+
+pub struct Expire<'a> {
+    key: RBytes<'a>,
+    seconds: u64,
+}
+
+impl<'a> StructuredCommand for Expire<'a> {
+    // whether the key existed to have its expiry set
+    type Output = bool;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("EXPIRE", &self.key, self.seconds.to_string())
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// EXPIRE only has a resolution of whole seconds, so the duration is rounded down.  Anything that
+// rounds down to zero would delete the key on the spot, which is rejected here as it's unlikely to
+// be what was meant - use `pexpire` for shorter expiries, or DEL to delete the key.
+pub fn expire<'a>(
+    key: impl Into<RBytes<'a>>,
+    duration: Duration,
+) -> Result<Expire<'a>, ValidationError> {
+    if duration.as_secs() == 0 {
+        return Err(ValidationError::ZeroExpiry { command: "EXPIRE" });
+    }
+
+    Ok(Expire {
+        key: key.into(),
+        seconds: duration.as_secs(),
+    })
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TtlResult {
+    NoKey,
+    NoExpiry,
+    Expires(Duration),
+}
+
+// The kinds of value a key can hold, as reported by TYPE
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum KeyType {
+    String,
+    List,
+    Set,
+    SortedSet,
+    Hash,
+    Stream,
+    // e.g. a type added by a module
+    Other(String),
+}
+
+pub struct Type<'a> {
+    key: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for Type<'a> {
+    // `None` if the key doesn't exist
+    type Output = Option<KeyType>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("TYPE", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        let name: String = result.try_into()?;
+        Ok(match name.as_str() {
+            "none" => None,
+            "string" => Some(KeyType::String),
+            "list" => Some(KeyType::List),
+            "set" => Some(KeyType::Set),
+            "zset" => Some(KeyType::SortedSet),
+            "hash" => Some(KeyType::Hash),
+            "stream" => Some(KeyType::Stream),
+            _ => Some(KeyType::Other(name)),
+        })
+    }
+}
+
+pub fn key_type<'a>(key: impl Into<RBytes<'a>>) -> Type<'a> {
+    Type { key: key.into() }
+}
+
+pub struct PTtl<'a> {
+    key: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for PTtl<'a> {
+    type Output = TtlResult;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("PTTL", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        ttl_result(result, Duration::from_millis)
+    }
+}
+
+pub fn pttl<'a>(key: impl Into<RBytes<'a>>) -> PTtl<'a> {
+    PTtl { key: key.into() }
+}
+
+pub struct Ttl<'a> {
+    key: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for Ttl<'a> {
+    type Output = TtlResult;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("TTL", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        ttl_result(result, Duration::from_secs)
+    }
+}
+
+// Like `pttl`, but only to the nearest second
+pub fn ttl<'a>(key: impl Into<RBytes<'a>>) -> Ttl<'a> {
+    Ttl { key: key.into() }
+}
+
+// TTL and PTTL use negative numbers to say why a key has no TTL, and otherwise reply with the TTL
+// in their own unit
+fn ttl_result(
+    result: RedisResult,
+    unit: fn(u64) -> Duration,
+) -> Result<TtlResult, ConversionError> {
+    match result {
+        RedisResult::Integer(-2) => Ok(TtlResult::NoKey),
+        RedisResult::Integer(-1) => Ok(TtlResult::NoExpiry),
+        RedisResult::Integer(ttl @ 0..=i64::MAX) => Ok(TtlResult::Expires(unit(ttl as u64))),
+        RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+        _ => Err(ConversionError::NoConversionTypeMatch {
+            value: result.try_into()?,
+        }),
+    }
+}
+
+pub struct Dump<'a> {
+    key: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for Dump<'a> {
+    // the serialized value, or `None` if the key doesn't exist
+    type Output = Option<Vec<u8>>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("DUMP", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+pub fn dump<'a>(key: impl Into<RBytes<'a>>) -> Dump<'a> {
+    Dump { key: key.into() }
+}
+
+// Redis only keeps one of these, depending on whether the eviction policy is LRU or LFU
+#[derive(Debug, Clone, Copy)]
+enum EvictionHint {
+    IdleTime(Duration),
+    Freq(u8),
+}
+
+pub struct Restore<'a> {
+    key: RBytes<'a>,
+    payload: RBytes<'a>,
+    ttl: Option<Duration>,
+    replace: bool,
+    eviction_hint: Option<EvictionHint>,
+}
+
+impl<'a> Restore<'a> {
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl.replace(ttl);
+        self
+    }
+
+    pub fn replace(mut self) -> Self {
+        self.replace = true;
+        self
+    }
+
+    // Sets the LRU idle time of the restored key.  This replaces any previously given `freq`.
+    pub fn idletime(mut self, idletime: Duration) -> Self {
+        self.eviction_hint.replace(EvictionHint::IdleTime(idletime));
+        self
+    }
+
+    // Sets the LFU frequency counter of the restored key.  This replaces any previously given
+    // `idletime`.
+    pub fn freq(mut self, freq: u8) -> Self {
+        self.eviction_hint.replace(EvictionHint::Freq(freq));
+        self
+    }
+}
+
+impl<'a> StructuredCommand for Restore<'a> {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let ttl = self.ttl.map(|ttl| ttl.as_millis()).unwrap_or(0).to_string();
+        let eviction_hint = self.eviction_hint.map(|hint| match hint {
+            EvictionHint::IdleTime(idletime) => ("IDLETIME", idletime.as_secs().to_string()),
+            EvictionHint::Freq(freq) => ("FREQ", freq.to_string()),
+        });
+
+        let mut count = 4;
+        if self.replace {
+            count += 1;
+        }
+        if eviction_hint.is_some() {
+            count += 2;
+        }
+
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice(count.to_string().as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "RESTORE");
+        insert_bytes_into_vec!(bytes, &self.key);
+        insert_bytes_into_vec!(bytes, ttl);
+        insert_bytes_into_vec!(bytes, &self.payload);
+        if self.replace {
+            insert_bytes_into_vec!(bytes, "REPLACE");
+        }
+        if let Some((name, value)) = eviction_hint {
+            insert_bytes_into_vec!(bytes, name);
+            insert_bytes_into_vec!(bytes, value);
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// The payload should come from a previous DUMP command
+pub fn restore<'a>(key: impl Into<RBytes<'a>>, payload: impl Into<RBytes<'a>>) -> Restore<'a> {
+    Restore {
+        key: key.into(),
+        payload: payload.into(),
+        ttl: None,
+        replace: false,
+        eviction_hint: None,
+    }
+}
+
+pub struct ObjectIdleTime<'a> {
+    key: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for ObjectIdleTime<'a> {
+    // the time since the key was last accessed, or `None` if the key doesn't exist
+    type Output = Option<Duration>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("OBJECT", "IDLETIME", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        let seconds = Option::<u64>::try_from(result)?;
+        Ok(seconds.map(Duration::from_secs))
+    }
+}
+
+pub fn object_idletime<'a>(key: impl Into<RBytes<'a>>) -> ObjectIdleTime<'a> {
+    ObjectIdleTime { key: key.into() }
+}
+
+pub struct ObjectEncoding<'a> {
+    key: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for ObjectEncoding<'a> {
+    // e.g. `int`, `embstr` or `listpack`, or `None` if the key doesn't exist
+    type Output = Option<String>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("OBJECT", "ENCODING", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// How redis is storing a key's value internally.  Strings holding integers report `int`, which can
+// be used to decide whether a value is worth reading as a number.
+pub fn object_encoding<'a>(key: impl Into<RBytes<'a>>) -> ObjectEncoding<'a> {
+    ObjectEncoding { key: key.into() }
+}
+
+// KEYS walks the whole keyspace in one go, blocking every other client until it's finished, so
+// it can't be issued until `i_know_this_blocks` has been called.  SCAN is almost always the
+// better choice outside of tests and debugging.
+pub struct UnacknowledgedKeys<'a> {
+    pattern: RBytes<'a>,
+}
+
+impl<'a> UnacknowledgedKeys<'a> {
+    pub fn i_know_this_blocks(self) -> Keys<'a> {
+        Keys {
+            pattern: self.pattern,
+        }
+    }
+}
+
+pub struct Keys<'a> {
+    pattern: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for Keys<'a> {
+    type Output = Vec<String>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("KEYS", &self.pattern)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        members(result)
+    }
+}
+
+pub fn keys<'a>(pattern: impl Into<RBytes<'a>>) -> UnacknowledgedKeys<'a> {
+    UnacknowledgedKeys {
+        pattern: pattern.into(),
+    }
+}
+
+// One batch of keys from SCAN, along with the cursor to pass to the next call.  The keys are left
+// as bytes, since any key in the database can turn up.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ScanPage {
+    pub cursor: u64,
+    pub keys: Vec<Vec<u8>>,
+}
+
+impl ScanPage {
+    // Redis hands back a cursor of zero once every key has been visited
+    pub fn is_complete(&self) -> bool {
+        self.cursor == 0
+    }
+}
+
+pub struct Scan<'a> {
+    cursor: u64,
+    pattern: Option<RBytes<'a>>,
+    count: Option<usize>,
+}
+
+impl<'a> Scan<'a> {
+    // Only returns keys matching a glob-style pattern.  The filtering happens after each batch is
+    // fetched, so pages can still come back empty before the scan is complete.
+    pub fn matching(mut self, pattern: impl Into<RBytes<'a>>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    // A hint for how many keys redis should look at per call - it may return more or fewer
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+}
+
+impl<'a> StructuredCommand for Scan<'a> {
+    type Output = ScanPage;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let cursor = self.cursor.to_string();
+        match (&self.pattern, self.count) {
+            (Some(pattern), Some(count)) => {
+                resp_bytes!("SCAN", cursor, "MATCH", pattern, "COUNT", count.to_string())
+            }
+            (Some(pattern), None) => resp_bytes!("SCAN", cursor, "MATCH", pattern),
+            (None, Some(count)) => resp_bytes!("SCAN", cursor, "COUNT", count.to_string()),
+            (None, None) => resp_bytes!("SCAN", cursor),
+        }
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Array(parts) if parts.len() == 2 => {
+                let mut parts = parts.into_iter();
+                let cursor = Option::<u64>::try_from(parts.next().unwrap())?
+                    .ok_or(ConversionError::NoConversionTypeMatch { value: None })?;
+                let keys = members(parts.next().unwrap())?;
+                Ok(ScanPage { cursor, keys })
+            }
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: Option::try_from(result)?,
+            }),
+        }
+    }
+}
+
+// Start with a cursor of zero, then pass each page's cursor to the next call until the page
+// `is_complete`.  `SyncClient::scan_iter` does this automatically.
+pub fn scan<'a>(cursor: u64) -> Scan<'a> {
+    Scan {
+        cursor,
+        pattern: None,
+        count: None,
+    }
+}
+
+pub struct Unlink<'a> {
+    keys: Vec<RBytes<'a>>,
+}
+
+impl<'a> StructuredCommand for Unlink<'a> {
+    // the number of keys that existed and were removed
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice((1 + self.keys.len()).to_string().as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "UNLINK");
+        for key in &self.keys {
+            insert_bytes_into_vec!(bytes, key);
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        Option::<u64>::try_from(result)?
+            .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+    }
+}
+
+// Removes keys like DEL, but frees their memory in the background, so that removing large values
+// doesn't block the server
+pub fn unlink<'a>(keys: impl IntoIterator<Item = impl Into<RBytes<'a>>>) -> Unlink<'a> {
+    Unlink {
+        keys: keys.into_iter().map(Into::into).collect(),
+    }
+}
+
+// DEL and EXISTS both take any number of keys and reply with how many of them they counted
+pub struct CountKeys<'a> {
+    command: &'static str,
+    keys: Vec<RBytes<'a>>,
+}
+
+impl<'a> CountKeys<'a> {
+    pub fn and_key(mut self, key: impl Into<RBytes<'a>>) -> Self {
+        self.keys.push(key.into());
+        self
+    }
+}
+
+impl<'a> StructuredCommand for CountKeys<'a> {
+    type Output = u32;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice((1 + self.keys.len()).to_string().as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, self.command);
+        for key in &self.keys {
+            insert_bytes_into_vec!(bytes, key);
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        Option::<u32>::try_from(result)?
+            .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+    }
+}
+
+// Outputs the number of keys that existed and were removed
+pub fn del<'a>(key: impl Into<RBytes<'a>>) -> CountKeys<'a> {
+    CountKeys {
+        command: "DEL",
+        keys: vec![key.into()],
+    }
+}
+
+// Outputs the number of keys that exist.  A key given more than once is counted each time.
+pub fn exists<'a>(key: impl Into<RBytes<'a>>) -> CountKeys<'a> {
+    CountKeys {
+        command: "EXISTS",
+        keys: vec![key.into()],
+    }
+}
+
+pub struct CopyKey<'a> {
+    source: RBytes<'a>,
+    destination: RBytes<'a>,
+    db: Option<u32>,
+    replace: bool,
+}
+
+impl<'a> CopyKey<'a> {
+    // Overwrites the destination key if it already exists, instead of leaving it alone
+    pub fn replace(mut self) -> Self {
+        self.replace = true;
+        self
+    }
+}
+
+impl<'a> StructuredCommand for CopyKey<'a> {
+    // whether the key was copied - false if the destination already existed
+    type Output = bool;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        match (self.db, self.replace) {
+            (Some(db), true) => resp_bytes!(
+                "COPY",
+                &self.source,
+                &self.destination,
+                "DB",
+                db.to_string(),
+                "REPLACE"
+            ),
+            (Some(db), false) => resp_bytes!(
+                "COPY",
+                &self.source,
+                &self.destination,
+                "DB",
+                db.to_string()
+            ),
+            (None, true) => resp_bytes!("COPY", &self.source, &self.destination, "REPLACE"),
+            (None, false) => resp_bytes!("COPY", &self.source, &self.destination),
+        }
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// Copies a key within the current database, which means the destination must be a different key
+pub fn copy<'a>(
+    source: impl Into<RBytes<'a>>,
+    destination: impl Into<RBytes<'a>>,
+) -> Result<CopyKey<'a>, ValidationError> {
+    let source = source.into();
+    let destination = destination.into();
+    if source == destination {
+        return Err(ValidationError::SameSourceAndDestination);
+    }
+
+    Ok(CopyKey {
+        source,
+        destination,
+        db: None,
+        replace: false,
+    })
+}
+
+// Copies a key into another database.  The client doesn't track which database is selected, so
+// copying a key onto itself isn't caught here - redis will reject it if `db` is the current one.
+pub fn copy_to_db<'a>(
+    source: impl Into<RBytes<'a>>,
+    destination: impl Into<RBytes<'a>>,
+    db: u32,
+) -> CopyKey<'a> {
+    CopyKey {
+        source: source.into(),
+        destination: destination.into(),
+        db: Some(db),
+        replace: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pexpire_command_converts_to_bytes_in_milliseconds() {
+        let cmd = pexpire("my-key", Duration::from_secs(2)).unwrap();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*3\r\n\
+             $7\r\nPEXPIRE\r\n\
+             $6\r\nmy-key\r\n\
+             $4\r\n2000\r\n"
+        );
+    }
+
+    #[test]
+    fn expire_rejects_durations_that_round_down_to_zero() {
+        for duration in &[Duration::from_secs(0), Duration::from_millis(999)] {
+            assert_eq!(
+                Some(ValidationError::ZeroExpiry { command: "EXPIRE" }),
+                expire("my-key", *duration).err()
+            );
+        }
+    }
+
+    #[test]
+    fn pexpire_rejects_durations_shorter_than_a_millisecond() {
+        for duration in &[Duration::ZERO, Duration::from_micros(999)] {
+            assert_eq!(
+                Some(ValidationError::ZeroExpiry { command: "PEXPIRE" }),
+                pexpire("my-key", *duration).err()
+            );
+        }
+    }
+
+    #[test]
+    fn expire_rounds_down_to_whole_seconds() {
+        let cmd = expire("my-key", Duration::from_millis(1999)).unwrap();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*3\r\n\
+             $6\r\nEXPIRE\r\n\
+             $6\r\nmy-key\r\n\
+             $1\r\n1\r\n"
+        );
+    }
+
+    #[test]
+    fn pttl_command_distinguishes_missing_keys_from_missing_expiries() {
+        assert_eq!(
+            TtlResult::NoKey,
+            pttl("k")
+                .convert_redis_result(RedisResult::Integer(-2))
+                .unwrap()
+        );
+        assert_eq!(
+            TtlResult::NoExpiry,
+            pttl("k")
+                .convert_redis_result(RedisResult::Integer(-1))
+                .unwrap()
+        );
+        assert_eq!(
+            TtlResult::Expires(Duration::from_millis(1500)),
+            pttl("k")
+                .convert_redis_result(RedisResult::Integer(1500))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn del_and_exists_take_many_keys() {
+        assert_eq!(
+            resp_bytes!("DEL", "a", "b"),
+            del("a").and_key("b").get_bytes()
+        );
+        assert_eq!(
+            resp_bytes!("EXISTS", "a", "a"),
+            exists("a").and_key("a").get_bytes()
+        );
+        assert_eq!(
+            2,
+            exists("a")
+                .and_key("a")
+                .convert_redis_result(RedisResult::Integer(2))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn key_counts_too_large_for_u32_are_out_of_range() {
+        match del("a").convert_redis_result(RedisResult::Integer(1 << 32)) {
+            Err(ConversionError::IntegerOutOfRange { value, .. }) => assert_eq!(1 << 32, value),
+            other => panic!("expected an out of range error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ttl_command_is_in_seconds() {
+        assert_eq!(resp_bytes!("TTL", "k"), ttl("k").get_bytes());
+        assert_eq!(
+            TtlResult::Expires(Duration::from_secs(15)),
+            ttl("k")
+                .convert_redis_result(RedisResult::Integer(15))
+                .unwrap()
+        );
+        assert_eq!(
+            TtlResult::NoKey,
+            ttl("k")
+                .convert_redis_result(RedisResult::Integer(-2))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn restore_command_defaults_to_no_ttl() {
+        let cmd = restore("my-key", b"\x00\x01");
+
+        assert_eq!(
+            cmd.get_bytes(),
+            b"*4\r\n\
+              $7\r\nRESTORE\r\n\
+              $6\r\nmy-key\r\n\
+              $1\r\n0\r\n\
+              $2\r\n\x00\x01\r\n"
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn restore_command_can_set_the_idletime() {
+        let cmd = restore("my-key", "payload")
+            .with_ttl(Duration::from_secs(1))
+            .replace()
+            .idletime(Duration::from_secs(100));
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*7\r\n\
+             $7\r\nRESTORE\r\n\
+             $6\r\nmy-key\r\n\
+             $4\r\n1000\r\n\
+             $7\r\npayload\r\n\
+             $7\r\nREPLACE\r\n\
+             $8\r\nIDLETIME\r\n\
+             $3\r\n100\r\n"
+        );
+    }
+
+    #[test]
+    fn restore_command_can_set_the_freq() {
+        let cmd = restore("my-key", "payload").freq(5);
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*6\r\n\
+             $7\r\nRESTORE\r\n\
+             $6\r\nmy-key\r\n\
+             $1\r\n0\r\n\
+             $7\r\npayload\r\n\
+             $4\r\nFREQ\r\n\
+             $1\r\n5\r\n"
+        );
+    }
+
+    #[test]
+    fn restore_command_only_sends_the_last_eviction_hint() {
+        let cmd = restore("my-key", "payload")
+            .idletime(Duration::from_secs(100))
+            .freq(5);
+
+        assert!(!String::from_utf8(cmd.get_bytes())
+            .unwrap()
+            .contains("IDLETIME"));
+    }
+
+    #[test]
+    fn copy_puts_the_destination_db_before_replace() {
+        let cmd = copy_to_db("source", "dest", 3).replace();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*6\r\n\
+             $4\r\nCOPY\r\n\
+             $6\r\nsource\r\n\
+             $4\r\ndest\r\n\
+             $2\r\nDB\r\n\
+             $1\r\n3\r\n\
+             $7\r\nREPLACE\r\n"
+        );
+    }
+
+    #[test]
+    fn copy_within_a_database_only_sends_the_options_given() {
+        assert_eq!(
+            String::from_utf8(copy("source", "dest").unwrap().get_bytes()).unwrap(),
+            "*3\r\n$4\r\nCOPY\r\n$6\r\nsource\r\n$4\r\ndest\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(copy("source", "dest").unwrap().replace().get_bytes()).unwrap(),
+            "*4\r\n$4\r\nCOPY\r\n$6\r\nsource\r\n$4\r\ndest\r\n$7\r\nREPLACE\r\n"
+        );
+    }
+
+    #[test]
+    fn copy_rejects_copying_a_key_onto_itself() {
+        assert_eq!(
+            Some(ValidationError::SameSourceAndDestination),
+            copy("key", "key").err()
+        );
+        assert!(copy_to_db("key", "key", 1)
+            .get_bytes()
+            .starts_with(b"*5\r\n$4\r\nCOPY"));
+    }
+
+    #[test]
+    fn scan_pages_are_only_complete_with_a_zero_cursor() {
+        let page = |cursor| ScanPage {
+            cursor,
+            keys: Vec::new(),
+        };
+
+        assert!(page(0).is_complete());
+        assert!(!page(1).is_complete());
+        assert!(!page(u64::MAX).is_complete());
+    }
+
+    #[test]
+    fn scan_reads_the_cursor_and_keys() {
+        let result = RedisResult::Array(vec![
+            RedisResult::String(b"17".to_vec()),
+            RedisResult::Array(vec![
+                RedisResult::String(b"first".to_vec()),
+                RedisResult::String(b"second".to_vec()),
+            ]),
+        ]);
+
+        assert_eq!(
+            ScanPage {
+                cursor: 17,
+                keys: vec![b"first".to_vec(), b"second".to_vec()],
+            },
+            scan(0).convert_redis_result(result).unwrap()
+        );
+    }
+
+    #[test]
+    fn scan_sends_match_before_count() {
+        assert_eq!(
+            String::from_utf8(scan(5).matching("user:*").count(100).get_bytes()).unwrap(),
+            "*6\r\n$4\r\nSCAN\r\n$1\r\n5\r\n$5\r\nMATCH\r\n$6\r\nuser:*\r\n\
+             $5\r\nCOUNT\r\n$3\r\n100\r\n"
+        );
+    }
+
+    #[test]
+    fn unlink_sends_every_key() {
+        assert_eq!(
+            String::from_utf8(unlink(vec!["first", "second"]).get_bytes()).unwrap(),
+            "*3\r\n$6\r\nUNLINK\r\n$5\r\nfirst\r\n$6\r\nsecond\r\n"
+        );
+    }
+
+    #[test]
+    fn object_encoding_converts_to_bytes() {
+        assert_eq!(
+            String::from_utf8(object_encoding("key").get_bytes()).unwrap(),
+            "*3\r\n$6\r\nOBJECT\r\n$8\r\nENCODING\r\n$3\r\nkey\r\n"
+        );
+        assert_eq!(
+            Some("int".to_string()),
+            object_encoding("key")
+                .convert_redis_result(RedisResult::String(b"int".to_vec()))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn key_types_are_parsed() {
+        let key_type_of = |name: &str| {
+            key_type("key")
+                .convert_redis_result(RedisResult::String(name.as_bytes().to_vec()))
+                .unwrap()
+        };
+
+        assert_eq!(None, key_type_of("none"));
+        assert_eq!(Some(KeyType::SortedSet), key_type_of("zset"));
+        assert_eq!(
+            Some(KeyType::Other("ReJSON-RL".to_string())),
+            key_type_of("ReJSON-RL")
+        );
+    }
+}
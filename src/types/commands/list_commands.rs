@@ -0,0 +1,193 @@
+use std::convert::{TryFrom, TryInto};
+use std::marker::PhantomData;
+
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use crate::RBytes;
+
+pub struct Push<'a> {
+    command: &'static str,
+    key: RBytes<'a>,
+    values: Vec<RBytes<'a>>,
+}
+
+impl<'a> Push<'a> {
+    pub fn value(mut self, value: impl Into<RBytes<'a>>) -> Self {
+        self.values.push(value.into());
+        self
+    }
+
+    pub fn with_values(mut self, values: impl IntoIterator<Item = impl Into<RBytes<'a>>>) -> Self {
+        self.values.extend(values.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl<'a> StructuredCommand for Push<'a> {
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice((2 + self.values.len()).to_string().as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, self.command);
+        insert_bytes_into_vec!(bytes, &self.key);
+        for value in &self.values {
+            insert_bytes_into_vec!(bytes, value);
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Integer(length @ 0..=std::i64::MAX) => Ok(length as u64),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+pub fn lpush<'a>(key: impl Into<RBytes<'a>>, value: impl Into<RBytes<'a>>) -> Push<'a> {
+    Push {
+        command: "LPUSH",
+        key: key.into(),
+        values: vec![value.into()],
+    }
+}
+
+pub fn rpush<'a>(key: impl Into<RBytes<'a>>, value: impl Into<RBytes<'a>>) -> Push<'a> {
+    Push {
+        command: "RPUSH",
+        key: key.into(),
+        values: vec![value.into()],
+    }
+}
+
+pub struct LRange<'a, T> {
+    key: RBytes<'a>,
+    start: i64,
+    stop: i64,
+    _t: PhantomData<T>,
+}
+
+impl<'a, T> StructuredCommand for LRange<'a, T>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    type Output = Vec<T>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!(
+            "LRANGE",
+            &self.key,
+            self.start.to_string(),
+            self.stop.to_string()
+        )
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            // list elements can never be null, so any nulls here mean something has gone wrong
+            RedisResult::Array(elements) => elements
+                .into_iter()
+                .map(|element| {
+                    element.try_into().and_then(|element: Option<T>| {
+                        element.ok_or(ConversionError::NoConversionTypeMatch { value: None })
+                    })
+                })
+                .collect(),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: Option::try_from(result)?,
+            }),
+        }
+    }
+}
+
+// Like redis, both `start` and `stop` are inclusive, and may be negative to count from the end
+pub fn lrange<'a, T>(key: impl Into<RBytes<'a>>, start: i64, stop: i64) -> LRange<'a, T> {
+    LRange {
+        key: key.into(),
+        start,
+        stop,
+        _t: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorKind, RedisErrorValue};
+
+    #[test]
+    fn push_commands_can_push_multiple_values() {
+        let cmd = rpush("my-list", 1).value("two").with_values(vec![3, 4]);
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*6\r\n\
+             $5\r\nRPUSH\r\n\
+             $7\r\nmy-list\r\n\
+             $1\r\n1\r\n\
+             $3\r\ntwo\r\n\
+             $1\r\n3\r\n\
+             $1\r\n4\r\n"
+        );
+    }
+
+    #[test]
+    fn lrange_command_converts_to_bytes() {
+        let cmd = lrange::<String>("my-list", 0, -1);
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*4\r\n\
+             $6\r\nLRANGE\r\n\
+             $7\r\nmy-list\r\n\
+             $1\r\n0\r\n\
+             $2\r\n-1\r\n"
+        );
+    }
+
+    #[test]
+    fn lrange_command_converts_each_element() {
+        let result = RedisResult::Array(vec![
+            RedisResult::String(b"1".to_vec()),
+            RedisResult::String(b"2".to_vec()),
+        ]);
+
+        assert_eq!(
+            vec![1, 2],
+            lrange::<i64>("my-list", 0, -1)
+                .convert_redis_result(result)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn wrong_type_errors_are_passed_through_as_redis_errors() {
+        let wrong_type = || {
+            RedisResult::Error(RedisErrorValue::new(
+                "WRONGTYPE Operation against a key holding the wrong kind of value",
+            ))
+        };
+
+        match lpush("my-list", 1).convert_redis_result(wrong_type()) {
+            Err(ConversionError::RedisReturnedError { error }) => {
+                assert_eq!(ErrorKind::WrongType, error.error_kind())
+            }
+            other => panic!("expected a WRONGTYPE error, got {:?}", other),
+        }
+        match lrange::<String>("my-list", 0, -1).convert_redis_result(wrong_type()) {
+            Err(ConversionError::RedisReturnedError { error }) => {
+                assert_eq!(ErrorKind::WrongType, error.error_kind())
+            }
+            other => panic!("expected a WRONGTYPE error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use std::convert::{TryFrom, TryInto};
+
+use crate::types::redis_values::{ConversionError, RedisResult};
+
+// Converts each element of a list of members (or keys) on its own.  Members can't be nil, so a nil
+// element is an error rather than a `None`.  RESP3 sends the members of a set as a set reply.
+pub(crate) fn members<T>(result: RedisResult) -> Result<Vec<T>, ConversionError>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    match result {
+        RedisResult::Array(members) | RedisResult::Set(members) => members
+            .into_iter()
+            .map(|member| {
+                member
+                    .try_into()?
+                    .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+            })
+            .collect(),
+        RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+        _ => Err(ConversionError::NoConversionTypeMatch {
+            value: Option::try_from(result)?,
+        }),
+    }
+}
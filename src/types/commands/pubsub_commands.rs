@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use crate::RBytes;
+
+pub struct Publish<'a> {
+    // PUBLISH or SPUBLISH, which only differ in which subscribers they reach
+    command: &'static str,
+    channel: RBytes<'a>,
+    message: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for Publish<'a> {
+    // the number of clients that received the message
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!(self.command, &self.channel, &self.message)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Integer(receivers @ 0..=std::i64::MAX) => Ok(receivers as u64),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+pub fn publish<'a>(channel: impl Into<RBytes<'a>>, message: impl Into<RBytes<'a>>) -> Publish<'a> {
+    Publish {
+        command: "PUBLISH",
+        channel: channel.into(),
+        message: message.into(),
+    }
+}
+
+// Publishes to a shard channel (redis 7+), which only reaches clients that subscribed with
+// SSUBSCRIBE.  In a cluster, the message stays on the shard that owns the channel's slot rather
+// than being broadcast to every node.
+pub fn spublish<'a>(channel: impl Into<RBytes<'a>>, message: impl Into<RBytes<'a>>) -> Publish<'a> {
+    Publish {
+        command: "SPUBLISH",
+        channel: channel.into(),
+        message: message.into(),
+    }
+}
+
+pub struct PubsubChannels<'a> {
+    pattern: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for PubsubChannels<'a> {
+    type Output = Vec<String>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("PUBSUB", "CHANNELS", &self.pattern)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Array(channels) => channels.into_iter().map(TryInto::try_into).collect(),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+// The channels with at least one subscriber that match a glob-style pattern (`*` for all of
+// them).  Pattern and shard subscriptions aren't included.
+pub fn pubsub_channels<'a>(pattern: impl Into<RBytes<'a>>) -> PubsubChannels<'a> {
+    PubsubChannels {
+        pattern: pattern.into(),
+    }
+}
+
+pub struct PubsubNumsub<'a> {
+    channels: Vec<RBytes<'a>>,
+}
+
+fn subscriber_count(result: RedisResult) -> Result<u32, ConversionError> {
+    Option::<u32>::try_from(result)?.ok_or(ConversionError::NoConversionTypeMatch { value: None })
+}
+
+impl<'a> StructuredCommand for PubsubNumsub<'a> {
+    // subscribers per channel, including channels nobody is subscribed to
+    type Output = HashMap<String, u32>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice((2 + self.channels.len()).to_string().as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "PUBSUB");
+        insert_bytes_into_vec!(bytes, "NUMSUB");
+        for channel in &self.channels {
+            insert_bytes_into_vec!(bytes, channel);
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            // RESP2 sends the channels and counts as one flat array, RESP3 as a map
+            RedisResult::Array(elements) => {
+                let mut elements = elements.into_iter();
+                let mut counts = HashMap::new();
+                while let Some(channel) = elements.next() {
+                    let count = elements
+                        .next()
+                        .ok_or(ConversionError::NoConversionTypeMatch { value: None })?;
+                    counts.insert(channel.try_into()?, subscriber_count(count)?);
+                }
+                Ok(counts)
+            }
+            RedisResult::Map(pairs) => pairs
+                .into_iter()
+                .map(|(channel, count)| Ok((channel.try_into()?, subscriber_count(count)?)))
+                .collect(),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+// The number of subscribers to each channel.  As with `pubsub_channels`, pattern subscribers
+// aren't counted.
+pub fn pubsub_numsub<'a>(
+    channels: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+) -> PubsubNumsub<'a> {
+    PubsubNumsub {
+        channels: channels.into_iter().map(Into::into).collect(),
+    }
+}
+
+pub struct PubsubNumpat;
+
+impl StructuredCommand for PubsubNumpat {
+    // the number of patterns subscribed to across every client
+    type Output = u32;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("PUBSUB", "NUMPAT")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        subscriber_count(result)
+    }
+}
+
+pub fn pubsub_numpat() -> PubsubNumpat {
+    PubsubNumpat
+}
+
+// (UN)SUBSCRIBE replies with one confirmation per channel, rather than a single reply, so it can't
+// be issued like a normal command - see `Subscription` for how the replies are read.
+pub(crate) fn subscription_command(command: &'static str, channels: &[RBytes]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.push(b'*');
+    bytes.extend_from_slice((1 + channels.len()).to_string().as_bytes());
+    bytes.extend_from_slice(b"\r\n");
+
+    insert_bytes_into_vec!(bytes, command);
+    for channel in channels {
+        insert_bytes_into_vec!(bytes, channel);
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn publish_command_converts_to_bytes() {
+        assert_eq!(
+            String::from_utf8(publish("news", "hello").get_bytes()).unwrap(),
+            "*3\r\n\
+             $7\r\nPUBLISH\r\n\
+             $4\r\nnews\r\n\
+             $5\r\nhello\r\n"
+        );
+    }
+
+    #[test]
+    fn spublish_command_converts_to_bytes() {
+        assert_eq!(
+            String::from_utf8(spublish("news", "hello").get_bytes()).unwrap(),
+            "*3\r\n\
+             $8\r\nSPUBLISH\r\n\
+             $4\r\nnews\r\n\
+             $5\r\nhello\r\n"
+        );
+    }
+
+    #[test]
+    fn pubsub_numsub_sends_every_channel() {
+        assert_eq!(
+            String::from_utf8(pubsub_numsub(vec!["news", "sport"]).get_bytes()).unwrap(),
+            "*4\r\n\
+             $6\r\nPUBSUB\r\n\
+             $6\r\nNUMSUB\r\n\
+             $4\r\nnews\r\n\
+             $5\r\nsport\r\n"
+        );
+    }
+
+    #[test]
+    fn pubsub_numsub_reads_flat_pairs_and_maps() {
+        let mut expected = HashMap::new();
+        expected.insert("news".to_string(), 2);
+        expected.insert("sport".to_string(), 0);
+
+        let flat = RedisResult::Array(vec![
+            RedisResult::String(b"news".to_vec()),
+            RedisResult::Integer(2),
+            RedisResult::String(b"sport".to_vec()),
+            RedisResult::Integer(0),
+        ]);
+        assert_eq!(
+            expected,
+            pubsub_numsub(vec!["news", "sport"])
+                .convert_redis_result(flat)
+                .unwrap()
+        );
+
+        let map = RedisResult::Map(vec![
+            (
+                RedisResult::String(b"news".to_vec()),
+                RedisResult::Integer(2),
+            ),
+            (
+                RedisResult::String(b"sport".to_vec()),
+                RedisResult::Integer(0),
+            ),
+        ]);
+        assert_eq!(
+            expected,
+            pubsub_numsub(vec!["news", "sport"])
+                .convert_redis_result(map)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn all_channels_are_sent_in_a_single_subscribe_command() {
+        assert_eq!(
+            String::from_utf8(subscription_command(
+                "SUBSCRIBE",
+                &["first".into(), "second".into()]
+            ))
+            .unwrap(),
+            "*3\r\n\
+             $9\r\nSUBSCRIBE\r\n\
+             $5\r\nfirst\r\n\
+             $6\r\nsecond\r\n"
+        );
+    }
+}
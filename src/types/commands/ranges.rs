@@ -0,0 +1,92 @@
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+// Redis ranges include both of their ends, so an exclusive Rust range stops one index earlier
+pub trait RangeWithBounds {
+    fn into_bounds(self) -> (i64, i64);
+}
+
+impl RangeWithBounds for Range<i64> {
+    fn into_bounds(self) -> (i64, i64) {
+        (self.start, self.end.saturating_sub(1))
+    }
+}
+
+impl RangeWithBounds for RangeInclusive<i64> {
+    fn into_bounds(self) -> (i64, i64) {
+        self.into_inner()
+    }
+}
+
+impl RangeWithBounds for RangeTo<i64> {
+    fn into_bounds(self) -> (i64, i64) {
+        RangeWithBounds::into_bounds(0..self.end)
+    }
+}
+
+impl RangeWithBounds for RangeToInclusive<i64> {
+    fn into_bounds(self) -> (i64, i64) {
+        RangeWithBounds::into_bounds(0..=self.end)
+    }
+}
+
+// In redis, -1 refers to the last element, so this covers the whole value
+impl RangeWithBounds for RangeFull {
+    fn into_bounds(self) -> (i64, i64) {
+        (0, -1)
+    }
+}
+
+pub trait RangeWithLowerBound {
+    fn into_bounds(self) -> (i64, Option<i64>);
+}
+
+impl<T> RangeWithLowerBound for T
+where
+    T: RangeWithBounds,
+{
+    fn into_bounds(self) -> (i64, Option<i64>) {
+        let (lower, upper) = self.into_bounds();
+        (lower, Some(upper))
+    }
+}
+
+impl RangeWithLowerBound for RangeFrom<i64> {
+    fn into_bounds(self) -> (i64, Option<i64>) {
+        (self.start, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_ranges_produce_the_expected_bounds() {
+        assert_eq!((2, 4), RangeWithBounds::into_bounds(2..5));
+        assert_eq!((2, 5), RangeWithBounds::into_bounds(2..=5));
+    }
+
+    #[test]
+    fn ranges_without_a_start_begin_at_zero() {
+        assert_eq!((0, 4), RangeWithBounds::into_bounds(..5));
+        assert_eq!((0, 5), RangeWithBounds::into_bounds(..=5));
+    }
+
+    #[test]
+    fn range_ends_at_the_limits_of_i64_do_not_overflow() {
+        assert_eq!((0, i64::MIN), RangeWithBounds::into_bounds(..=i64::MIN));
+        assert_eq!((0, i64::MIN), RangeWithBounds::into_bounds(..i64::MIN));
+        assert_eq!((0, i64::MAX), RangeWithBounds::into_bounds(..=i64::MAX));
+    }
+
+    #[test]
+    fn full_range_covers_the_whole_value() {
+        assert_eq!((0, -1), RangeWithBounds::into_bounds(..));
+        assert_eq!((0, Some(-1)), RangeWithLowerBound::into_bounds(..));
+    }
+
+    #[test]
+    fn ranges_without_an_end_have_no_upper_bound() {
+        assert_eq!((3, None), RangeWithLowerBound::into_bounds(3..));
+    }
+}
@@ -0,0 +1,89 @@
+use std::convert::TryInto;
+use std::marker::PhantomData;
+
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use crate::RBytes;
+
+pub struct Eval<'a, T> {
+    script: RBytes<'a>,
+    keys: Vec<RBytes<'a>>,
+    args: Vec<RBytes<'a>>,
+    _t: PhantomData<T>,
+}
+
+impl<'a, T> Eval<'a, T> {
+    pub fn key(mut self, key: impl Into<RBytes<'a>>) -> Self {
+        self.keys.push(key.into());
+        self
+    }
+
+    pub fn arg(mut self, arg: impl Into<RBytes<'a>>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+}
+
+impl<'a, T> StructuredCommand for Eval<'a, T>
+where
+    RedisResult: TryInto<T, Error = ConversionError>,
+{
+    type Output = T;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice(
+            (3 + self.keys.len() + self.args.len())
+                .to_string()
+                .as_bytes(),
+        );
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "EVAL");
+        insert_bytes_into_vec!(bytes, &self.script);
+        insert_bytes_into_vec!(bytes, self.keys.len().to_string());
+        for key in &self.keys {
+            insert_bytes_into_vec!(bytes, key);
+        }
+        for arg in &self.args {
+            insert_bytes_into_vec!(bytes, arg);
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+pub fn eval<'a, T>(script: impl Into<RBytes<'a>>) -> Eval<'a, T> {
+    Eval {
+        script: script.into(),
+        keys: Vec::new(),
+        args: Vec::new(),
+        _t: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval_command_puts_the_key_count_before_keys_and_args() {
+        let cmd = eval::<i64>("return 1").key("k1").key("k2").arg("a1");
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*6\r\n\
+             $4\r\nEVAL\r\n\
+             $8\r\nreturn 1\r\n\
+             $1\r\n2\r\n\
+             $2\r\nk1\r\n\
+             $2\r\nk2\r\n\
+             $2\r\na1\r\n"
+        );
+    }
+}
@@ -0,0 +1,985 @@
+use super::fields::split_fields;
+use crate::types::redis_values::ConversionError;
+use crate::{Command, RBytes, RedisResult, StructuredCommand, ValidationError};
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub struct Time;
+
+impl StructuredCommand for Time {
+    type Output = SystemTime;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("TIME")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            // redis returns the unix timestamp in seconds, and the microseconds already elapsed
+            // in the current second, as two separate (string-encoded) integers
+            RedisResult::Array(parts) if parts.len() == 2 => {
+                let mut parts = parts.into_iter();
+                let seconds: Option<u64> = parts.next().unwrap().try_into()?;
+                let micros: Option<u64> = parts.next().unwrap().try_into()?;
+
+                match (seconds, micros) {
+                    (Some(seconds), Some(micros)) => Ok(UNIX_EPOCH
+                        + Duration::from_secs(seconds)
+                        + Duration::from_micros(micros)),
+                    _ => Err(ConversionError::NoConversionTypeMatch { value: None }),
+                }
+            }
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+pub fn time() -> Time {
+    Time
+}
+
+pub struct Wait {
+    numreplicas: u32,
+    timeout: Duration,
+}
+
+impl StructuredCommand for Wait {
+    // the number of replicas that acknowledged all previous writes on this connection
+    type Output = u32;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!(
+            "WAIT",
+            self.numreplicas.to_string(),
+            self.timeout.as_millis().to_string()
+        )
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        Option::<u32>::try_from(result)?
+            .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+    }
+}
+
+// A timeout of zero means that redis will block forever until enough acknowledgements arrive
+pub fn wait(numreplicas: u32, timeout: Duration) -> Wait {
+    Wait {
+        numreplicas,
+        timeout,
+    }
+}
+
+pub struct WaitAof {
+    numlocal: u32,
+    numreplicas: u32,
+    timeout: Duration,
+}
+
+impl StructuredCommand for WaitAof {
+    // the number of local redis instances, and the number of replicas, that acknowledged the
+    // writes being fsynced to the AOF
+    type Output = (u32, u32);
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!(
+            "WAITAOF",
+            self.numlocal.to_string(),
+            self.numreplicas.to_string(),
+            self.timeout.as_millis().to_string()
+        )
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Array(parts) if parts.len() == 2 => {
+                let mut parts = parts.into_iter();
+                let local: Option<u32> = parts.next().unwrap().try_into()?;
+                let replicas: Option<u32> = parts.next().unwrap().try_into()?;
+
+                match (local, replicas) {
+                    (Some(local), Some(replicas)) => Ok((local, replicas)),
+                    _ => Err(ConversionError::NoConversionTypeMatch { value: None }),
+                }
+            }
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+// A timeout of zero means that redis will block forever until enough acknowledgements arrive
+pub fn waitaof(numlocal: u32, numreplicas: u32, timeout: Duration) -> WaitAof {
+    WaitAof {
+        numlocal,
+        numreplicas,
+        timeout,
+    }
+}
+
+pub struct CommandGetKeys<'c, 'a> {
+    command: &'c Command<'a>,
+}
+
+impl<'c, 'a> StructuredCommand for CommandGetKeys<'c, 'a> {
+    type Output = Vec<String>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let args = self.command.args();
+
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice((3 + args.len()).to_string().as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "COMMAND");
+        insert_bytes_into_vec!(bytes, "GETKEYS");
+        insert_bytes_into_vec!(bytes, self.command.name());
+        for arg in args {
+            insert_bytes_into_vec!(bytes, arg);
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Array(keys) => keys.into_iter().map(TryInto::try_into).collect(),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+// Asks the server which of the command's arguments are keys, which works even for commands that
+// this library doesn't know anything about
+pub fn command_getkeys<'c, 'a>(command: &'c Command<'a>) -> CommandGetKeys<'c, 'a> {
+    CommandGetKeys { command }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CommandInfo {
+    pub name: String,
+    // positive for an exact number of arguments, or negative for a minimum number of arguments -
+    // either way, the command name itself is counted as one of the arguments
+    pub arity: i64,
+    // where the command's key arguments are, counting the command name as position 0.  A
+    // negative `last_key` counts back from the end, and a `first_key` of 0 means either that the
+    // command has no keys or that they can't be found this way (e.g. EVAL's `numkeys`).
+    pub first_key: i64,
+    pub last_key: i64,
+    pub key_step: i64,
+}
+
+impl CommandInfo {
+    // Whether the command can be called with `args` arguments, not counting the command name
+    pub fn accepts(&self, args: usize) -> bool {
+        let given = args as i64 + 1;
+        if self.arity >= 0 {
+            given == self.arity
+        } else {
+            given >= -self.arity
+        }
+    }
+
+    // The positions of the key arguments among `args` arguments (not counting the command name,
+    // so the first argument is at position 0)
+    pub fn key_positions(&self, args: usize) -> Vec<usize> {
+        if self.first_key <= 0 {
+            return Vec::new();
+        }
+
+        let last = if self.last_key < 0 {
+            args as i64 + 1 + self.last_key
+        } else {
+            self.last_key.min(args as i64)
+        };
+        (self.first_key..=last)
+            .step_by(self.key_step.max(1) as usize)
+            .map(|position| position as usize - 1)
+            .collect()
+    }
+}
+
+pub struct CommandInfoCommand<'a> {
+    name: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for CommandInfoCommand<'a> {
+    // `None` if redis doesn't know about the command
+    type Output = Option<CommandInfo>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("COMMAND", "INFO", &self.name)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Array(commands) if commands.len() == 1 => {
+                match commands.into_iter().next().unwrap() {
+                    RedisResult::Array(details) if details.len() >= 2 => {
+                        let mut details = details.into_iter();
+                        let name: String = details.next().unwrap().try_into()?;
+                        let arity: i64 = details.next().unwrap().try_into()?;
+                        // the flags come before the key positions
+                        details.next();
+                        let mut key_spec = || -> Result<i64, ConversionError> {
+                            details.next().map_or(Ok(0), TryInto::try_into)
+                        };
+                        Ok(Some(CommandInfo {
+                            name,
+                            arity,
+                            first_key: key_spec()?,
+                            last_key: key_spec()?,
+                            key_step: key_spec()?,
+                        }))
+                    }
+                    RedisResult::Null => Ok(None),
+                    other => Err(ConversionError::NoConversionTypeMatch {
+                        value: other.try_into()?,
+                    }),
+                }
+            }
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+pub fn command_info<'a>(name: impl Into<RBytes<'a>>) -> CommandInfoCommand<'a> {
+    CommandInfoCommand { name: name.into() }
+}
+
+pub struct CommandCount;
+
+impl StructuredCommand for CommandCount {
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("COMMAND", "COUNT")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        Option::<u64>::try_from(result)?
+            .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+    }
+}
+
+// The number of commands that the server supports, including ones added by modules
+pub fn command_count() -> CommandCount {
+    CommandCount
+}
+
+pub struct CommandList;
+
+impl StructuredCommand for CommandList {
+    type Output = Vec<String>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("COMMAND", "LIST")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Array(names) => names.into_iter().map(TryInto::try_into).collect(),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+// The (lowercase) names of every command that the server supports, for checking whether a
+// feature is available before relying on it.  Needs redis 7 or later.
+pub fn command_list() -> CommandList {
+    CommandList
+}
+
+pub struct ConfigRewrite;
+
+impl StructuredCommand for ConfigRewrite {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("CONFIG", "REWRITE")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.expect_ok()
+    }
+}
+
+// Writes the server's current configuration back to its config file.  Redis replies with an
+// error if it was started without one.
+pub fn config_rewrite() -> ConfigRewrite {
+    ConfigRewrite
+}
+
+pub struct ConfigResetStat;
+
+impl StructuredCommand for ConfigResetStat {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("CONFIG", "RESETSTAT")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.expect_ok()
+    }
+}
+
+// Resets the statistics reported by INFO, such as the keyspace hits and the command counts
+pub fn config_resetstat() -> ConfigResetStat {
+    ConfigResetStat
+}
+
+pub struct DebugChangeReplId;
+
+impl StructuredCommand for DebugChangeReplId {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("DEBUG", "CHANGE-REPL-ID")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.expect_ok()
+    }
+}
+
+// Changes the replication ID of the server, forcing replicas into a full resync.  Like DEBUG
+// OBJECT, this needs `enable-debug-command` on redis 7+.
+pub fn debug_change_repl_id() -> DebugChangeReplId {
+    DebugChangeReplId
+}
+
+pub struct DebugReload;
+
+impl StructuredCommand for DebugReload {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("DEBUG", "RELOAD")
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.expect_ok()
+    }
+}
+
+// Saves the dataset to an RDB file and loads it straight back in, which is a quick way to check
+// that data survives persistence.  This blocks the server while it runs, and needs
+// `enable-debug-command` on redis 7+.
+pub fn debug_reload() -> DebugReload {
+    DebugReload
+}
+
+pub struct SwapDb {
+    first: u32,
+    second: u32,
+}
+
+impl StructuredCommand for SwapDb {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("SWAPDB", self.first.to_string(), self.second.to_string())
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.expect_ok()
+    }
+}
+
+// Atomically swaps two databases, so that clients connected to one immediately see the other's
+// data.  This makes it possible to rebuild a cache in a spare database and then switch it in.
+pub fn swapdb(first: u32, second: u32) -> SwapDb {
+    SwapDb { first, second }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DebugObjectParseError {
+    field: String,
+}
+
+impl fmt::Display for DebugObjectParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "missing or invalid debug object field `{}`", self.field)
+    }
+}
+
+impl Error for DebugObjectParseError {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DebugObject {
+    pub refcount: u64,
+    pub encoding: String,
+    pub serialized_length: u64,
+    pub lru_seconds_idle: Duration,
+    // these are only present for lists that are encoded as quicklists
+    pub ql_nodes: Option<u64>,
+    pub ql_avg_node: Option<f64>,
+    fields: HashMap<String, String>,
+}
+
+impl DebugObject {
+    // Any field from the raw reply, including the ones that aren't parsed into the struct
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+impl FromStr for DebugObject {
+    type Err = DebugObjectParseError;
+
+    // Parses the space-separated `key:value` pairs returned by DEBUG OBJECT
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields = split_fields(line, ':');
+
+        fn optional<N: FromStr>(
+            fields: &HashMap<String, String>,
+            field: &str,
+        ) -> Result<Option<N>, DebugObjectParseError> {
+            fields
+                .get(field)
+                .map(|value| {
+                    value.parse().map_err(|_| DebugObjectParseError {
+                        field: field.to_string(),
+                    })
+                })
+                .transpose()
+        }
+
+        fn required<N: FromStr>(
+            fields: &HashMap<String, String>,
+            field: &str,
+        ) -> Result<N, DebugObjectParseError> {
+            optional(fields, field)?.ok_or_else(|| DebugObjectParseError {
+                field: field.to_string(),
+            })
+        }
+
+        Ok(DebugObject {
+            refcount: required(&fields, "refcount")?,
+            encoding: required(&fields, "encoding")?,
+            serialized_length: required(&fields, "serializedlength")?,
+            lru_seconds_idle: Duration::from_secs(required(&fields, "lru_seconds_idle")?),
+            ql_nodes: optional(&fields, "ql_nodes")?,
+            ql_avg_node: optional(&fields, "ql_avg_node")?,
+            fields,
+        })
+    }
+}
+
+pub struct DebugObjectCommand<'a> {
+    key: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for DebugObjectCommand<'a> {
+    type Output = DebugObject;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("DEBUG", "OBJECT", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        let text: String = result.try_into()?;
+        text.parse()
+            .map_err(|err| ConversionError::CannotParseStringResponse {
+                error: Box::new(err),
+                text: text.clone(),
+            })
+    }
+}
+
+// Since redis 7, DEBUG is disabled unless the server is started with `enable-debug-command`
+pub fn debug_object<'a>(key: impl Into<RBytes<'a>>) -> DebugObjectCommand<'a> {
+    DebugObjectCommand { key: key.into() }
+}
+
+pub struct Lolwut {
+    version: Option<u32>,
+}
+
+impl Lolwut {
+    // Asks for the art from a particular version of redis, instead of the server's own version
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+        self
+    }
+}
+
+impl StructuredCommand for Lolwut {
+    // some generated art, followed by the server's version
+    type Output = String;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        match self.version {
+            Some(version) => resp_bytes!("LOLWUT", "VERSION", version.to_string()),
+            None => resp_bytes!("LOLWUT"),
+        }
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        Option::<String>::try_from(result)?
+            .ok_or(ConversionError::NoConversionTypeMatch { value: None })
+    }
+}
+
+pub fn lolwut() -> Lolwut {
+    Lolwut { version: None }
+}
+
+pub struct Failover<'a> {
+    to: Option<(RBytes<'a>, u16)>,
+    force: bool,
+    abort: bool,
+    timeout: Option<Duration>,
+}
+
+impl<'a> Failover<'a> {
+    // Fails over to a specific replica, rather than letting the master choose one
+    pub fn to(mut self, host: impl Into<RBytes<'a>>, port: u16) -> Result<Self, ValidationError> {
+        if self.abort {
+            return Err(ValidationError::IncompatibleFlags {
+                first: "ABORT",
+                second: "TO",
+            });
+        }
+        self.to = Some((host.into(), port));
+        Ok(self)
+    }
+
+    // Fails over even if the replica hasn't caught up by the time the timeout runs out.  Redis
+    // only allows this along with a target and a timeout, so both need to be set first.
+    pub fn force(mut self) -> Result<Self, ValidationError> {
+        if self.abort {
+            return Err(ValidationError::IncompatibleFlags {
+                first: "ABORT",
+                second: "FORCE",
+            });
+        }
+        for (requires, set) in &[
+            ("TO", self.to.is_some()),
+            ("TIMEOUT", self.timeout.is_some()),
+        ] {
+            if !set {
+                return Err(ValidationError::MissingRequiredFlag {
+                    flag: "FORCE",
+                    requires,
+                });
+            }
+        }
+        self.force = true;
+        Ok(self)
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Result<Self, ValidationError> {
+        if self.abort {
+            return Err(ValidationError::IncompatibleFlags {
+                first: "ABORT",
+                second: "TIMEOUT",
+            });
+        }
+        self.timeout = Some(timeout);
+        Ok(self)
+    }
+
+    // Stops a failover that's already in progress, which can't be combined with any other option
+    pub fn abort(mut self) -> Result<Self, ValidationError> {
+        let set = [
+            ("TO", self.to.is_some()),
+            ("FORCE", self.force),
+            ("TIMEOUT", self.timeout.is_some()),
+        ];
+        if let Some((other, _)) = set.iter().find(|(_, set)| *set) {
+            return Err(ValidationError::IncompatibleFlags {
+                first: other,
+                second: "ABORT",
+            });
+        }
+        self.abort = true;
+        Ok(self)
+    }
+}
+
+impl<'a> StructuredCommand for Failover<'a> {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let to_len = match self.to {
+            Some(_) if self.force => 4,
+            Some(_) => 3,
+            None => 0,
+        };
+        let timeout_len = if self.timeout.is_some() { 2 } else { 0 };
+        let abort_len = if self.abort { 1 } else { 0 };
+
+        let mut bytes = vec![b'*'];
+        bytes.extend_from_slice(
+            (1 + to_len + timeout_len + abort_len)
+                .to_string()
+                .as_bytes(),
+        );
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "FAILOVER");
+        if let Some((host, port)) = &self.to {
+            insert_bytes_into_vec!(bytes, "TO");
+            insert_bytes_into_vec!(bytes, host);
+            insert_bytes_into_vec!(bytes, port.to_string());
+            if self.force {
+                insert_bytes_into_vec!(bytes, "FORCE");
+            }
+        }
+        if self.abort {
+            insert_bytes_into_vec!(bytes, "ABORT");
+        }
+        if let Some(timeout) = self.timeout {
+            insert_bytes_into_vec!(bytes, "TIMEOUT");
+            insert_bytes_into_vec!(bytes, timeout.as_millis().to_string());
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.expect_ok()
+    }
+}
+
+// Hands the master role over to one of this server's replicas.  The reply only means that the
+// failover has started - check ROLE or INFO to see when it's finished.
+pub fn failover<'a>() -> Failover<'a> {
+    Failover {
+        to: None,
+        force: false,
+        abort: false,
+        timeout: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_command_converts_to_bytes() {
+        assert_eq!(
+            String::from_utf8(time().get_bytes()).unwrap(),
+            "*1\r\n$4\r\nTIME\r\n"
+        );
+    }
+
+    #[test]
+    fn time_command_combines_seconds_and_microseconds() {
+        let result = RedisResult::Array(vec![
+            RedisResult::String(b"1560000000".to_vec()),
+            RedisResult::String(b"250000".to_vec()),
+        ]);
+
+        assert_eq!(
+            UNIX_EPOCH + Duration::from_millis(1_560_000_000_250),
+            time().convert_redis_result(result).unwrap()
+        );
+    }
+
+    #[test]
+    fn command_list_reads_every_command_name() {
+        let result = RedisResult::Array(vec![
+            RedisResult::String(b"get".to_vec()),
+            RedisResult::String(b"set".to_vec()),
+        ]);
+
+        assert_eq!(
+            vec!["get".to_string(), "set".to_string()],
+            command_list().convert_redis_result(result).unwrap()
+        );
+    }
+
+    #[test]
+    fn command_info_reads_the_name_and_arity() {
+        let result = RedisResult::Array(vec![RedisResult::Array(vec![
+            RedisResult::String(b"get".to_vec()),
+            RedisResult::Integer(2),
+            RedisResult::Array(vec![RedisResult::String(b"readonly".to_vec())]),
+            RedisResult::Integer(1),
+            RedisResult::Integer(1),
+            RedisResult::Integer(1),
+        ])]);
+
+        assert_eq!(
+            Some(CommandInfo {
+                name: "get".to_string(),
+                arity: 2,
+                first_key: 1,
+                last_key: 1,
+                key_step: 1,
+            }),
+            command_info("GET").convert_redis_result(result).unwrap()
+        );
+        assert_eq!(
+            None,
+            command_info("NOPE")
+                .convert_redis_result(RedisResult::Array(vec![RedisResult::Null]))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn command_arity_can_be_exact_or_a_minimum() {
+        let get = CommandInfo {
+            name: "get".to_string(),
+            arity: 2,
+            first_key: 1,
+            last_key: 1,
+            key_step: 1,
+        };
+        assert!(!get.accepts(0));
+        assert!(get.accepts(1));
+        assert!(!get.accepts(2));
+
+        let mget = CommandInfo {
+            name: "mget".to_string(),
+            arity: -2,
+            first_key: 1,
+            last_key: -1,
+            key_step: 1,
+        };
+        assert!(!mget.accepts(0));
+        assert!(mget.accepts(1));
+        assert!(mget.accepts(5));
+    }
+
+    #[test]
+    fn key_positions_follow_the_key_spec() {
+        let info = |first_key, last_key, key_step| CommandInfo {
+            name: "cmd".to_string(),
+            arity: -2,
+            first_key,
+            last_key,
+            key_step,
+        };
+
+        // GET key
+        assert_eq!(vec![0], info(1, 1, 1).key_positions(1));
+        // MGET key [key ...]
+        assert_eq!(vec![0, 1, 2], info(1, -1, 1).key_positions(3));
+        // MSET key value [key value ...]
+        assert_eq!(vec![0, 2], info(1, -1, 2).key_positions(4));
+        // EVAL script numkeys [key ...]
+        assert_eq!(Vec::<usize>::new(), info(0, 0, 0).key_positions(4));
+    }
+
+    #[test]
+    fn wait_command_converts_to_bytes() {
+        let cmd = wait(2, Duration::from_millis(500));
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*3\r\n\
+             $4\r\nWAIT\r\n\
+             $1\r\n2\r\n\
+             $3\r\n500\r\n"
+        );
+    }
+
+    #[test]
+    fn waitaof_command_converts_to_bytes() {
+        let cmd = waitaof(1, 2, Duration::from_secs(1));
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*4\r\n\
+             $7\r\nWAITAOF\r\n\
+             $1\r\n1\r\n\
+             $1\r\n2\r\n\
+             $4\r\n1000\r\n"
+        );
+    }
+
+    #[test]
+    fn waitaof_command_returns_local_and_replica_acknowledgements() {
+        let result = RedisResult::Array(vec![RedisResult::Integer(1), RedisResult::Integer(0)]);
+
+        assert_eq!(
+            (1, 0),
+            waitaof(1, 0, Duration::from_secs(1))
+                .convert_redis_result(result)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn command_getkeys_command_wraps_the_whole_inner_command() {
+        let inner = Command::cmd("SET").with_arg("foo").with_arg("bar");
+        let cmd = command_getkeys(&inner);
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*5\r\n\
+             $7\r\nCOMMAND\r\n\
+             $7\r\nGETKEYS\r\n\
+             $3\r\nSET\r\n\
+             $3\r\nfoo\r\n\
+             $3\r\nbar\r\n"
+        );
+    }
+
+    #[test]
+    fn debug_object_reply_is_parsed_into_typed_fields() {
+        let object: DebugObject = "Value at:0x7f2a1c00e0c0 refcount:1 encoding:listpack \
+                                   serializedlength:12 lru:1234 lru_seconds_idle:3"
+            .parse()
+            .unwrap();
+
+        assert_eq!(1, object.refcount);
+        assert_eq!("listpack", object.encoding);
+        assert_eq!(12, object.serialized_length);
+        assert_eq!(Duration::from_secs(3), object.lru_seconds_idle);
+        assert_eq!(None, object.ql_nodes);
+        assert_eq!(None, object.ql_avg_node);
+        assert_eq!(Some("0x7f2a1c00e0c0"), object.field("at"));
+    }
+
+    #[test]
+    fn debug_object_reply_includes_quicklist_fields_when_present() {
+        let object: DebugObject = "Value at:0x7f2a1c00e0c0 refcount:1 encoding:quicklist \
+                                   serializedlength:3000 lru:1234 lru_seconds_idle:0 \
+                                   ql_nodes:4 ql_avg_node:250.00 ql_listpack_max:-2 \
+                                   ql_compressed:0 ql_uncompressed_size:12000"
+            .parse()
+            .unwrap();
+
+        assert_eq!(Some(4), object.ql_nodes);
+        assert_eq!(Some(250.0), object.ql_avg_node);
+    }
+
+    #[test]
+    fn debug_object_reply_with_invalid_fields_is_an_error() {
+        let error = "refcount:1 encoding:quicklist serializedlength:1 lru_seconds_idle:0 \
+                     ql_nodes:many"
+            .parse::<DebugObject>()
+            .unwrap_err();
+        assert_eq!("ql_nodes", error.field);
+    }
+
+    #[test]
+    fn lolwut_only_sends_a_version_if_one_is_given() {
+        assert_eq!(
+            String::from_utf8(lolwut().get_bytes()).unwrap(),
+            "*1\r\n$6\r\nLOLWUT\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(lolwut().version(5).get_bytes()).unwrap(),
+            "*3\r\n$6\r\nLOLWUT\r\n$7\r\nVERSION\r\n$1\r\n5\r\n"
+        );
+    }
+
+    #[test]
+    fn failover_frames_each_form() {
+        let bytes = |cmd: Failover| String::from_utf8(cmd.get_bytes()).unwrap();
+
+        assert_eq!(bytes(failover()), "*1\r\n$8\r\nFAILOVER\r\n");
+        assert_eq!(
+            bytes(failover().abort().unwrap()),
+            "*2\r\n$8\r\nFAILOVER\r\n$5\r\nABORT\r\n"
+        );
+        assert_eq!(
+            bytes(failover().timeout(Duration::from_secs(5)).unwrap()),
+            "*3\r\n$8\r\nFAILOVER\r\n$7\r\nTIMEOUT\r\n$4\r\n5000\r\n"
+        );
+        assert_eq!(
+            bytes(failover().to("10.0.0.2", 6380).unwrap()),
+            "*4\r\n$8\r\nFAILOVER\r\n$2\r\nTO\r\n$8\r\n10.0.0.2\r\n$4\r\n6380\r\n"
+        );
+        assert_eq!(
+            bytes(
+                failover()
+                    .to("10.0.0.2", 6380)
+                    .unwrap()
+                    .timeout(Duration::from_millis(500))
+                    .unwrap()
+                    .force()
+                    .unwrap()
+            ),
+            "*7\r\n$8\r\nFAILOVER\r\n\
+             $2\r\nTO\r\n$8\r\n10.0.0.2\r\n$4\r\n6380\r\n$5\r\nFORCE\r\n\
+             $7\r\nTIMEOUT\r\n$3\r\n500\r\n"
+        );
+    }
+
+    #[test]
+    fn failover_rejects_abort_with_other_options() {
+        assert_eq!(
+            Some(ValidationError::IncompatibleFlags {
+                first: "TO",
+                second: "ABORT"
+            }),
+            failover().to("host", 6379).unwrap().abort().err()
+        );
+        assert_eq!(
+            Some(ValidationError::IncompatibleFlags {
+                first: "ABORT",
+                second: "TIMEOUT"
+            }),
+            failover()
+                .abort()
+                .unwrap()
+                .timeout(Duration::from_secs(1))
+                .err()
+        );
+    }
+
+    #[test]
+    fn failover_force_needs_a_target_and_timeout() {
+        assert_eq!(
+            Some(ValidationError::MissingRequiredFlag {
+                flag: "FORCE",
+                requires: "TO"
+            }),
+            failover().force().err()
+        );
+        assert_eq!(
+            Some(ValidationError::MissingRequiredFlag {
+                flag: "FORCE",
+                requires: "TIMEOUT"
+            }),
+            failover().to("host", 6379).unwrap().force().err()
+        );
+    }
+
+    #[test]
+    fn config_subcommands_convert_to_bytes() {
+        assert_eq!(
+            String::from_utf8(config_rewrite().get_bytes()).unwrap(),
+            "*2\r\n$6\r\nCONFIG\r\n$7\r\nREWRITE\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(config_resetstat().get_bytes()).unwrap(),
+            "*2\r\n$6\r\nCONFIG\r\n$9\r\nRESETSTAT\r\n"
+        );
+    }
+
+    #[test]
+    fn swapdb_command_converts_to_bytes() {
+        assert_eq!(
+            String::from_utf8(swapdb(0, 1).get_bytes()).unwrap(),
+            "*3\r\n$6\r\nSWAPDB\r\n$1\r\n0\r\n$1\r\n1\r\n"
+        );
+    }
+}
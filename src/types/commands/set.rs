@@ -1,10 +1,10 @@
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::time::Duration;
 
 use crate::types::redis_values::{ConversionError, RedisResult};
 use crate::types::StructuredCommand;
-use crate::RBytes;
+use crate::{RBytes, ValidationError};
 
 // TODO: derive Debug for all commands
 pub struct Set<'a> {
@@ -22,13 +22,11 @@ impl<'a> Set<'a> {
         }
     }
 
-    pub fn with_expiry(mut self, duration: Duration) -> Self {
-        if cfg!(debug_assertions) && duration == Duration::from_millis(0) {
-            panic!("duration cannot be 0 in length");
-        }
-
+    // Expires the key after `duration`, with millisecond precision
+    pub fn with_expiry(mut self, duration: Duration) -> Result<Self, ValidationError> {
+        check_millisecond_expiry(duration, "SET")?;
         self.expiry.replace(duration);
-        self
+        Ok(self)
     }
 
     pub fn if_exists(self) -> SetIfExists<'a> {
@@ -58,13 +56,23 @@ pub struct SetIfExists<'a> {
 }
 
 impl<'a> SetIfExists<'a> {
-    pub fn with_expiry(mut self, duration: Duration) -> Self {
-        if cfg!(debug_assertions) && duration == Duration::from_millis(0) {
-            panic!("duration cannot be 0 in length");
-        }
-
+    pub fn with_expiry(mut self, duration: Duration) -> Result<Self, ValidationError> {
+        check_millisecond_expiry(duration, "SET")?;
         self.expiry.replace(duration);
-        self
+        Ok(self)
+    }
+}
+
+// Redis rejects a PX expiry of zero, and durations are sent in whole milliseconds, so anything
+// shorter than a millisecond is rejected too
+pub(crate) fn check_millisecond_expiry(
+    duration: Duration,
+    command: &'static str,
+) -> Result<(), ValidationError> {
+    if duration.as_millis() == 0 {
+        Err(ValidationError::ZeroExpiry { command })
+    } else {
+        Ok(())
     }
 }
 
@@ -72,6 +80,14 @@ pub fn set<'a>(key: impl Into<RBytes<'a>>, value: impl Into<RBytes<'a>>) -> Set<
     Set::new(key.into(), value.into())
 }
 
+// Like `set`, but checks the key's size before the command is built
+pub fn try_set<'a>(
+    key: impl Into<RBytes<'a>>,
+    value: impl Into<RBytes<'a>>,
+) -> Result<Set<'a>, ValidationError> {
+    Ok(Set::new(RBytes::try_from_key(key)?, value.into()))
+}
+
 impl<'a> StructuredCommand for Set<'a> {
     type Output = ();
 
@@ -112,11 +128,7 @@ impl<'a> StructuredCommand for SetIfExists<'a> {
     }
 
     fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
-        match result {
-            RedisResult::Null => Ok(false),
-            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
-            _ => Ok(true),
-        }
+        result.try_into()
     }
 }
 
@@ -198,14 +210,7 @@ impl<'a> StructuredCommand for SetManyIfExists<'a> {
     }
 
     fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
-        match result {
-            RedisResult::Integer(0) => Ok(false),
-            RedisResult::Integer(1) => Ok(true),
-            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
-            _ => Err(ConversionError::NoConversionTypeMatch {
-                value: Option::try_from(result)?,
-            }),
-        }
+        result.try_into()
     }
 }
 
@@ -248,13 +253,50 @@ where
     }
 }
 
+pub struct SetEx<'a> {
+    key: RBytes<'a>,
+    seconds: u64,
+    value: RBytes<'a>,
+}
+
+impl<'a> StructuredCommand for SetEx<'a> {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("SETEX", &self.key, self.seconds.to_string(), &self.value)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// Redis rejects a zero expiry for SETEX, so that's caught here rather than sent
+pub fn setex<'a>(
+    key: impl Into<RBytes<'a>>,
+    seconds: u64,
+    value: impl Into<RBytes<'a>>,
+) -> Result<SetEx<'a>, ValidationError> {
+    if seconds == 0 {
+        return Err(ValidationError::ZeroExpiry { command: "SETEX" });
+    }
+
+    Ok(SetEx {
+        key: key.into(),
+        seconds,
+        value: value.into(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn set_command_converts_to_bytes_with_expiry_data() {
-        let cmd = set("my-first-key", 42).with_expiry(Duration::from_secs(400));
+        let cmd = set("my-first-key", 42)
+            .with_expiry(Duration::from_secs(400))
+            .unwrap();
 
         assert_eq!(
             String::from_utf8(cmd.get_bytes()).unwrap(),
@@ -285,7 +327,8 @@ mod tests {
     fn set_if_exists_can_have_an_optional_duration() {
         let cmd = set("my-first-key", 42)
             .if_exists()
-            .with_expiry(Duration::from_millis(1000));
+            .with_expiry(Duration::from_millis(1000))
+            .unwrap();
 
         assert_eq!(
             String::from_utf8(cmd.get_bytes()).unwrap(),
@@ -303,6 +346,7 @@ mod tests {
     fn set_command_with_duration_keeps_duration_when_transformed_to_set_if_exists() {
         let cmd = set("my-first-key", 42)
             .with_expiry(Duration::from_millis(1000))
+            .unwrap()
             .if_exists();
 
         assert_eq!(
@@ -330,4 +374,51 @@ mod tests {
              $2\r\nNX\r\n"
         );
     }
+
+    #[test]
+    fn set_rejects_expiries_shorter_than_a_millisecond() {
+        let error = Some(ValidationError::ZeroExpiry { command: "SET" });
+        assert_eq!(
+            error,
+            set("my-key", 42).with_expiry(Duration::from_secs(0)).err()
+        );
+        assert_eq!(
+            error,
+            set("my-key", 42)
+                .with_expiry(Duration::from_micros(500))
+                .err()
+        );
+        assert_eq!(
+            error,
+            set("my-key", 42)
+                .if_not_exists()
+                .with_expiry(Duration::from_secs(0))
+                .err()
+        );
+        assert!(set("my-key", 42)
+            .with_expiry(Duration::from_millis(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn setex_rejects_a_zero_expiry() {
+        assert_eq!(
+            Some(ValidationError::ZeroExpiry { command: "SETEX" }),
+            setex("my-key", 0, "value").err()
+        );
+    }
+
+    #[test]
+    fn setex_accepts_a_one_second_expiry() {
+        let cmd = setex("my-key", 1, "value").unwrap();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*4\r\n\
+             $5\r\nSETEX\r\n\
+             $6\r\nmy-key\r\n\
+             $1\r\n1\r\n\
+             $5\r\nvalue\r\n"
+        );
+    }
 }
@@ -0,0 +1,140 @@
+use std::convert::TryInto;
+use std::marker::PhantomData;
+
+use super::members::members;
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use crate::RBytes;
+
+pub struct SInterCard<'a> {
+    keys: Vec<RBytes<'a>>,
+    limit: Option<u64>,
+}
+
+impl<'a> SInterCard<'a> {
+    // Stops counting once the intersection is known to be at least this big
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit.replace(limit);
+        self
+    }
+}
+
+impl<'a> StructuredCommand for SInterCard<'a> {
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        let limit_len = if self.limit.is_some() { 2 } else { 0 };
+
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice((2 + self.keys.len() + limit_len).to_string().as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "SINTERCARD");
+        insert_bytes_into_vec!(bytes, self.keys.len().to_string());
+        for key in &self.keys {
+            insert_bytes_into_vec!(bytes, key);
+        }
+        if let Some(limit) = self.limit {
+            insert_bytes_into_vec!(bytes, "LIMIT");
+            insert_bytes_into_vec!(bytes, limit.to_string());
+        }
+
+        bytes
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Integer(count @ 0..=std::i64::MAX) => Ok(count as u64),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+// Counts the members in the intersection of the sets, without fetching them (redis 7+)
+pub fn sintercard<'a>(keys: impl IntoIterator<Item = impl Into<RBytes<'a>>>) -> SInterCard<'a> {
+    SInterCard {
+        keys: keys.into_iter().map(Into::into).collect(),
+        limit: None,
+    }
+}
+
+pub struct SMembers<'a, T> {
+    key: RBytes<'a>,
+    _t: PhantomData<T>,
+}
+
+impl<'a, T> StructuredCommand for SMembers<'a, T>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    // in no particular order, and empty if the key doesn't exist
+    type Output = Vec<T>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("SMEMBERS", &self.key)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        members(result)
+    }
+}
+
+pub fn smembers<'a, T>(key: impl Into<RBytes<'a>>) -> SMembers<'a, T> {
+    SMembers {
+        key: key.into(),
+        _t: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sintercard_sends_the_key_count_and_limit() {
+        assert_eq!(
+            String::from_utf8(sintercard(vec!["a", "b"]).limit(10).get_bytes()).unwrap(),
+            "*6\r\n\
+             $10\r\nSINTERCARD\r\n\
+             $1\r\n2\r\n\
+             $1\r\na\r\n\
+             $1\r\nb\r\n\
+             $5\r\nLIMIT\r\n\
+             $2\r\n10\r\n"
+        );
+    }
+
+    #[test]
+    fn smembers_converts_each_member() {
+        let result = RedisResult::Array(vec![
+            RedisResult::String(b"a".to_vec()),
+            RedisResult::String(b"b".to_vec()),
+        ]);
+
+        assert_eq!(
+            vec![b"a".to_vec(), b"b".to_vec()],
+            smembers::<Vec<u8>>("set")
+                .convert_redis_result(result)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn smembers_accepts_resp3_set_replies() {
+        let result = RedisResult::Set(vec![
+            RedisResult::String(b"a".to_vec()),
+            RedisResult::String(b"b".to_vec()),
+        ]);
+
+        assert_eq!(
+            vec!["a".to_string(), "b".to_string()],
+            smembers::<String>("set")
+                .convert_redis_result(result)
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,821 @@
+use std::convert::{TryFrom, TryInto};
+use std::marker::PhantomData;
+
+use super::members::members;
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use crate::{RBytes, ValidationError};
+
+// A minimum or maximum score for the sorted set commands that select members by score
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ScoreBound {
+    Inclusive(f64),
+    Exclusive(f64),
+    NegInfinity,
+    PosInfinity,
+}
+
+impl ScoreBound {
+    pub fn to_redis_token(&self) -> String {
+        match self {
+            ScoreBound::Inclusive(score) => score.to_string(),
+            ScoreBound::Exclusive(score) => format!("({}", score),
+            ScoreBound::NegInfinity => "-inf".to_string(),
+            ScoreBound::PosInfinity => "+inf".to_string(),
+        }
+    }
+}
+
+pub struct ZCount<'a> {
+    key: RBytes<'a>,
+    min: ScoreBound,
+    max: ScoreBound,
+}
+
+impl<'a> StructuredCommand for ZCount<'a> {
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!(
+            "ZCOUNT",
+            &self.key,
+            self.min.to_redis_token(),
+            self.max.to_redis_token()
+        )
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Integer(count @ 0..=std::i64::MAX) => Ok(count as u64),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+pub fn zcount<'a>(key: impl Into<RBytes<'a>>, min: ScoreBound, max: ScoreBound) -> ZCount<'a> {
+    ZCount {
+        key: key.into(),
+        min,
+        max,
+    }
+}
+
+pub struct ZAdd<'a> {
+    key: RBytes<'a>,
+    members: Vec<(f64, RBytes<'a>)>,
+    nx: bool,
+    xx: bool,
+    gt: bool,
+    lt: bool,
+    changed: bool,
+}
+
+impl<'a> ZAdd<'a> {
+    pub fn member(
+        mut self,
+        score: f64,
+        member: impl Into<RBytes<'a>>,
+    ) -> Result<Self, ValidationError> {
+        check_score(score)?;
+        self.members.push((score, member.into()));
+        Ok(self)
+    }
+
+    fn check_compatible(
+        &self,
+        flag: &'static str,
+        incompatible: &[(&'static str, bool)],
+    ) -> Result<(), ValidationError> {
+        match incompatible.iter().find(|(_, set)| *set) {
+            Some((other, _)) => Err(ValidationError::IncompatibleFlags {
+                first: other,
+                second: flag,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    // Only adds new members, never updating existing ones
+    pub fn nx(mut self) -> Result<Self, ValidationError> {
+        self.check_compatible("NX", &[("XX", self.xx), ("GT", self.gt), ("LT", self.lt)])?;
+        self.nx = true;
+        Ok(self)
+    }
+
+    // Only updates existing members, never adding new ones
+    pub fn xx(mut self) -> Result<Self, ValidationError> {
+        self.check_compatible("XX", &[("NX", self.nx)])?;
+        self.xx = true;
+        Ok(self)
+    }
+
+    // Only updates existing members if the new score is greater than the current score
+    pub fn gt(mut self) -> Result<Self, ValidationError> {
+        self.check_compatible("GT", &[("NX", self.nx), ("LT", self.lt)])?;
+        self.gt = true;
+        Ok(self)
+    }
+
+    // Only updates existing members if the new score is less than the current score
+    pub fn lt(mut self) -> Result<Self, ValidationError> {
+        self.check_compatible("LT", &[("NX", self.nx), ("GT", self.gt)])?;
+        self.lt = true;
+        Ok(self)
+    }
+
+    // Outputs the number of members added or updated, rather than just the number added
+    pub fn changed(mut self) -> Self {
+        self.changed = true;
+        self
+    }
+
+    // Increments the member's score instead of setting it, and outputs the new score.  Redis only
+    // allows this with a single member.
+    pub fn incr(self) -> Result<ZAddIncr<'a>, ValidationError> {
+        if self.members.len() != 1 {
+            return Err(ValidationError::IncrRequiresSingleMember {
+                members: self.members.len(),
+            });
+        }
+        Ok(ZAddIncr { command: self })
+    }
+
+    fn bytes_with_incr(&self, incr: bool) -> Vec<u8> {
+        let flags: Vec<&str> = vec![
+            ("NX", self.nx),
+            ("XX", self.xx),
+            ("GT", self.gt),
+            ("LT", self.lt),
+            ("CH", self.changed),
+            ("INCR", incr),
+        ]
+        .into_iter()
+        .filter(|(_, set)| *set)
+        .map(|(flag, _)| flag)
+        .collect();
+
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice(
+            (2 + flags.len() + self.members.len() * 2)
+                .to_string()
+                .as_bytes(),
+        );
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "ZADD");
+        insert_bytes_into_vec!(bytes, &self.key);
+        for flag in flags {
+            insert_bytes_into_vec!(bytes, flag);
+        }
+        for (score, member) in &self.members {
+            insert_bytes_into_vec!(bytes, score.to_string());
+            insert_bytes_into_vec!(bytes, member);
+        }
+
+        bytes
+    }
+}
+
+impl<'a> StructuredCommand for ZAdd<'a> {
+    // the number of members added (or added and updated, if `changed` is set)
+    type Output = u64;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.bytes_with_incr(false)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            RedisResult::Integer(count @ 0..=std::i64::MAX) => Ok(count as u64),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: result.try_into()?,
+            }),
+        }
+    }
+}
+
+pub struct ZAddIncr<'a> {
+    command: ZAdd<'a>,
+}
+
+impl<'a> StructuredCommand for ZAddIncr<'a> {
+    // the new score, or `None` if the NX/XX/GT/LT conditions prevented the update
+    type Output = Option<f64>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.command.bytes_with_incr(true)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        match result {
+            // e.g. adding -inf to a score of +inf
+            RedisResult::Error(error)
+                if error
+                    .message()
+                    .is_some_and(|message| message.contains("not a number")) =>
+            {
+                Err(ConversionError::ScoreNotANumber { error })
+            }
+            result => result.try_into(),
+        }
+    }
+}
+
+// Redis rejects NaN scores, so they're caught before the command is sent.  Infinite scores are
+// fine, and are sent as `inf` and `-inf`.
+fn check_score(score: f64) -> Result<(), ValidationError> {
+    if score.is_nan() {
+        Err(ValidationError::NanScore)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn zadd<'a>(
+    key: impl Into<RBytes<'a>>,
+    score: f64,
+    member: impl Into<RBytes<'a>>,
+) -> Result<ZAdd<'a>, ValidationError> {
+    check_score(score)?;
+    Ok(ZAdd {
+        key: key.into(),
+        members: vec![(score, member.into())],
+        nx: false,
+        xx: false,
+        gt: false,
+        lt: false,
+        changed: false,
+    })
+}
+
+// How ZUNION and ZINTER combine the scores of a member that is in more than one set
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Aggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    fn to_redis_token(self) -> &'static str {
+        match self {
+            Aggregate::Sum => "SUM",
+            Aggregate::Min => "MIN",
+            Aggregate::Max => "MAX",
+        }
+    }
+}
+
+// RESP2 sends members and scores as one flat array, whereas RESP3 sends an array of pairs
+fn members_with_scores<T>(result: RedisResult) -> Result<Vec<(T, f64)>, ConversionError>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    let elements = match result {
+        RedisResult::Array(elements) => elements,
+        RedisResult::Error(error) => return Err(ConversionError::RedisReturnedError { error }),
+        _ => {
+            return Err(ConversionError::NoConversionTypeMatch {
+                value: Option::try_from(result)?,
+            })
+        }
+    };
+
+    let mut flattened = Vec::with_capacity(elements.len() * 2);
+    for element in elements {
+        match element {
+            RedisResult::Array(pair) => flattened.extend(pair),
+            element => flattened.push(element),
+        }
+    }
+    if flattened.len() % 2 != 0 {
+        return Err(ConversionError::NoConversionTypeMatch { value: None });
+    }
+
+    let mut flattened = flattened.into_iter();
+    let mut pairs = Vec::with_capacity(flattened.len() / 2);
+    while let (Some(member), Some(score)) = (flattened.next(), flattened.next()) {
+        let member: Option<T> = member.try_into()?;
+        let member = member.ok_or(ConversionError::NoConversionTypeMatch { value: None })?;
+        let score = f64::try_from(score)?;
+        pairs.push((member, score));
+    }
+    Ok(pairs)
+}
+
+pub struct ZDiff<'a> {
+    keys: Vec<RBytes<'a>>,
+}
+
+impl<'a> ZDiff<'a> {
+    // Outputs each member along with its score
+    pub fn with_scores(self) -> ZDiffWithScores<'a> {
+        ZDiffWithScores { command: self }
+    }
+
+    fn bytes_with_scores(&self, with_scores: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice(
+            (2 + self.keys.len() + with_scores as usize)
+                .to_string()
+                .as_bytes(),
+        );
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, "ZDIFF");
+        insert_bytes_into_vec!(bytes, self.keys.len().to_string());
+        for key in &self.keys {
+            insert_bytes_into_vec!(bytes, key);
+        }
+        if with_scores {
+            insert_bytes_into_vec!(bytes, "WITHSCORES");
+        }
+
+        bytes
+    }
+}
+
+impl<'a> StructuredCommand for ZDiff<'a> {
+    type Output = Vec<String>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.bytes_with_scores(false)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        members(result)
+    }
+}
+
+pub struct ZDiffWithScores<'a> {
+    command: ZDiff<'a>,
+}
+
+impl<'a> StructuredCommand for ZDiffWithScores<'a> {
+    type Output = Vec<(String, f64)>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.command.bytes_with_scores(true)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        members_with_scores(result)
+    }
+}
+
+// The members of the first set that aren't in any of the others
+pub fn zdiff<'a>(keys: impl IntoIterator<Item = impl Into<RBytes<'a>>>) -> ZDiff<'a> {
+    ZDiff {
+        keys: keys.into_iter().map(Into::into).collect(),
+    }
+}
+
+pub struct ZRange<'a, T> {
+    key: RBytes<'a>,
+    start: i64,
+    stop: i64,
+    _t: PhantomData<T>,
+}
+
+impl<'a, T> ZRange<'a, T> {
+    // Outputs each member along with its score
+    pub fn with_scores(self) -> ZRangeWithScores<'a, T> {
+        ZRangeWithScores { command: self }
+    }
+
+    fn bytes_with_scores(&self, with_scores: bool) -> Vec<u8> {
+        let start = self.start.to_string();
+        let stop = self.stop.to_string();
+        if with_scores {
+            resp_bytes!("ZRANGE", &self.key, start, stop, "WITHSCORES")
+        } else {
+            resp_bytes!("ZRANGE", &self.key, start, stop)
+        }
+    }
+}
+
+impl<'a, T> StructuredCommand for ZRange<'a, T>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    type Output = Vec<T>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.bytes_with_scores(false)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        members(result)
+    }
+}
+
+pub struct ZRangeWithScores<'a, T> {
+    command: ZRange<'a, T>,
+}
+
+impl<'a, T> StructuredCommand for ZRangeWithScores<'a, T>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    type Output = Vec<(T, f64)>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.command.bytes_with_scores(true)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        members_with_scores(result)
+    }
+}
+
+// The members ranked `start` to `stop` (inclusive) by score, lowest first.  As with LRANGE, the
+// ranks can be negative to count from the highest score.
+pub fn zrange<'a, T>(key: impl Into<RBytes<'a>>, start: i64, stop: i64) -> ZRange<'a, T> {
+    ZRange {
+        key: key.into(),
+        start,
+        stop,
+        _t: PhantomData,
+    }
+}
+
+// ZUNION and ZINTER take exactly the same arguments, so share a builder
+pub struct ZCombine<'a> {
+    command: &'static str,
+    keys: Vec<RBytes<'a>>,
+    weights: Vec<f64>,
+    aggregate: Option<Aggregate>,
+}
+
+impl<'a> ZCombine<'a> {
+    // Multiplies the scores in each set by the matching weight before they are aggregated.  There
+    // should be one weight per key.
+    pub fn weights(mut self, weights: impl IntoIterator<Item = f64>) -> Self {
+        self.weights = weights.into_iter().collect();
+        self
+    }
+
+    pub fn aggregate(mut self, aggregate: Aggregate) -> Self {
+        self.aggregate.replace(aggregate);
+        self
+    }
+
+    // Outputs each member along with its combined score
+    pub fn with_scores(self) -> ZCombineWithScores<'a> {
+        ZCombineWithScores { command: self }
+    }
+
+    fn bytes_with_scores(&self, with_scores: bool) -> Vec<u8> {
+        let weights_len = if self.weights.is_empty() {
+            0
+        } else {
+            1 + self.weights.len()
+        };
+        let aggregate_len = if self.aggregate.is_some() { 2 } else { 0 };
+
+        let mut bytes = Vec::new();
+        bytes.push(b'*');
+        bytes.extend_from_slice(
+            (2 + self.keys.len() + weights_len + aggregate_len + with_scores as usize)
+                .to_string()
+                .as_bytes(),
+        );
+        bytes.extend_from_slice(b"\r\n");
+
+        insert_bytes_into_vec!(bytes, self.command);
+        insert_bytes_into_vec!(bytes, self.keys.len().to_string());
+        for key in &self.keys {
+            insert_bytes_into_vec!(bytes, key);
+        }
+        if !self.weights.is_empty() {
+            insert_bytes_into_vec!(bytes, "WEIGHTS");
+            for weight in &self.weights {
+                insert_bytes_into_vec!(bytes, weight.to_string());
+            }
+        }
+        if let Some(aggregate) = self.aggregate {
+            insert_bytes_into_vec!(bytes, "AGGREGATE");
+            insert_bytes_into_vec!(bytes, aggregate.to_redis_token());
+        }
+        if with_scores {
+            insert_bytes_into_vec!(bytes, "WITHSCORES");
+        }
+
+        bytes
+    }
+}
+
+impl<'a> StructuredCommand for ZCombine<'a> {
+    type Output = Vec<String>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.bytes_with_scores(false)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        members(result)
+    }
+}
+
+pub struct ZCombineWithScores<'a> {
+    command: ZCombine<'a>,
+}
+
+impl<'a> StructuredCommand for ZCombineWithScores<'a> {
+    type Output = Vec<(String, f64)>;
+
+    fn get_bytes(&self) -> Vec<u8> {
+        self.command.bytes_with_scores(true)
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        members_with_scores(result)
+    }
+}
+
+fn zcombine<'a>(
+    command: &'static str,
+    keys: impl IntoIterator<Item = impl Into<RBytes<'a>>>,
+) -> ZCombine<'a> {
+    ZCombine {
+        command,
+        keys: keys.into_iter().map(Into::into).collect(),
+        weights: Vec::new(),
+        aggregate: None,
+    }
+}
+
+pub fn zunion<'a>(keys: impl IntoIterator<Item = impl Into<RBytes<'a>>>) -> ZCombine<'a> {
+    zcombine("ZUNION", keys)
+}
+
+pub fn zinter<'a>(keys: impl IntoIterator<Item = impl Into<RBytes<'a>>>) -> ZCombine<'a> {
+    zcombine("ZINTER", keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RedisErrorValue;
+
+    #[test]
+    fn score_bounds_render_as_redis_tokens() {
+        assert_eq!("5", ScoreBound::Inclusive(5.0).to_redis_token());
+        assert_eq!("2.5", ScoreBound::Inclusive(2.5).to_redis_token());
+        assert_eq!("(5", ScoreBound::Exclusive(5.0).to_redis_token());
+        assert_eq!("(-1.5", ScoreBound::Exclusive(-1.5).to_redis_token());
+        assert_eq!("-inf", ScoreBound::NegInfinity.to_redis_token());
+        assert_eq!("+inf", ScoreBound::PosInfinity.to_redis_token());
+    }
+
+    #[test]
+    fn zcount_command_uses_score_bound_tokens() {
+        let cmd = zcount(
+            "my-set",
+            ScoreBound::Exclusive(1.0),
+            ScoreBound::PosInfinity,
+        );
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*4\r\n\
+             $6\r\nZCOUNT\r\n\
+             $6\r\nmy-set\r\n\
+             $2\r\n(1\r\n\
+             $4\r\n+inf\r\n"
+        );
+    }
+
+    fn zadd_bytes(flags: &[&str]) -> String {
+        let mut expected = format!("*{}\r\n$4\r\nZADD\r\n$6\r\nmy-set\r\n", 4 + flags.len());
+        for flag in flags {
+            expected.push_str(&format!("${}\r\n{}\r\n", flag.len(), flag));
+        }
+        expected.push_str("$3\r\n1.5\r\n$3\r\none\r\n");
+        expected
+    }
+
+    #[test]
+    fn zadd_command_converts_to_bytes() {
+        let cmd = zadd("my-set", 1.5, "one")
+            .unwrap()
+            .member(2.0, "two")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*6\r\n\
+             $4\r\nZADD\r\n\
+             $6\r\nmy-set\r\n\
+             $3\r\n1.5\r\n\
+             $3\r\none\r\n\
+             $1\r\n2\r\n\
+             $3\r\ntwo\r\n"
+        );
+    }
+
+    #[test]
+    fn zadd_command_includes_each_flag() {
+        let cmd = || zadd("my-set", 1.5, "one").unwrap();
+
+        assert_eq!(
+            zadd_bytes(&["NX"]),
+            String::from_utf8(cmd().nx().unwrap().get_bytes()).unwrap()
+        );
+        assert_eq!(
+            zadd_bytes(&["XX"]),
+            String::from_utf8(cmd().xx().unwrap().get_bytes()).unwrap()
+        );
+        assert_eq!(
+            zadd_bytes(&["GT"]),
+            String::from_utf8(cmd().gt().unwrap().get_bytes()).unwrap()
+        );
+        assert_eq!(
+            zadd_bytes(&["LT"]),
+            String::from_utf8(cmd().lt().unwrap().get_bytes()).unwrap()
+        );
+        assert_eq!(
+            zadd_bytes(&["CH"]),
+            String::from_utf8(cmd().changed().get_bytes()).unwrap()
+        );
+        assert_eq!(
+            zadd_bytes(&["INCR"]),
+            String::from_utf8(cmd().incr().unwrap().get_bytes()).unwrap()
+        );
+        assert_eq!(
+            zadd_bytes(&["XX", "GT", "CH", "INCR"]),
+            String::from_utf8(
+                cmd()
+                    .gt()
+                    .unwrap()
+                    .changed()
+                    .xx()
+                    .unwrap()
+                    .incr()
+                    .unwrap()
+                    .get_bytes()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn zadd_command_rejects_incompatible_flags() {
+        let cmd = || zadd("my-set", 1.5, "one").unwrap();
+
+        assert_eq!(
+            Some(ValidationError::IncompatibleFlags {
+                first: "NX",
+                second: "GT"
+            }),
+            cmd().nx().unwrap().gt().err()
+        );
+        assert_eq!(
+            Some(ValidationError::IncompatibleFlags {
+                first: "XX",
+                second: "NX"
+            }),
+            cmd().xx().unwrap().nx().err()
+        );
+        assert_eq!(
+            Some(ValidationError::IncompatibleFlags {
+                first: "GT",
+                second: "LT"
+            }),
+            cmd().gt().unwrap().lt().err()
+        );
+    }
+
+    #[test]
+    fn zadd_incr_requires_a_single_member() {
+        assert_eq!(
+            Some(ValidationError::IncrRequiresSingleMember { members: 2 }),
+            zadd("my-set", 1.5, "one")
+                .unwrap()
+                .member(2.0, "two")
+                .unwrap()
+                .incr()
+                .err()
+        );
+    }
+
+    #[test]
+    fn zadd_rejects_nan_scores() {
+        assert_eq!(
+            Some(ValidationError::NanScore),
+            zadd("my-set", f64::NAN, "one").err()
+        );
+        assert_eq!(
+            Some(ValidationError::NanScore),
+            zadd("my-set", 1.5, "one")
+                .unwrap()
+                .member(f64::NAN, "two")
+                .err()
+        );
+        assert!(zadd("my-set", f64::INFINITY, "one").is_ok());
+    }
+
+    #[test]
+    fn zadd_incr_maps_a_nan_result_to_its_own_error() {
+        let cmd = zadd("my-set", f64::NEG_INFINITY, "one")
+            .unwrap()
+            .incr()
+            .unwrap();
+
+        match cmd.convert_redis_result(RedisResult::Error(RedisErrorValue::new(
+            "ERR resulting score is not a number (NaN)",
+        ))) {
+            Err(ConversionError::ScoreNotANumber { .. }) => {}
+            other => panic!("expected a NaN score error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn zadd_incr_outputs_the_new_score() {
+        let cmd = || zadd("my-set", 1.5, "one").unwrap().incr().unwrap();
+
+        assert_eq!(
+            Some(3.0),
+            cmd()
+                .convert_redis_result(RedisResult::String(b"3".to_vec()))
+                .unwrap()
+        );
+        assert_eq!(None, cmd().convert_redis_result(RedisResult::Null).unwrap());
+    }
+
+    #[test]
+    fn zunion_includes_weights_and_aggregate_in_order() {
+        let cmd = zunion(vec!["a", "b"])
+            .weights(vec![2.0, 0.5])
+            .aggregate(Aggregate::Max)
+            .with_scores();
+
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*10\r\n\
+             $6\r\nZUNION\r\n\
+             $1\r\n2\r\n\
+             $1\r\na\r\n\
+             $1\r\nb\r\n\
+             $7\r\nWEIGHTS\r\n\
+             $1\r\n2\r\n\
+             $3\r\n0.5\r\n\
+             $9\r\nAGGREGATE\r\n\
+             $3\r\nMAX\r\n\
+             $10\r\nWITHSCORES\r\n"
+        );
+    }
+
+    #[test]
+    fn zdiff_without_options_only_sends_the_keys() {
+        assert_eq!(
+            String::from_utf8(zdiff(vec!["a", "b"]).get_bytes()).unwrap(),
+            "*4\r\n$5\r\nZDIFF\r\n$1\r\n2\r\n$1\r\na\r\n$1\r\nb\r\n"
+        );
+    }
+
+    #[test]
+    fn scores_are_read_from_flat_and_paired_replies() {
+        let flat = RedisResult::Array(vec![
+            RedisResult::String(b"one".to_vec()),
+            RedisResult::String(b"1.5".to_vec()),
+        ]);
+        let paired = RedisResult::Array(vec![RedisResult::Array(vec![
+            RedisResult::String(b"one".to_vec()),
+            RedisResult::Double(1.5),
+        ])]);
+
+        let expected = vec![("one".to_string(), 1.5)];
+        assert_eq!(expected, members_with_scores(flat).unwrap());
+        assert_eq!(expected, members_with_scores(paired).unwrap());
+    }
+
+    #[test]
+    fn zrange_with_scores_converts_to_bytes_and_pairs() {
+        let cmd = zrange::<Vec<u8>>("my-set", 0, -1).with_scores();
+        assert_eq!(
+            String::from_utf8(cmd.get_bytes()).unwrap(),
+            "*5\r\n\
+             $6\r\nZRANGE\r\n\
+             $6\r\nmy-set\r\n\
+             $1\r\n0\r\n\
+             $2\r\n-1\r\n\
+             $10\r\nWITHSCORES\r\n"
+        );
+
+        let result = RedisResult::Array(vec![
+            RedisResult::String(b"one".to_vec()),
+            RedisResult::String(b"1.5".to_vec()),
+        ]);
+        assert_eq!(
+            vec![(b"one".to_vec(), 1.5)],
+            cmd.convert_redis_result(result).unwrap()
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::types::redis_values::ConversionError;
 use crate::{RBytes, RedisResult, RedisValue, StructuredCommand};
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
 pub struct Ping;
 
@@ -12,21 +12,14 @@ impl StructuredCommand for Ping {
     }
 
     fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
-        match result {
-            // TODO: test this in subscription mode
-            RedisResult::String(string) => {
-                if string == b"PONG" {
-                    Ok(())
-                } else {
-                    Err(ConversionError::NoConversionTypeMatch {
-                        value: Some(RedisValue::String(string)),
-                    })
-                }
-            }
-            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
-            _ => Err(ConversionError::NoConversionTypeMatch {
-                value: result.try_into()?,
-            }),
+        // subscribed connections reply with a lowercase `pong`
+        let reply = result.coerce_simple_ok()?;
+        if reply.eq_ignore_ascii_case(b"PONG") {
+            Ok(())
+        } else {
+            Err(ConversionError::NoConversionTypeMatch {
+                value: Some(RedisValue::String(reply)),
+            })
         }
     }
 }
@@ -45,10 +38,161 @@ impl<'a> StructuredCommand for Echo<'a> {
     }
 
     fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
-        result.try_into()
+        Option::<String>::try_from(result)?
+            .ok_or(ConversionError::NoConversionTypeMatch { value: None })
     }
 }
 
 pub fn echo<'a>(text: impl Into<String>) -> Echo<'a> {
     Echo(text.into().into())
 }
+
+pub struct Auth<'a> {
+    username: Option<RBytes<'a>>,
+    password: RBytes<'a>,
+}
+
+impl<'a> Auth<'a> {
+    // Logs in as a specific ACL user (redis 6+), rather than the default user
+    pub fn with_username(mut self, username: impl Into<RBytes<'a>>) -> Self {
+        self.username.replace(username.into());
+        self
+    }
+}
+
+impl<'a> StructuredCommand for Auth<'a> {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        match &self.username {
+            Some(username) => resp_bytes!("AUTH", username, &self.password),
+            None => resp_bytes!("AUTH", &self.password),
+        }
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.expect_ok()
+    }
+}
+
+pub fn auth<'a>(password: impl Into<RBytes<'a>>) -> Auth<'a> {
+    Auth {
+        username: None,
+        password: password.into(),
+    }
+}
+
+pub struct Hello {
+    protocol: u8,
+}
+
+impl StructuredCommand for Hello {
+    type Output = ();
+
+    fn get_bytes(&self) -> Vec<u8> {
+        resp_bytes!("HELLO", self.protocol.to_string())
+    }
+
+    fn convert_redis_result(self, result: RedisResult) -> Result<Self::Output, ConversionError> {
+        result.try_into()
+    }
+}
+
+// Switches the connection to the given protocol version (redis 6+).  Note that after `HELLO 3`,
+// replies can contain RESP3-only types.
+pub fn hello(protocol: u8) -> Hello {
+    Hello { protocol }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ping_accepts_both_the_normal_and_subscribed_replies() {
+        assert!(ping()
+            .convert_redis_result(RedisResult::String(b"PONG".to_vec()))
+            .is_ok());
+        assert!(ping()
+            .convert_redis_result(RedisResult::Array(vec![
+                RedisResult::String(b"pong".to_vec()),
+                RedisResult::String(b"".to_vec()),
+            ]))
+            .is_ok());
+        assert!(ping()
+            .convert_redis_result(RedisResult::String(b"PING".to_vec()))
+            .is_err());
+    }
+
+    #[test]
+    fn echo_only_accepts_a_string_reply() {
+        assert_eq!(
+            "hello",
+            echo("hello")
+                .convert_redis_result(RedisResult::String(b"hello".to_vec()))
+                .unwrap()
+        );
+        match echo("hello").convert_redis_result(RedisResult::Array(vec![RedisResult::String(
+            b"hello".to_vec(),
+        )])) {
+            Err(ConversionError::NoConversionTypeMatch { .. }) => {}
+            other => panic!("expected no conversion, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn coerce_simple_ok_rejects_other_shapes() {
+        assert!(RedisResult::Integer(1).coerce_simple_ok().is_err());
+        assert!(RedisResult::Array(vec![]).coerce_simple_ok().is_err());
+        assert!(RedisResult::Array(vec![RedisResult::Integer(1)])
+            .coerce_simple_ok()
+            .is_err());
+    }
+
+    #[test]
+    fn coerce_simple_ok_only_accepts_the_subscribed_pong_array() {
+        let array = |parts: &[&[u8]]| {
+            RedisResult::Array(
+                parts
+                    .iter()
+                    .map(|part| RedisResult::String(part.to_vec()))
+                    .collect(),
+            )
+        };
+
+        assert_eq!(
+            b"pong".to_vec(),
+            array(&[b"pong", b"message"]).coerce_simple_ok().unwrap()
+        );
+        assert!(array(&[b"hello", b"world"]).coerce_simple_ok().is_err());
+        assert!(array(&[b"pong"]).coerce_simple_ok().is_err());
+        assert!(array(&[b"pong", b"", b"extra"]).coerce_simple_ok().is_err());
+    }
+
+    #[test]
+    fn auth_command_only_sends_a_username_if_given() {
+        assert_eq!(
+            String::from_utf8(auth("pass").get_bytes()).unwrap(),
+            "*2\r\n\
+             $4\r\nAUTH\r\n\
+             $4\r\npass\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(auth("pass").with_username("user").get_bytes()).unwrap(),
+            "*3\r\n\
+             $4\r\nAUTH\r\n\
+             $4\r\nuser\r\n\
+             $4\r\npass\r\n"
+        );
+    }
+
+    #[test]
+    fn auth_command_requires_an_ok_reply() {
+        assert!(auth("pass")
+            .convert_redis_result(RedisResult::String(b"OK".to_vec()))
+            .is_ok());
+        assert!(auth("pass")
+            .convert_redis_result(RedisResult::Integer(1))
+            .is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{RedisResult, RedisValue};
+
+// Encodes a value the way a server would send it as a reply.  Requests to redis are always flat
+// arrays of bulk strings, so this is only really useful for testing the parser against, or for
+// pretending to be a server.
+pub fn encode_value(value: &RedisValue) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    encode_into(&mut bytes, Some(value));
+    bytes
+}
+
+fn encode_into(bytes: &mut Vec<u8>, value: Option<&RedisValue>) {
+    match value {
+        Some(RedisValue::String(string)) => {
+            bytes.push(b'$');
+            bytes.extend_from_slice(string.len().to_string().as_bytes());
+            bytes.extend_from_slice(b"\r\n");
+            bytes.extend_from_slice(string);
+            bytes.extend_from_slice(b"\r\n");
+        }
+        Some(RedisValue::Integer(int)) => {
+            bytes.push(b':');
+            bytes.extend_from_slice(int.to_string().as_bytes());
+            bytes.extend_from_slice(b"\r\n");
+        }
+        Some(RedisValue::Array(elements)) => {
+            bytes.push(b'*');
+            bytes.extend_from_slice(elements.len().to_string().as_bytes());
+            bytes.extend_from_slice(b"\r\n");
+            for element in elements {
+                encode_into(bytes, element.as_ref());
+            }
+        }
+        None => bytes.extend_from_slice(b"$-1\r\n"),
+    }
+}
+
+// As `encode_value`, but covering every type the parser understands, including errors and the
+// RESP3-only types.  Strings are always sent as bulk strings.
+pub fn encode_result(result: &RedisResult) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    encode_result_into(&mut bytes, result);
+    bytes
+}
+
+fn encode_line(bytes: &mut Vec<u8>, prefix: u8, line: &[u8]) {
+    bytes.push(prefix);
+    bytes.extend_from_slice(line);
+    bytes.extend_from_slice(b"\r\n");
+}
+
+fn encode_result_into(bytes: &mut Vec<u8>, result: &RedisResult) {
+    match result {
+        RedisResult::String(string) => {
+            encode_line(bytes, b'$', string.len().to_string().as_bytes());
+            bytes.extend_from_slice(string);
+            bytes.extend_from_slice(b"\r\n");
+        }
+        RedisResult::Integer(int) => encode_line(bytes, b':', int.to_string().as_bytes()),
+        RedisResult::Error(error) => encode_line(bytes, b'-', error.to_string().as_bytes()),
+        RedisResult::Array(elements) => {
+            encode_line(bytes, b'*', elements.len().to_string().as_bytes());
+            for element in elements {
+                encode_result_into(bytes, element);
+            }
+        }
+        RedisResult::Null => bytes.extend_from_slice(b"$-1\r\n"),
+        RedisResult::VerbatimString { format, data } => {
+            encode_line(bytes, b'=', (data.len() + 4).to_string().as_bytes());
+            bytes.extend_from_slice(format);
+            bytes.push(b':');
+            bytes.extend_from_slice(data);
+            bytes.extend_from_slice(b"\r\n");
+        }
+        RedisResult::Double(double) => encode_line(bytes, b',', double.to_string().as_bytes()),
+        RedisResult::Boolean(boolean) => {
+            encode_line(bytes, b'#', if *boolean { b"t" } else { b"f" })
+        }
+        RedisResult::BigNumber(digits) => encode_line(bytes, b'(', digits.as_bytes()),
+        RedisResult::Map(pairs) => {
+            encode_line(bytes, b'%', pairs.len().to_string().as_bytes());
+            for (key, value) in pairs {
+                encode_result_into(bytes, key);
+                encode_result_into(bytes, value);
+            }
+        }
+        RedisResult::Push(elements) => {
+            encode_line(bytes, b'>', elements.len().to_string().as_bytes());
+            for element in elements {
+                encode_result_into(bytes, element);
+            }
+        }
+        RedisResult::Set(elements) => {
+            encode_line(bytes, b'~', elements.len().to_string().as_bytes());
+            for element in elements {
+                encode_result_into(bytes, element);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_arrays_are_encoded_recursively() {
+        let value = RedisValue::Array(vec![
+            Some(RedisValue::Integer(1)),
+            None,
+            Some(RedisValue::Array(vec![Some(RedisValue::String(
+                b"hi".to_vec(),
+            ))])),
+        ]);
+
+        assert_eq!(
+            b"*3\r\n:1\r\n$-1\r\n*1\r\n$2\r\nhi\r\n".to_vec(),
+            encode_value(&value)
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::sans_io::ParseError;
+use crate::types::redis_values::ConversionError;
 use crate::{RedisErrorValue, RedisValue};
 use std::error::Error;
 use std::io::Error as IoError;
@@ -12,6 +13,88 @@ pub enum RedisError {
     ProtocolParseError(ParseError),
     InternalConnectionError(RecvError),
     ConversionError(Option<RedisValue>),
-    StringParseError(Box<Error>),
+    // the parsing error, and the string that couldn't be parsed
+    StringParseError(Box<Error>, String),
     InvalidUtf8String(FromUtf8Error),
+    IntegerOutOfRange(i64, &'static str),
+    InsufficientReplicas { acknowledged: u32, requested: u32 },
+    // redis refused to update a sorted set score because the result would be NaN
+    ScoreNotANumber(RedisErrorValue),
+    ValidationError(ValidationError),
+    // a reply couldn't be parsed, so the connection is out of step with the server and every
+    // later reply would be read as the answer to the wrong command - a new connection is needed
+    ConnectionDesynchronized,
+    // no reply arrived before the deadline.  The reply may still turn up later, so the connection
+    // is treated as desynchronized from then on.
+    Timeout,
+}
+
+impl RedisError {
+    pub(crate) fn from_conversion_error(error: ConversionError) -> Self {
+        match error {
+            ConversionError::NoConversionTypeMatch { value } => RedisError::ConversionError(value),
+            ConversionError::RedisReturnedError { error } => RedisError::RedisReturnedError(error),
+            ConversionError::CannotParseStringResponse { error, text } => {
+                RedisError::StringParseError(error, text)
+            }
+            ConversionError::InvalidUtf8String(error) => RedisError::InvalidUtf8String(error),
+            ConversionError::IntegerOutOfRange { value, target } => {
+                RedisError::IntegerOutOfRange(value, target)
+            }
+            ConversionError::ScoreNotANumber { error } => RedisError::ScoreNotANumber(error),
+        }
+    }
+
+    // Whether the connection to redis was lost, rather than e.g. redis rejecting the command.  A
+    // new connection may succeed where this one failed.
+    pub fn is_connection_error(&self) -> bool {
+        matches!(
+            self,
+            RedisError::ConnectionError(_) | RedisError::InternalConnectionError(_)
+        )
+    }
+}
+
+// Errors caught on the client side, before a command is ever sent to redis
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ValidationError {
+    BitOffsetTooLarge {
+        offset: u32,
+        max: u32,
+    },
+    // `args` doesn't include the command name, but `arity` does (as redis reports it)
+    WrongArity {
+        command: String,
+        arity: i64,
+        args: usize,
+    },
+    IncompatibleFlags {
+        first: &'static str,
+        second: &'static str,
+    },
+    IncrRequiresSingleMember {
+        members: usize,
+    },
+    KeyTooLarge {
+        size: usize,
+        max: usize,
+    },
+    // an expiry that rounds down to zero in the command's units (seconds or milliseconds), which
+    // e.g. SETEX and SET PX reject and EXPIRE treats as deleting the key
+    ZeroExpiry {
+        command: &'static str,
+    },
+    // COPY can't copy a key onto itself within the same database
+    SameSourceAndDestination,
+    // `flag` can only be used once `requires` has already been set
+    MissingRequiredFlag {
+        flag: &'static str,
+        requires: &'static str,
+    },
+    // sorted set scores can be infinite, but not NaN
+    NanScore,
+    // a key that isn't valid UTF-8, when the client requires UTF-8 keys
+    NonUtf8Key {
+        key: Vec<u8>,
+    },
 }
@@ -1,13 +1,22 @@
 #[macro_use]
 mod resp_macros;
+#[macro_use]
+mod value_macros;
 
-mod redis_bytes;
 mod command;
 pub mod commands;
+mod encoding;
 mod errors;
-pub(in crate) mod redis_values;
+mod redis_bytes;
+pub(crate) mod redis_values;
+#[cfg(feature = "bytes")]
+mod shared_values;
+pub mod transaction;
 
-pub use command::{Command, StructuredCommand};
-pub use errors::RedisError;
-pub use redis_values::{RedisErrorValue, RedisResult, RedisValue};
-pub use redis_bytes::RBytes;
+pub use command::{Command, PreparedCommand, StructuredCommand};
+pub use encoding::{encode_result, encode_value};
+pub use errors::{RedisError, ValidationError};
+pub use redis_bytes::{RBytes, MAX_KEY_SIZE};
+pub use redis_values::{ErrorKind, RedisErrorValue, RedisResult, RedisValue, SimpleString};
+#[cfg(feature = "bytes")]
+pub use shared_values::SharedRedisResult;
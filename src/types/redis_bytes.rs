@@ -3,6 +3,8 @@ use std::borrow::Cow;
 use std::fmt;
 use std::ops::Deref;
 
+use crate::ValidationError;
+
 #[derive(PartialEq, Eq)]
 pub struct RBytes<'a>(Cow<'a, [u8]>);
 
@@ -17,12 +19,32 @@ impl<'a> fmt::Debug for RBytes<'a> {
     }
 }
 
+// Redis rejects any key (or string value) longer than this
+pub const MAX_KEY_SIZE: usize = 512 * 1024 * 1024;
+
 impl<'a> RBytes<'a> {
+    // Converts a key, checking that it isn't too long for redis to accept
+    pub fn try_from_key(key: impl Into<RBytes<'a>>) -> Result<Self, ValidationError> {
+        let key = key.into();
+        check_key_len(key.0.len())?;
+        Ok(key)
+    }
+
     pub(crate) fn as_bytes(&'a self) -> &'a [u8] {
         self.0.deref()
     }
 }
 
+fn check_key_len(len: usize) -> Result<(), ValidationError> {
+    if len > MAX_KEY_SIZE {
+        return Err(ValidationError::KeyTooLarge {
+            size: len,
+            max: MAX_KEY_SIZE,
+        });
+    }
+    Ok(())
+}
+
 impl<'a> From<String> for RBytes<'a> {
     fn from(other: String) -> Self {
         RBytes(Cow::from(other.into_bytes()))
@@ -118,4 +140,22 @@ mod tests {
 
         assert_eq!("RBytes([255, 249, 0])", format!("{:?}", bytes));
     }
+
+    #[test]
+    fn keys_up_to_the_maximum_size_are_accepted() {
+        assert!(RBytes::try_from_key("my-key").is_ok());
+        assert_eq!(Ok(()), check_key_len(0));
+        assert_eq!(Ok(()), check_key_len(MAX_KEY_SIZE));
+    }
+
+    #[test]
+    fn oversized_keys_are_an_error() {
+        assert_eq!(
+            Err(ValidationError::KeyTooLarge {
+                size: MAX_KEY_SIZE + 1,
+                max: MAX_KEY_SIZE
+            }),
+            check_key_len(MAX_KEY_SIZE + 1)
+        );
+    }
 }
@@ -1,8 +1,21 @@
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 use std::string::FromUtf8Error;
 
-#[derive(Debug, Eq, PartialEq)]
+// The error prefixes that callers commonly need to react to.  Anything else is `Other`, and the raw
+// prefix is still available via `RedisErrorValue::kind`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ErrorKind {
+    NoAuth,
+    WrongPass,
+    NoPerm,
+    WrongType,
+    Other,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct RedisErrorValue {
     contents: String,
 }
@@ -21,18 +34,51 @@ impl RedisErrorValue {
     pub fn message(&self) -> Option<&str> {
         self.contents.splitn(2, ' ').nth(1)
     }
+
+    pub fn error_kind(&self) -> ErrorKind {
+        match self.kind() {
+            Some("NOAUTH") => ErrorKind::NoAuth,
+            Some("WRONGPASS") => ErrorKind::WrongPass,
+            Some("NOPERM") => ErrorKind::NoPerm,
+            Some("WRONGTYPE") => ErrorKind::WrongType,
+            _ => ErrorKind::Other,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+// The error exactly as redis sent it, e.g. `WRONGTYPE Operation against a key ...`
+impl fmt::Display for RedisErrorValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.contents)
+    }
+}
+
+// Not `Eq`, because RESP3 doubles can be NaN
+#[derive(Debug, PartialEq, Clone)]
 pub enum RedisResult {
     String(Vec<u8>),
     Integer(i64),
     Error(RedisErrorValue),
     Array(Vec<RedisResult>),
     Null,
+    // RESP3 only - a string along with a three-character format, e.g. `txt` or `mkd`
+    VerbatimString { format: [u8; 3], data: Vec<u8> },
+    // RESP3 only
+    Double(f64),
+    // RESP3 only
+    Boolean(bool),
+    // RESP3 only - an integer that may be too big for an i64, as its decimal digits
+    BigNumber(String),
+    // RESP3 only - key-value pairs, in the order the server sent them
+    Map(Vec<(RedisResult, RedisResult)>),
+    // RESP3 only - data the server sent unprompted (e.g. pubsub messages), rather than as the
+    // reply to a command
+    Push(Vec<RedisResult>),
+    // RESP3 only - an unordered collection, e.g. the reply to SMEMBERS
+    Set(Vec<RedisResult>),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum RedisValue {
     String(Vec<u8>),
     Integer(i64),
@@ -44,8 +90,129 @@ pub enum RedisValue {
 pub enum ConversionError {
     NoConversionTypeMatch { value: Option<RedisValue> },
     RedisReturnedError { error: RedisErrorValue },
-    CannotParseStringResponse { error: Box<Error> },
+    // `text` is the string that couldn't be parsed
+    CannotParseStringResponse { error: Box<Error>, text: String },
     InvalidUtf8String(FromUtf8Error),
+    IntegerOutOfRange { value: i64, target: &'static str },
+    // a sorted set score would have become NaN, e.g. by incrementing +inf by -inf
+    ScoreNotANumber { error: RedisErrorValue },
+}
+
+impl RedisResult {
+    // Some simple replies change shape depending on the connection - e.g. PING replies `+PONG`
+    // normally, but `["pong", <message>]` in subscribed mode.  This gets the simple string out of
+    // either shape, so that commands don't each need to know about every variation.  No other
+    // array is accepted.
+    pub fn coerce_simple_ok(self) -> Result<Vec<u8>, ConversionError> {
+        match self {
+            RedisResult::String(string) => Ok(string),
+            RedisResult::Array(parts) if is_subscribed_pong(&parts) => {
+                match parts.into_iter().next() {
+                    Some(RedisResult::String(string)) => Ok(string),
+                    _ => unreachable!("a subscribed-mode pong starts with a string"),
+                }
+            }
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: Option::try_from(self)?,
+            }),
+        }
+    }
+
+    // Checks for exactly `+OK`, unlike the `()` conversion which accepts any non-error reply
+    pub fn expect_ok(self) -> Result<(), ConversionError> {
+        match self {
+            RedisResult::String(ref string) if string == b"OK" => Ok(()),
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: Option::try_from(self)?,
+            }),
+        }
+    }
+}
+
+// These are mostly for building expected replies with `redis_value!`.  Strings become bulk
+// strings - RESP2 status replies are parsed into the same variant anyway.
+impl From<i64> for RedisResult {
+    fn from(value: i64) -> Self {
+        RedisResult::Integer(value)
+    }
+}
+
+impl From<f64> for RedisResult {
+    fn from(value: f64) -> Self {
+        RedisResult::Double(value)
+    }
+}
+
+impl From<bool> for RedisResult {
+    fn from(value: bool) -> Self {
+        RedisResult::Boolean(value)
+    }
+}
+
+impl From<&str> for RedisResult {
+    fn from(value: &str) -> Self {
+        RedisResult::String(value.as_bytes().to_vec())
+    }
+}
+
+impl From<&[u8]> for RedisResult {
+    fn from(value: &[u8]) -> Self {
+        RedisResult::String(value.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for RedisResult {
+    fn from(value: Vec<u8>) -> Self {
+        RedisResult::String(value)
+    }
+}
+
+impl From<RedisErrorValue> for RedisResult {
+    fn from(error: RedisErrorValue) -> Self {
+        RedisResult::Error(error)
+    }
+}
+
+// A status reply, e.g. `+OK` or `+PONG`, as opposed to a bulk string
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SimpleString(pub String);
+
+impl TryFrom<RedisResult> for SimpleString {
+    type Error = ConversionError;
+
+    fn try_from(value: RedisResult) -> Result<Self, Self::Error> {
+        String::from_utf8(value.coerce_simple_ok()?)
+            .map(SimpleString)
+            .map_err(ConversionError::InvalidUtf8String)
+    }
+}
+
+fn is_subscribed_pong(parts: &[RedisResult]) -> bool {
+    match parts {
+        [RedisResult::String(kind), _] => kind.eq_ignore_ascii_case(b"pong"),
+        _ => false,
+    }
+}
+
+// Numbers often come back as strings (e.g. from GET), so these are parsed as a fallback
+fn parse_string_response<T>(text: Vec<u8>) -> Result<T, ConversionError>
+where
+    T: FromStr,
+    T::Err: Error + 'static,
+{
+    let text = String::from_utf8(text).map_err(ConversionError::InvalidUtf8String)?;
+    text.parse()
+        .map_err(|err| ConversionError::CannotParseStringResponse {
+            error: Box::new(err),
+            text,
+        })
+}
+
+// Integer replies are always i64s, so smaller types are checked rather than truncated
+fn narrow_integer<T: TryFrom<i64>>(int: i64, target: &'static str) -> Result<T, ConversionError> {
+    T::try_from(int).map_err(|_| ConversionError::IntegerOutOfRange { value: int, target })
 }
 
 macro_rules! create_try_from_impl {
@@ -72,10 +239,29 @@ impl TryFrom<RedisResult> for Option<RedisValue> {
     fn try_from(r: RedisResult) -> Result<Self, Self::Error> {
         match r {
             RedisResult::String(string) => Ok(Some(RedisValue::String(string))),
+            RedisResult::VerbatimString { data, .. } => Ok(Some(RedisValue::String(data))),
+            // RESP2 sends doubles as strings anyway, so this matches what older servers return
+            RedisResult::Double(double) => {
+                Ok(Some(RedisValue::String(double.to_string().into_bytes())))
+            }
             RedisResult::Integer(int) => Ok(Some(RedisValue::Integer(int))),
-            RedisResult::Array(array) => Ok(Some(RedisValue::Array(
-                array
+            // likewise, RESP2 sends booleans as 0 or 1
+            RedisResult::Boolean(boolean) => Ok(Some(RedisValue::Integer(boolean as i64))),
+            RedisResult::Array(array) | RedisResult::Push(array) | RedisResult::Set(array) => {
+                Ok(Some(RedisValue::Array(
+                    array
+                        .into_iter()
+                        .map(Option::try_from)
+                        .collect::<Result<_, _>>()?,
+                )))
+            }
+            // RESP2 sends big numbers as bulk strings
+            RedisResult::BigNumber(digits) => Ok(Some(RedisValue::String(digits.into_bytes()))),
+            // RESP2 sends maps as a flat array of alternating keys and values
+            RedisResult::Map(pairs) => Ok(Some(RedisValue::Array(
+                pairs
                     .into_iter()
+                    .flat_map(|(key, value)| vec![key, value])
                     .map(Option::try_from)
                     .collect::<Result<_, _>>()?,
             ))),
@@ -108,15 +294,33 @@ impl TryFrom<RedisResult> for Option<()> {
     }
 }
 
+// Commands like `SET ... NX` reply with `+OK` when they did something, and a null when they
+// didn't, whereas commands like `MSETNX` reply with `1` or `0`.  Both end up meaning the same.
+impl TryFrom<RedisResult> for bool {
+    type Error = ConversionError;
+
+    fn try_from(r: RedisResult) -> Result<Self, Self::Error> {
+        match r {
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            RedisResult::Boolean(boolean) => Ok(boolean),
+            RedisResult::Null => Ok(false),
+            RedisResult::Integer(0) => Ok(false),
+            _ => Ok(true),
+        }
+    }
+}
+
 impl TryFrom<RedisResult> for Option<String> {
     type Error = ConversionError;
 
     fn try_from(value: RedisResult) -> Result<Self, Self::Error> {
         match value {
             RedisResult::Null => Ok(None),
-            RedisResult::String(bytes) => String::from_utf8(bytes)
-                .map(Option::Some)
-                .map_err(ConversionError::InvalidUtf8String),
+            RedisResult::String(bytes) | RedisResult::VerbatimString { data: bytes, .. } => {
+                String::from_utf8(bytes)
+                    .map(Option::Some)
+                    .map_err(ConversionError::InvalidUtf8String)
+            }
             RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
             _ => Err(ConversionError::NoConversionTypeMatch {
                 value: Option::try_from(value).unwrap(),
@@ -127,157 +331,99 @@ impl TryFrom<RedisResult> for Option<String> {
 
 create_try_from_impl! { Option<isize>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as isize)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "isize")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<i64>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as i64)),
+    RedisResult::Integer(int) => Ok(Some(int)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<i32>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as i32)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "i32")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<i16>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as i16)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "i16")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<i8>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as i8)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "i8")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<usize>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as usize)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "usize")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<u64>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as u64)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "u64")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<u32>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as u32)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "u32")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<u16>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as u16)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "u16")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<u8>; value => {
     RedisResult::Null => Ok(None),
-    RedisResult::Integer(int) => Ok(Some(int as u8)),
+    RedisResult::Integer(int) => Ok(Some(narrow_integer(int, "u8")?)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<f64>; value => {
     RedisResult::Null => Ok(None),
+    RedisResult::Double(double) => Ok(Some(double)),
     RedisResult::Integer(int) => Ok(Some(int as f64)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
 create_try_from_impl! { Option<f32>; value => {
     RedisResult::Null => Ok(None),
+    RedisResult::Double(double) => Ok(Some(double as f32)),
     RedisResult::Integer(int) => Ok(Some(int as f32)),
     RedisResult::String(text) => Ok(Some(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     )),
 }}
 
@@ -286,6 +432,10 @@ create_try_from_impl! { String; value => {
         String::from_utf8(text)
             .map_err(ConversionError::InvalidUtf8String)?,
     ),
+    RedisResult::VerbatimString { data, .. } => Ok(
+        String::from_utf8(data)
+            .map_err(ConversionError::InvalidUtf8String)?,
+    ),
 }}
 
 create_try_from_impl! { i64; value => {
@@ -293,13 +443,9 @@ create_try_from_impl! { i64; value => {
 }}
 
 create_try_from_impl! { f64; value => {
+    RedisResult::Double(double) => Ok(double),
     RedisResult::String(text) => Ok(
-        String::from_utf8(text)
-            .map_err(ConversionError::InvalidUtf8String)?
-            .parse()
-            .map_err(|err| ConversionError::CannotParseStringResponse {
-                error: Box::new(err),
-            })?,
+        parse_string_response(text)?,
     ),
 }}
 
@@ -309,6 +455,7 @@ impl TryFrom<RedisResult> for Option<Vec<u8>> {
     fn try_from(r: RedisResult) -> Result<Self, Self::Error> {
         match r {
             RedisResult::String(string) => Ok(Some(string)),
+            RedisResult::VerbatimString { data, .. } => Ok(Some(data)),
             RedisResult::Null => Ok(None),
             RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
             _ => Err(ConversionError::NoConversionTypeMatch {
@@ -318,9 +465,103 @@ impl TryFrom<RedisResult> for Option<Vec<u8>> {
     }
 }
 
+// Each element of the array is converted separately, so nulls (e.g. missing keys in MGET) become
+// `None` without affecting the rest of the array
+impl<T> TryFrom<RedisResult> for Vec<Option<T>>
+where
+    RedisResult: TryInto<Option<T>, Error = ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(r: RedisResult) -> Result<Self, Self::Error> {
+        match r {
+            RedisResult::Array(elements) | RedisResult::Set(elements) => {
+                elements.into_iter().map(TryInto::try_into).collect()
+            }
+            RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+            _ => Err(ConversionError::NoConversionTypeMatch {
+                value: Option::try_from(r)?,
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::errors::RedisError;
+
+    #[test]
+    fn null_converts_to_false() {
+        let result: bool = RedisResult::Null.try_into().unwrap();
+        assert!(!result);
+    }
+
+    #[test]
+    fn ok_converts_to_true() {
+        let result: bool = RedisResult::String(b"OK".to_vec()).try_into().unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn integers_convert_to_bools() {
+        let zero: bool = RedisResult::Integer(0).try_into().unwrap();
+        let one: bool = RedisResult::Integer(1).try_into().unwrap();
+        assert_eq!((false, true), (zero, one));
+    }
+
+    #[test]
+    fn errors_do_not_convert_to_bools() {
+        let result: Result<bool, _> =
+            RedisResult::Error(RedisErrorValue::new("ERR bad")).try_into();
+        match result {
+            Err(ConversionError::RedisReturnedError { error }) => {
+                assert_eq!(Some("ERR"), error.kind())
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn integers_that_do_not_fit_the_target_type_are_an_error() {
+        let result: Result<Option<u8>, _> = RedisResult::Integer(300).try_into();
+        match result {
+            Err(ConversionError::IntegerOutOfRange { value, target }) => {
+                assert_eq!((300, "u8"), (value, target))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let result: Result<Option<u32>, _> = RedisResult::Integer(-1).try_into();
+        match result {
+            Err(ConversionError::IntegerOutOfRange { value, target }) => {
+                assert_eq!((-1, "u32"), (value, target))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn integers_that_fit_the_target_type_are_converted() {
+        let result: Option<u8> = RedisResult::Integer(255).try_into().unwrap();
+        assert_eq!(Some(255), result);
+
+        let result: Option<i8> = RedisResult::Integer(-128).try_into().unwrap();
+        assert_eq!(Some(-128), result);
+    }
+
+    #[test]
+    fn arrays_convert_to_vecs_with_nulls_as_none() {
+        let result: Vec<Option<i64>> = RedisResult::Array(vec![
+            RedisResult::Integer(1),
+            RedisResult::Null,
+            RedisResult::String(b"3".to_vec()),
+        ])
+        .try_into()
+        .unwrap();
+
+        assert_eq!(vec![Some(1), None, Some(3)], result);
+    }
 
     #[test]
     fn redis_error_contains_correct_two_parts() {
@@ -332,4 +573,116 @@ mod tests {
             error.message()
         )
     }
+
+    #[test]
+    fn redis_error_is_classified_by_its_prefix() {
+        assert_eq!(
+            ErrorKind::NoAuth,
+            RedisErrorValue::new("NOAUTH Authentication required.").error_kind()
+        );
+        assert_eq!(
+            ErrorKind::WrongPass,
+            RedisErrorValue::new("WRONGPASS invalid username-password pair").error_kind()
+        );
+        assert_eq!(
+            ErrorKind::Other,
+            RedisErrorValue::new("ERR unknown command").error_kind()
+        );
+    }
+
+    #[test]
+    fn verbatim_strings_convert_like_normal_strings() {
+        let verbatim = || RedisResult::VerbatimString {
+            format: *b"txt",
+            data: b"Some string".to_vec(),
+        };
+
+        let text: String = verbatim().try_into().unwrap();
+        assert_eq!("Some string", text);
+        let text: Option<String> = verbatim().try_into().unwrap();
+        assert_eq!(Some("Some string".to_string()), text);
+        let bytes: Option<Vec<u8>> = verbatim().try_into().unwrap();
+        assert_eq!(Some(b"Some string".to_vec()), bytes);
+    }
+
+    #[test]
+    fn doubles_convert_directly_into_floats() {
+        let double: f64 = RedisResult::Double(2.75).try_into().unwrap();
+        assert_eq!(2.75, double);
+        let double: Option<f64> = RedisResult::Double(2.75).try_into().unwrap();
+        assert_eq!(Some(2.75), double);
+
+        // and RESP2 replies still convert to the same value
+        let double: f64 = RedisResult::String(b"2.75".to_vec()).try_into().unwrap();
+        assert_eq!(2.75, double);
+    }
+
+    #[test]
+    fn resp2_and_resp3_booleans_convert_to_the_same_bool() {
+        let resp3: bool = RedisResult::Boolean(true).try_into().unwrap();
+        let resp2: bool = RedisResult::Integer(1).try_into().unwrap();
+        assert!(resp3);
+        assert!(resp2);
+
+        let resp3: bool = RedisResult::Boolean(false).try_into().unwrap();
+        let resp2: bool = RedisResult::Integer(0).try_into().unwrap();
+        assert!(!resp3);
+        assert!(!resp2);
+    }
+
+    #[test]
+    fn sets_and_big_numbers_convert_like_their_resp2_equivalents() {
+        let set: Vec<Option<i64>> = RedisResult::Set(vec![RedisResult::Integer(1)])
+            .try_into()
+            .unwrap();
+        assert_eq!(vec![Some(1)], set);
+
+        let big_number: Option<RedisValue> =
+            RedisResult::BigNumber("3492890328409238509324850943850943825024385".to_string())
+                .try_into()
+                .unwrap();
+        assert_eq!(
+            Some(RedisValue::String(
+                b"3492890328409238509324850943850943825024385".to_vec()
+            )),
+            big_number
+        );
+    }
+
+    #[test]
+    fn maps_convert_to_the_same_flat_array_as_resp2() {
+        let resp3: Option<RedisValue> = RedisResult::Map(vec![(
+            RedisResult::String(b"field".to_vec()),
+            RedisResult::Integer(1),
+        )])
+        .try_into()
+        .unwrap();
+        let resp2: Option<RedisValue> = RedisResult::Array(vec![
+            RedisResult::String(b"field".to_vec()),
+            RedisResult::Integer(1),
+        ])
+        .try_into()
+        .unwrap();
+        assert_eq!(resp2, resp3);
+    }
+
+    #[test]
+    fn unparseable_numbers_keep_the_original_string() {
+        let result: Result<Option<i64>, _> = RedisResult::String(b"notanumber".to_vec()).try_into();
+        let error = result.unwrap_err();
+        match &error {
+            ConversionError::CannotParseStringResponse { text, .. } => {
+                assert_eq!("notanumber", text)
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+
+        // the text should survive being turned into the error that callers see
+        match RedisError::from_conversion_error(error) {
+            RedisError::StringParseError(_, text) => {
+                assert_eq!("notanumber", text)
+            }
+            other => panic!("expected a string parse error, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,83 @@
+use crate::{RedisErrorValue, RedisResult};
+use bytes::Bytes;
+
+// The same as `RedisResult`, but strings are slices of the buffer that the reply was read into,
+// rather than each being copied out into its own `Vec`.  Converting into a `RedisResult` copies
+// the strings, so the rest of the API can be used as normal.
+#[derive(Debug, PartialEq)]
+pub enum SharedRedisResult {
+    String(Bytes),
+    Integer(i64),
+    Error(RedisErrorValue),
+    Array(Vec<SharedRedisResult>),
+    Null,
+    VerbatimString { format: [u8; 3], data: Bytes },
+    Double(f64),
+    Boolean(bool),
+    BigNumber(String),
+    Map(Vec<(SharedRedisResult, SharedRedisResult)>),
+    Push(Vec<SharedRedisResult>),
+    Set(Vec<SharedRedisResult>),
+}
+
+impl From<SharedRedisResult> for RedisResult {
+    fn from(value: SharedRedisResult) -> Self {
+        match value {
+            SharedRedisResult::String(string) => RedisResult::String(string.to_vec()),
+            SharedRedisResult::Integer(int) => RedisResult::Integer(int),
+            SharedRedisResult::Error(error) => RedisResult::Error(error),
+            SharedRedisResult::Array(elements) => {
+                RedisResult::Array(elements.into_iter().map(RedisResult::from).collect())
+            }
+            SharedRedisResult::Null => RedisResult::Null,
+            SharedRedisResult::VerbatimString { format, data } => RedisResult::VerbatimString {
+                format,
+                data: data.to_vec(),
+            },
+            SharedRedisResult::Double(double) => RedisResult::Double(double),
+            SharedRedisResult::Boolean(boolean) => RedisResult::Boolean(boolean),
+            SharedRedisResult::BigNumber(digits) => RedisResult::BigNumber(digits),
+            SharedRedisResult::Map(pairs) => RedisResult::Map(
+                pairs
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+            ),
+            SharedRedisResult::Push(elements) => {
+                RedisResult::Push(elements.into_iter().map(RedisResult::from).collect())
+            }
+            SharedRedisResult::Set(elements) => {
+                RedisResult::Set(elements.into_iter().map(RedisResult::from).collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converting_copies_nested_strings() {
+        let shared = SharedRedisResult::Array(vec![
+            SharedRedisResult::String(Bytes::from_static(b"hello")),
+            SharedRedisResult::Integer(42),
+            SharedRedisResult::VerbatimString {
+                format: *b"txt",
+                data: Bytes::from_static(b"world"),
+            },
+        ]);
+
+        assert_eq!(
+            RedisResult::Array(vec![
+                RedisResult::String(b"hello".to_vec()),
+                RedisResult::Integer(42),
+                RedisResult::VerbatimString {
+                    format: *b"txt",
+                    data: b"world".to_vec(),
+                },
+            ]),
+            RedisResult::from(shared)
+        );
+    }
+}
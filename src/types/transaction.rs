@@ -0,0 +1,151 @@
+use crate::types::redis_values::{ConversionError, RedisResult};
+use crate::types::StructuredCommand;
+use std::any::Any;
+use std::convert::TryFrom;
+
+// A command whose output type has been erased, so that commands with different outputs can be
+// queued up together, e.g. between MULTI and EXEC
+pub trait QueuedCommand {
+    fn queued_bytes(&self) -> Vec<u8>;
+    fn convert_boxed(self: Box<Self>, result: RedisResult)
+        -> Result<Box<dyn Any>, ConversionError>;
+}
+
+impl<Cmd> QueuedCommand for Cmd
+where
+    Cmd: StructuredCommand,
+    Cmd::Output: 'static,
+{
+    fn queued_bytes(&self) -> Vec<u8> {
+        self.get_bytes()
+    }
+
+    fn convert_boxed(
+        self: Box<Self>,
+        result: RedisResult,
+    ) -> Result<Box<dyn Any>, ConversionError> {
+        let output = (*self).convert_redis_result(result)?;
+        Ok(Box::new(output))
+    }
+}
+
+// The output of one queued command, still to be downcast to the command's output type
+pub type QueuedOutput = Result<Box<dyn Any>, ConversionError>;
+
+// Converts each element of an EXEC reply with the command that was queued in the same position.
+// Each output can be recovered with `downcast`, using the output type of its command.  Commands
+// can fail individually inside a transaction, so each conversion has its own result.  A null
+// reply (the transaction was aborted because a WATCHed key changed) gives `None`.
+pub fn apply_exec_array<'a>(
+    array: RedisResult,
+    commands: Vec<Box<dyn QueuedCommand + 'a>>,
+) -> Result<Option<Vec<QueuedOutput>>, ConversionError> {
+    match array {
+        RedisResult::Array(replies) if replies.len() == commands.len() => Ok(Some(
+            replies
+                .into_iter()
+                .zip(commands)
+                .map(|(reply, command)| command.convert_boxed(reply))
+                .collect(),
+        )),
+        RedisResult::Null => Ok(None),
+        RedisResult::Error(error) => Err(ConversionError::RedisReturnedError { error }),
+        _ => Err(ConversionError::NoConversionTypeMatch {
+            value: Option::try_from(array)?,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RedisErrorValue;
+    use std::convert::TryInto;
+
+    struct MockInteger;
+
+    impl StructuredCommand for MockInteger {
+        type Output = i64;
+
+        fn get_bytes(&self) -> Vec<u8> {
+            b"integer".to_vec()
+        }
+
+        fn convert_redis_result(
+            self,
+            result: RedisResult,
+        ) -> Result<Self::Output, ConversionError> {
+            result.try_into()
+        }
+    }
+
+    struct MockString;
+
+    impl StructuredCommand for MockString {
+        type Output = String;
+
+        fn get_bytes(&self) -> Vec<u8> {
+            b"string".to_vec()
+        }
+
+        fn convert_redis_result(
+            self,
+            result: RedisResult,
+        ) -> Result<Self::Output, ConversionError> {
+            result.try_into()
+        }
+    }
+
+    fn queue() -> Vec<Box<dyn QueuedCommand>> {
+        vec![Box::new(MockInteger), Box::new(MockString)]
+    }
+
+    #[test]
+    fn each_element_is_converted_by_its_own_command() {
+        let array = RedisResult::Array(vec![
+            RedisResult::Integer(42),
+            RedisResult::String(b"hello".to_vec()),
+        ]);
+
+        let mut outputs = apply_exec_array(array, queue())
+            .unwrap()
+            .unwrap()
+            .into_iter();
+
+        let first = outputs.next().unwrap().unwrap();
+        assert_eq!(Some(&42), first.downcast_ref::<i64>());
+        let second = outputs.next().unwrap().unwrap();
+        assert_eq!(Some(&"hello".to_string()), second.downcast_ref::<String>());
+    }
+
+    #[test]
+    fn failed_commands_do_not_affect_the_others() {
+        let array = RedisResult::Array(vec![
+            RedisResult::Error(RedisErrorValue::new("WRONGTYPE bad")),
+            RedisResult::String(b"hello".to_vec()),
+        ]);
+
+        let outputs = apply_exec_array(array, queue()).unwrap().unwrap();
+        assert!(outputs[0].is_err());
+        assert!(outputs[1].is_ok());
+    }
+
+    #[test]
+    fn aborted_transactions_have_no_outputs() {
+        assert!(apply_exec_array(RedisResult::Null, queue())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn replies_must_match_the_number_of_commands() {
+        let array = RedisResult::Array(vec![RedisResult::Integer(42)]);
+        assert!(apply_exec_array(array, queue()).is_err());
+    }
+
+    #[test]
+    fn queued_commands_keep_their_bytes() {
+        let commands = queue();
+        assert_eq!(b"integer".to_vec(), commands[0].queued_bytes());
+    }
+}
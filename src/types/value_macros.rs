@@ -0,0 +1,63 @@
+// Builds a `RedisResult` tree, mostly for writing out expected replies in tests, e.g.
+// `redis_value!([1, "foo", [2, 3], null])`.  Arrays are written with brackets and `null` is
+// `RedisResult::Null`; anything else is converted with `RedisResult::from`, so strings become
+// bulk strings, integers become integers, and a `RedisErrorValue` becomes an error reply.
+#[macro_export]
+macro_rules! redis_value {
+    (null) => {
+        $crate::RedisResult::Null
+    };
+    ([ $($elements:tt)* ]) => {
+        $crate::RedisResult::Array($crate::redis_value!(@array [] $($elements)*))
+    };
+
+    // Array elements can be arbitrary expressions, so they're munched one at a time rather than
+    // matched as a repetition.  Converted elements are accumulated in the first brackets.
+    (@array [$($done:expr,)*]) => {
+        vec![$($done,)*]
+    };
+    (@array [$($done:expr,)*] null $(, $($rest:tt)*)?) => {
+        $crate::redis_value!(@array [$($done,)* $crate::RedisResult::Null,] $($($rest)*)?)
+    };
+    (@array [$($done:expr,)*] [ $($inner:tt)* ] $(, $($rest:tt)*)?) => {
+        $crate::redis_value!(
+            @array [$($done,)* $crate::redis_value!([$($inner)*]),] $($($rest)*)?
+        )
+    };
+    (@array [$($done:expr,)*] $next:expr $(, $($rest:tt)*)?) => {
+        $crate::redis_value!(@array [$($done,)* $crate::RedisResult::from($next),] $($($rest)*)?)
+    };
+
+    ($other:expr) => {
+        $crate::RedisResult::from($other)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RedisErrorValue, RedisResult};
+
+    #[test]
+    fn scalars_are_converted() {
+        assert_eq!(RedisResult::Integer(-1), redis_value!(-1));
+        assert_eq!(RedisResult::String(b"foo".to_vec()), redis_value!("foo"));
+        assert_eq!(RedisResult::Null, redis_value!(null));
+        assert_eq!(
+            RedisResult::Error(RedisErrorValue::new("ERR oops")),
+            redis_value!(RedisErrorValue::new("ERR oops"))
+        );
+    }
+
+    #[test]
+    fn arrays_can_be_nested() {
+        assert_eq!(
+            RedisResult::Array(vec![
+                RedisResult::Integer(1),
+                RedisResult::String(b"foo".to_vec()),
+                RedisResult::Array(vec![RedisResult::Integer(2), RedisResult::Null]),
+                RedisResult::Array(vec![]),
+            ]),
+            redis_value!([1, "foo", [2, null], [],])
+        );
+    }
+}
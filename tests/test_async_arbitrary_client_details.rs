@@ -13,7 +13,7 @@ async fn can_ping_the_default_server() {
     let server = load_redis_instance();
     let mut client = reredis::AsyncClient::new(server.address()).await.unwrap();
 
-    assert_eq!((), client.issue(ping()).unwrap());
+    assert_eq!((), client.issue(ping()).await.unwrap());
 }
 
 #[runtime::test]
@@ -23,5 +23,14 @@ async fn can_login_with_authorisation() {
         .build();
     let mut client = reredis::AsyncClient::with_auth(server.address(), "test password").await.unwrap();
 
-    assert_eq!((), client.issue(ping()).unwrap());
+    assert_eq!((), client.issue(ping()).await.unwrap());
+}
+
+#[runtime::test]
+async fn replies_are_converted_to_the_command_output() {
+    let server = load_redis_instance();
+    let mut client = reredis::AsyncClient::new(server.address()).await.unwrap();
+
+    client.issue(set("k", 42)).await.unwrap();
+    assert_eq!(Some(42), client.issue(get::<i64, _>("k")).await.unwrap());
 }
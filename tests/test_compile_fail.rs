@@ -0,0 +1,7 @@
+#![cfg(feature = "sync-client")]
+
+#[test]
+fn commands_that_need_acknowledging_cannot_be_issued_directly() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
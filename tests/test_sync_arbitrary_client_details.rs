@@ -34,3 +34,258 @@ fn can_emit_echo_command() {
 
     assert_eq!("test", client.issue(echo("test")).unwrap());
 }
+
+#[test]
+fn logging_in_with_the_wrong_password_is_a_classified_error() {
+    let server = RedisInstance::new()
+        .with_setting("requirepass", ["\"test password\""])
+        .build();
+
+    match reredis::SyncClient::with_auth(server.address(), "wrong password") {
+        Err(reredis::RedisError::RedisReturnedError(error)) => {
+            assert_eq!(reredis::ErrorKind::WrongPass, error.error_kind())
+        }
+        other => panic!("expected a WRONGPASS error, got {:?}", other),
+    }
+}
+
+#[test]
+fn commands_without_authorisation_are_a_classified_error() {
+    let server = RedisInstance::new()
+        .with_setting("requirepass", ["\"test password\""])
+        .build();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    match client.issue(ping()) {
+        Err(reredis::RedisError::RedisReturnedError(error)) => {
+            assert_eq!(reredis::ErrorKind::NoAuth, error.error_kind())
+        }
+        other => panic!("expected a NOAUTH error, got {:?}", other),
+    }
+}
+
+#[test]
+fn can_login_as_an_acl_user() {
+    let server = RedisInstance::new()
+        .with_setting("user", ["test-user", "on", ">test-password", "~*", "+@all"])
+        .build();
+    let mut client =
+        reredis::SyncClient::with_user_auth(server.address(), "test-user", "test-password")
+            .unwrap();
+
+    assert_eq!((), client.issue(ping()).unwrap());
+}
+
+#[test]
+fn resp3_clients_fall_back_to_resp2_on_servers_without_hello() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"HELLO" => b"-ERR unknown command `HELLO`, with args beginning with: `3`, \r\n".to_vec(),
+        b"PING" => b"+PONG\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+
+    let mut client = reredis::SyncClient::new_resp3(address).unwrap();
+    assert_eq!(2, client.protocol_version());
+    client.issue(ping()).unwrap();
+}
+
+#[test]
+fn resp3_clients_can_read_resp3_replies() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new_resp3(server.address()).unwrap();
+    assert_eq!(3, client.protocol_version());
+
+    // missing keys are sent as the RESP3 null
+    assert_eq!(None, client.issue(get::<String, _>("missing")).unwrap());
+    client.issue(set("my-key", "value")).unwrap();
+    assert_eq!(
+        Some("value".to_string()),
+        client.issue(get("my-key")).unwrap()
+    );
+}
+
+#[test]
+fn metrics_sink_reports_the_verb_and_latency_of_each_command() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    let address = mock_server(|command| {
+        std::thread::sleep(Duration::from_millis(1));
+        match command[0].as_slice() {
+            b"PING" => b"+PONG\r\n".to_vec(),
+            b"SET" => b"+OK\r\n".to_vec(),
+            b"GET" => b"$5\r\nvalue\r\n".to_vec(),
+            _ => b"-ERR unexpected command\r\n".to_vec(),
+        }
+    });
+
+    let metrics = Arc::new(Mutex::new(Vec::new()));
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    let sink = metrics.clone();
+    client.set_metrics_sink(move |verb, latency| {
+        sink.lock().unwrap().push((verb.to_string(), latency))
+    });
+
+    client.issue(ping()).unwrap();
+    client.issue(set("key", "value")).unwrap();
+    client
+        .issue_with_timeout(get::<String, _>("key"), Duration::from_secs(5))
+        .unwrap();
+
+    let metrics = metrics.lock().unwrap();
+    let verbs: Vec<_> = metrics.iter().map(|(verb, _)| verb.as_str()).collect();
+    assert_eq!(vec!["PING", "SET", "GET"], verbs);
+    assert!(metrics
+        .iter()
+        .all(|(_, latency)| *latency >= Duration::from_millis(1)));
+}
+
+#[test]
+fn commands_are_resent_after_a_dropped_connection() {
+    let address = scripted_server(|index, connection| {
+        // the first connection is dropped as soon as the command arrives
+        connection.read_command();
+        if index > 0 {
+            connection.write(b":7\r\n");
+            connection.hold_open();
+        }
+    });
+
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    assert_eq!(7, client.issue_with_retry(incr("counter"), 3).unwrap());
+}
+
+#[test]
+fn retried_commands_log_in_again_after_a_dropped_connection() {
+    let address = scripted_server(|index, connection| {
+        match connection.read_command() {
+            Some(command) if command == [b"AUTH".to_vec(), b"secret".to_vec()] => {
+                connection.write(b"+OK\r\n")
+            }
+            _ => return connection.write(b"-NOAUTH Authentication required.\r\n"),
+        }
+        connection.read_command();
+        if index > 0 {
+            connection.write(b":7\r\n");
+            connection.hold_open();
+        }
+    });
+
+    let mut client = reredis::SyncClient::with_auth(address, "secret").unwrap();
+    assert_eq!(7, client.issue_with_retry(incr("counter"), 3).unwrap());
+}
+
+#[test]
+fn retries_give_up_after_the_last_attempt() {
+    // every connection is closed straight away
+    let address = scripted_server(|_, _| {});
+
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    match client.issue_with_retry(incr("counter"), 3) {
+        Err(reredis::RedisError::ConnectionError(_)) => {}
+        other => panic!("expected a connection error, got {:?}", other),
+    }
+}
+
+#[test]
+fn sent_and_received_bytes_are_counted() {
+    use reredis::StructuredCommand;
+
+    let address = mock_server(|_| b"$5\r\nvalue\r\n".to_vec());
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    assert_eq!((0, 0), (client.bytes_sent(), client.bytes_received()));
+
+    let encoded = get::<String, _>("key").get_bytes();
+    client.issue(get::<String, _>("key")).unwrap();
+    assert_eq!(encoded.len() as u64, client.bytes_sent());
+    assert_eq!(11, client.bytes_received());
+}
+
+#[test]
+fn strict_clients_reject_keys_that_are_not_utf8() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        // COMMAND INFO for SET, which has a single key as its first argument
+        b"COMMAND" => b"*1\r\n*6\r\n$3\r\nset\r\n:-3\r\n*0\r\n:1\r\n:1\r\n:1\r\n".to_vec(),
+        b"SET" => b"+OK\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    client.require_utf8_keys(true);
+
+    match client.issue(set(b"key\xFF".as_ref(), "value")) {
+        Err(reredis::RedisError::ValidationError(reredis::ValidationError::NonUtf8Key { key })) => {
+            assert_eq!(b"key\xFF".to_vec(), key)
+        }
+        other => panic!("expected a non-UTF-8 key error, got {:?}", other),
+    }
+
+    // only keys are checked, so binary values are still fine
+    client.issue(set("key", b"\xFF".as_ref())).unwrap();
+
+    client.require_utf8_keys(false);
+    client.issue(set(b"key\xFF".as_ref(), "value")).unwrap();
+}
+
+#[test]
+fn strict_clients_check_every_command_that_is_sent() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"COMMAND" => b"*1\r\n*6\r\n$3\r\nset\r\n:-3\r\n*0\r\n:1\r\n:1\r\n:1\r\n".to_vec(),
+        b"SET" => b"+OK\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    client.require_utf8_keys(true);
+
+    let is_non_utf8_key = |error: reredis::RedisError| match error {
+        reredis::RedisError::ValidationError(reredis::ValidationError::NonUtf8Key { key }) => {
+            key == b"key\xFF".to_vec()
+        }
+        _ => false,
+    };
+    // the bad key is in the second of the pipelined commands
+    let pipelined = client
+        .pipeline((set("key", "value"), set(b"key\xFF".as_ref(), "value")))
+        .execute();
+    assert!(is_non_utf8_key(pipelined.unwrap_err()));
+    let retried = client.issue_with_retry(set(b"key\xFF".as_ref(), "value"), 3);
+    assert!(is_non_utf8_key(retried.unwrap_err()));
+
+    // nothing was sent, so the connection is still in step
+    client.issue(set("key", "value")).unwrap();
+}
+
+#[test]
+fn prepared_commands_get_the_same_replies_as_fresh_ones() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"GET" => b"$5\r\nvalue\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    let prepared = reredis::PreparedCommand::new(|| get::<String, _>("key"));
+
+    let fresh = client.issue(get::<String, _>("key")).unwrap();
+    for _ in 0..3 {
+        assert_eq!(fresh, client.issue_prepared(&prepared).unwrap());
+    }
+}
+
+#[test]
+fn single_threaded_clients_read_replies_themselves() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"SET" => b"+OK\r\n".to_vec(),
+        b"GET" => b"$5\r\nvalue\r\n".to_vec(),
+        // never replies
+        _ => Vec::new(),
+    });
+    let mut client = reredis::SyncClient::new_single_threaded(address).unwrap();
+
+    client.issue(set("key", "value")).unwrap();
+    assert_eq!(
+        (Some("value".to_string()), Some("value".to_string())),
+        client.pipeline((get("key"), get("key"))).execute().unwrap()
+    );
+    match client.issue_with_timeout(ping(), std::time::Duration::from_millis(50)) {
+        Err(reredis::RedisError::Timeout) => {}
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
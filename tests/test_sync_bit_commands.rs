@@ -52,7 +52,7 @@ fn bitcount_can_get_the_count_of_some_bits_in_a_string() {
     let mut client = reredis::SyncClient::new(server.address()).unwrap();
 
     client.issue(set("mykey", "foobar")).unwrap();
-    assert_eq!(10, client.issue(bitcount("mykey").in_range(0..1)).unwrap());
+    assert_eq!(10, client.issue(bitcount("mykey").in_range(0..2)).unwrap());
 }
 
 #[test]
@@ -61,16 +61,19 @@ fn bitcount_can_get_the_count_of_the_last_bits_in_a_string() {
     let mut client = reredis::SyncClient::new(server.address()).unwrap();
 
     client.issue(set("mykey", "foobar")).unwrap();
-    assert_eq!(7, client.issue(bitcount("mykey").in_range(-2..-1)).unwrap());
+    assert_eq!(
+        7,
+        client.issue(bitcount("mykey").in_range(-2..=-1)).unwrap()
+    );
 }
 
 #[test]
-fn bitcount_can_use_exclusive_upper_ranges_if_desired() {
+fn bitcount_can_use_inclusive_upper_ranges() {
     let server = load_redis_instance();
     let mut client = reredis::SyncClient::new(server.address()).unwrap();
 
     client.issue(set("mykey", "foobar")).unwrap();
-    assert_eq!(10, client.issue(bitcount("mykey").in_range(0..=2)).unwrap());
+    assert_eq!(10, client.issue(bitcount("mykey").in_range(0..=1)).unwrap());
 }
 
 #[test]
@@ -122,9 +125,15 @@ fn bitop_and_returns_combination_of_several_keys() {
     let server = load_redis_instance();
     let mut client = reredis::SyncClient::new(server.address()).unwrap();
 
-    client.issue(set("source-1", &[0b00101011, 0b00001101])).unwrap();
-    client.issue(set("source-2", &[0b01100010, 0b00010111])).unwrap();
-    client.issue(set("source-3", &[0b11101011, 0b00111011, 0b01010101])).unwrap();
+    client
+        .issue(set("source-1", &[0b00101011, 0b00001101]))
+        .unwrap();
+    client
+        .issue(set("source-2", &[0b01100010, 0b00010111]))
+        .unwrap();
+    client
+        .issue(set("source-3", &[0b11101011, 0b00111011, 0b01010101]))
+        .unwrap();
 
     assert_eq!(
         3,
@@ -148,9 +157,15 @@ fn bitop_or_returns_combination_of_several_keys() {
     let server = load_redis_instance();
     let mut client = reredis::SyncClient::new(server.address()).unwrap();
 
-    client.issue(set("source-1", &[0b00101011, 0b00001101])).unwrap();
-    client.issue(set("source-2", &[0b01100010, 0b00010111])).unwrap();
-    client.issue(set("source-3", &[0b01101011, 0b00111011, 0b01010101])).unwrap();
+    client
+        .issue(set("source-1", &[0b00101011, 0b00001101]))
+        .unwrap();
+    client
+        .issue(set("source-2", &[0b01100010, 0b00010111]))
+        .unwrap();
+    client
+        .issue(set("source-3", &[0b01101011, 0b00111011, 0b01010101]))
+        .unwrap();
 
     assert_eq!(
         3,
@@ -174,9 +189,15 @@ fn bitop_xor_returns_combination_of_several_keys() {
     let server = load_redis_instance();
     let mut client = reredis::SyncClient::new(server.address()).unwrap();
 
-    client.issue(set("source-1", &[0b00101011, 0b00001101])).unwrap();
-    client.issue(set("source-2", &[0b01100010, 0b00010111])).unwrap();
-    client.issue(set("source-3", &[0b01101010, 0b00111011, 0b01010101])).unwrap();
+    client
+        .issue(set("source-1", &[0b00101011, 0b00001101]))
+        .unwrap();
+    client
+        .issue(set("source-2", &[0b01100010, 0b00010111]))
+        .unwrap();
+    client
+        .issue(set("source-3", &[0b01101010, 0b00111011, 0b01010101]))
+        .unwrap();
 
     assert_eq!(
         3,
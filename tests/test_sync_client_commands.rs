@@ -0,0 +1,162 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+
+use crate::utils::{load_redis_instance, mock_server};
+use reredis::commands::Invalidation;
+use std::sync::mpsc::channel;
+use std::time::{Duration, Instant};
+
+#[test]
+fn client_tracking_can_be_enabled_and_disabled() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+    let mut invalidation_client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let redirect_id = invalidation_client.issue(client_id()).unwrap();
+
+    client
+        .issue(
+            client_tracking(true)
+                .redirect(redirect_id)
+                .prefix("user:")
+                .bcast(),
+        )
+        .unwrap();
+    client.issue(client_tracking(false)).unwrap();
+}
+
+#[test]
+fn tracked_keys_are_invalidated_when_another_client_changes_them() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new_resp3(server.address()).unwrap();
+    let mut writer = reredis::SyncClient::new(server.address()).unwrap();
+    let (tx_invalidations, rx_invalidations) = channel();
+    client.set_invalidation_handler(move |invalidation| {
+        tx_invalidations.send(invalidation).unwrap();
+    });
+
+    writer.issue(set("key", "before")).unwrap();
+    client.issue(client_tracking(true)).unwrap();
+    client.issue(get::<String, _>("key")).unwrap();
+    writer.issue(set("key", "after")).unwrap();
+
+    // the invalidation is pushed ahead of the next reply
+    client.issue(ping()).unwrap();
+    assert_eq!(
+        Invalidation::Keys(vec![b"key".to_vec()]),
+        rx_invalidations.try_recv().unwrap()
+    );
+}
+
+#[test]
+fn redirected_invalidations_are_read_by_the_subscribed_client() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+    let mut writer = reredis::SyncClient::new(server.address()).unwrap();
+    let mut invalidation_client = reredis::SyncClient::new(server.address()).unwrap();
+    let (tx_invalidations, rx_invalidations) = channel();
+    invalidation_client.set_invalidation_handler(move |invalidation| {
+        tx_invalidations.send(invalidation).unwrap();
+    });
+    let redirect_id = invalidation_client.issue(client_id()).unwrap();
+    let mut subscription = invalidation_client
+        .subscribe(vec!["__redis__:invalidate"])
+        .unwrap();
+
+    writer.issue(set("key", "before")).unwrap();
+    client
+        .issue(client_tracking(true).redirect(redirect_id))
+        .unwrap();
+    client.issue(get::<String, _>("key")).unwrap();
+    writer.issue(set("key", "after")).unwrap();
+
+    assert_eq!(
+        None,
+        subscription
+            .next_message_timeout(Duration::from_millis(200))
+            .unwrap()
+    );
+    assert_eq!(
+        Invalidation::Keys(vec![b"key".to_vec()]),
+        rx_invalidations.try_recv().unwrap()
+    );
+}
+
+#[test]
+fn pushed_invalidations_are_passed_to_the_handler() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"GET" => b">2\r\n$10\r\ninvalidate\r\n*1\r\n$3\r\nkey\r\n$5\r\nvalue\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    let (tx_invalidations, rx_invalidations) = channel();
+    client.set_invalidation_handler(move |invalidation| {
+        tx_invalidations.send(invalidation).unwrap();
+    });
+
+    assert_eq!(Some("value".to_string()), client.issue(get("key")).unwrap());
+    assert_eq!(
+        Invalidation::Keys(vec![b"key".to_vec()]),
+        rx_invalidations.try_recv().unwrap()
+    );
+}
+
+#[test]
+fn client_tracking_fails_when_redirecting_to_a_missing_client() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert!(client
+        .issue(client_tracking(true).redirect(999_999))
+        .is_err());
+}
+
+#[test]
+fn client_info_describes_the_current_connection() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let id = client.issue(client_id()).unwrap();
+    let info = client.issue(client_info()).unwrap();
+
+    assert_eq!(id, info.id);
+    assert_eq!(0, info.db);
+}
+
+#[test]
+fn client_list_includes_every_connection() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+    let mut other_client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let id = client.issue(client_id()).unwrap();
+    let other_id = other_client.issue(client_id()).unwrap();
+
+    let ids = client
+        .issue(client_list())
+        .unwrap()
+        .into_iter()
+        .map(|info| info.id)
+        .collect::<Vec<_>>();
+    assert!(ids.contains(&id));
+    assert!(ids.contains(&other_id));
+}
+
+#[test]
+fn client_pause_blocks_other_connections_until_it_elapses() {
+    let server = load_redis_instance();
+    let mut admin = reredis::SyncClient::new(server.address()).unwrap();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let start = Instant::now();
+    admin
+        .issue(client_pause(Duration::from_millis(500)).all())
+        .unwrap();
+    client.issue(ping()).unwrap();
+
+    assert!(start.elapsed() >= Duration::from_millis(400));
+}
@@ -7,6 +7,7 @@ use reredis::commands::*;
 
 use quickcheck::TestResult;
 use quickcheck_macros::quickcheck;
+use std::cell::Cell;
 use std::collections::HashSet;
 use std::thread;
 use std::time::Duration;
@@ -48,7 +49,11 @@ fn qc_can_insert_an_arbitrary_key_with_a_timeout_into_redis(
 
     let mut client = reredis::SyncClient::new(server.address()).unwrap();
     client
-        .issue(set(key.clone(), value).with_expiry(Duration::from_secs(timeout as u64)))
+        .issue(
+            set(key.clone(), value)
+                .with_expiry(Duration::from_secs(timeout as u64))
+                .unwrap(),
+        )
         .unwrap();
 
     let returned_value = client.issue(get(key)).unwrap();
@@ -64,7 +69,11 @@ fn inserting_a_key_with_a_timeout_expires_the_key() {
     let mut client = reredis::SyncClient::new(server.address()).unwrap();
 
     client
-        .issue(set("test-key", 0).with_expiry(Duration::from_secs(1)))
+        .issue(
+            set("test-key", 0)
+                .with_expiry(Duration::from_secs(1))
+                .unwrap(),
+        )
         .unwrap();
 
     let returned = client.issue(get::<i64, _>("test-key")).unwrap();
@@ -158,6 +167,30 @@ fn mget_returns_a_list_of_keys_with_the_same_type() {
     )
 }
 
+#[test]
+fn mset_and_mget_round_trip_binary_values() {
+    let server = load_redis_instance();
+
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let binary = vec![0x00, 0xFF, b'\r', b'\n', 0xC3, 0x28];
+    let empty: Vec<u8> = Vec::new();
+    client
+        .issue(
+            mset()
+                .add("binary", binary.as_slice())
+                .add("empty", empty.as_slice()),
+        )
+        .unwrap();
+
+    assert_eq!(
+        vec![Some(binary), Some(empty), None],
+        client
+            .issue(mget::<Vec<u8>>().with_keys(vec!["binary", "empty", "missing"]))
+            .unwrap()
+    );
+}
+
 #[quickcheck]
 fn qc_mget_and_mset_can_work_together(pairs: Vec<(String, i64)>) -> TestResult {
     if pairs.len() == 0 {
@@ -207,3 +240,133 @@ fn getset_returns_a_value_if_the_key_has_previously_been_set() {
     let value = client.issue(getset("test-key", 120)).unwrap();
     assert_eq!(value, Some("this is a value".to_string()));
 }
+
+#[test]
+fn missing_numbers_can_default_to_zero() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert_eq!(
+        0,
+        client.issue(get::<i64, _>("missing").or_default()).unwrap()
+    );
+}
+
+#[test]
+fn invalid_utf8_can_be_read_lossily() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("my-key", b"bad \xFF bytes")).unwrap();
+
+    assert!(client.issue(get::<String, _>("my-key")).is_err());
+    assert_eq!(
+        Some("bad \u{FFFD} bytes".to_string()),
+        client.issue(get_lossy("my-key")).unwrap()
+    );
+}
+
+#[test]
+fn mget_stream_pairs_keys_with_values_lazily() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let keys: Vec<String> = (0..3000).map(|i| format!("key-{}", i)).collect();
+    for (i, key) in keys.iter().enumerate().filter(|(i, _)| i % 2 == 0) {
+        client.issue(set(key.as_str(), i)).unwrap();
+    }
+
+    let taken = Cell::new(0);
+    let key_source = keys.iter().map(|key| {
+        taken.set(taken.get() + 1);
+        key.as_str()
+    });
+    let mut stream = client.mget_stream::<_, i64>(key_source, 100);
+
+    assert_eq!(
+        (b"key-0".to_vec(), Some(0)),
+        stream.next().unwrap().unwrap()
+    );
+    assert_eq!(100, taken.get());
+
+    let rest: Vec<_> = stream.map(Result::unwrap).collect();
+    assert_eq!(2999, rest.len());
+    assert_eq!(3000, taken.get());
+    for (i, (key, value)) in rest.into_iter().enumerate().map(|(i, pair)| (i + 1, pair)) {
+        assert_eq!(format!("key-{}", i).into_bytes(), key);
+        assert_eq!(if i % 2 == 0 { Some(i as i64) } else { None }, value);
+    }
+}
+
+#[test]
+fn take_returns_the_value_and_leaves_the_key_absent() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("token", "secret")).unwrap();
+    assert_eq!(
+        Some("secret".to_string()),
+        client.take::<String>("token").unwrap()
+    );
+    assert_eq!(0, client.issue(exists("token")).unwrap());
+    assert_eq!(None, client.take::<String>("token").unwrap());
+}
+
+#[test]
+fn getdel_returns_the_value_and_removes_the_key() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("number", 42)).unwrap();
+    assert_eq!(Some(42), client.issue(getdel::<i64, _>("number")).unwrap());
+    assert_eq!(None, client.issue(getdel::<i64, _>("number")).unwrap());
+
+    assert_eq!(
+        5,
+        client
+            .issue(getdel::<i64, _>("number").with_default(5))
+            .unwrap()
+    );
+    assert_eq!(
+        0,
+        client
+            .issue(getdel::<i64, _>("number").or_default())
+            .unwrap()
+    );
+
+    client.issue(set("binary", b"\x00\xFF")).unwrap();
+    assert_eq!(
+        Some(vec![0x00, 0xFF]),
+        client.issue(getdel::<Vec<u8>, _>("binary")).unwrap()
+    );
+
+    client.issue(set("text", b"a\xFFb")).unwrap();
+    assert_eq!(
+        Some("a\u{FFFD}b".to_string()),
+        client.issue(getdel_lossy("text")).unwrap()
+    );
+    assert_eq!(None, client.issue(getdel_lossy("text")).unwrap());
+}
+
+#[test]
+fn getex_can_set_or_remove_the_expiry() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("key", "value")).unwrap();
+    assert_eq!(
+        Some("value".to_string()),
+        client
+            .issue(getex("key").with_expiry(Duration::from_secs(60)).unwrap())
+            .unwrap()
+    );
+    assert!(client.issue(pttl("key")).unwrap() != TtlResult::NoExpiry);
+
+    assert_eq!(
+        Some("value".to_string()),
+        client
+            .issue(getex::<Vec<u8>, _>("key").persist().lossy())
+            .unwrap()
+    );
+    assert_eq!(TtlResult::NoExpiry, client.issue(pttl("key")).unwrap());
+}
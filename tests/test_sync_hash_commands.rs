@@ -0,0 +1,25 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+use reredis::{Command, RedisValue};
+
+use crate::utils::load_redis_instance;
+
+#[test]
+fn hsetnx_only_sets_missing_fields() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert!(client.issue(hsetnx("hash", "field", "first")).unwrap());
+    assert!(!client.issue(hsetnx("hash", "field", "second")).unwrap());
+
+    assert_eq!(
+        Some(RedisValue::String(b"first".to_vec())),
+        client
+            .issue(Command::cmd("HGET").with_arg("hash").with_arg("field"))
+            .unwrap()
+    );
+}
@@ -0,0 +1,477 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+
+use crate::utils::{load_redis_instance, mock_server};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn expire_now_removes_a_key_almost_immediately() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("my-key", 1)).unwrap();
+    assert!(client.expire_now("my-key").unwrap());
+
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(None, client.issue(get::<i64, _>("my-key")).unwrap());
+}
+
+#[test]
+fn expire_now_reports_missing_keys() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert!(!client.expire_now("missing").unwrap());
+}
+
+#[test]
+fn set_with_refresh_resets_the_ttl_on_every_write() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .set_with_refresh("session", "first", Duration::from_secs(10))
+        .unwrap();
+    client
+        .issue(pexpire("session", Duration::from_secs(2)).unwrap())
+        .unwrap();
+
+    client
+        .set_with_refresh("session", "second", Duration::from_secs(10))
+        .unwrap();
+    match client.issue(pttl("session")).unwrap() {
+        TtlResult::Expires(ttl) => assert!(ttl > Duration::from_secs(5)),
+        other => panic!("expected the key to expire, got {:?}", other),
+    }
+    assert_eq!(
+        Some("second".to_string()),
+        client.issue(get("session")).unwrap()
+    );
+}
+
+#[test]
+fn touch_ttl_extends_the_expiry_without_changing_the_value() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .set_with_refresh("session", "value", Duration::from_secs(2))
+        .unwrap();
+    assert!(client
+        .touch_ttl("session", Duration::from_secs(100))
+        .unwrap());
+    assert!(!client
+        .touch_ttl("missing", Duration::from_secs(100))
+        .unwrap());
+
+    match client.issue(pttl("session")).unwrap() {
+        TtlResult::Expires(ttl) => assert!(ttl > Duration::from_secs(50)),
+        other => panic!("expected the key to expire, got {:?}", other),
+    }
+    assert_eq!(
+        Some("value".to_string()),
+        client.issue(get("session")).unwrap()
+    );
+}
+
+#[test]
+fn get_with_ttl_returns_the_value_and_remaining_ttl_together() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .issue(
+            set("my-key", 42)
+                .with_expiry(Duration::from_secs(100))
+                .unwrap(),
+        )
+        .unwrap();
+
+    match client.get_with_ttl::<i64>("my-key").unwrap() {
+        Some((42, TtlResult::Expires(ttl))) => {
+            assert!(ttl > Duration::from_secs(90));
+            assert!(ttl <= Duration::from_secs(100));
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // the connection should still be in step afterwards
+    assert_eq!(Some(42), client.issue(get("my-key")).unwrap());
+}
+
+#[test]
+fn get_with_ttl_returns_none_for_a_missing_key() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert_eq!(None, client.get_with_ttl::<i64>("missing").unwrap());
+    assert_eq!((), client.issue(ping()).unwrap());
+}
+
+#[test]
+fn dumped_keys_can_be_restored_with_an_idletime() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("my-key", "some value")).unwrap();
+    let payload = client.issue(dump("my-key")).unwrap().unwrap();
+
+    client
+        .issue(restore("restored-key", payload).idletime(Duration::from_secs(1000)))
+        .unwrap();
+
+    let idletime = client
+        .issue(object_idletime("restored-key"))
+        .unwrap()
+        .unwrap();
+    assert!(idletime >= Duration::from_secs(1000));
+    assert_eq!(
+        Some("some value".to_string()),
+        client.issue(get("restored-key")).unwrap()
+    );
+}
+
+#[test]
+fn object_encoding_reports_how_values_are_stored() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+    client.issue(set("counter", 42)).unwrap();
+    client.issue(set("greeting", "hello")).unwrap();
+
+    assert_eq!(
+        Some("int".to_string()),
+        client.issue(object_encoding("counter")).unwrap()
+    );
+    assert_eq!(
+        Some("embstr".to_string()),
+        client.issue(object_encoding("greeting")).unwrap()
+    );
+    assert_eq!(None, client.issue(object_encoding("missing")).unwrap());
+}
+
+#[test]
+fn dump_and_object_idletime_return_none_for_missing_keys() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert_eq!(None, client.issue(dump("missing")).unwrap());
+    assert_eq!(None, client.issue(object_idletime("missing")).unwrap());
+}
+
+#[test]
+fn keys_can_be_listed_once_acknowledged() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("user:1", 1)).unwrap();
+    client.issue(set("user:2", 2)).unwrap();
+    client.issue(set("session:1", 3)).unwrap();
+
+    let mut users = client.issue(keys("user:*").i_know_this_blocks()).unwrap();
+    users.sort();
+    assert_eq!(vec!["user:1".to_string(), "user:2".to_string()], users);
+}
+
+#[test]
+fn the_shortest_valid_expiries_are_accepted_by_redis() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .issue(setex("short-lived", 1, "value").unwrap())
+        .unwrap();
+    client.issue(set("my-key", "value")).unwrap();
+    assert!(client
+        .issue(expire("my-key", Duration::from_secs(1)).unwrap())
+        .unwrap());
+
+    thread::sleep(Duration::from_millis(1500));
+    assert_eq!(None, client.issue(get::<String, _>("short-lived")).unwrap());
+    assert_eq!(None, client.issue(get::<String, _>("my-key")).unwrap());
+}
+
+#[test]
+fn copy_only_overwrites_existing_keys_with_replace() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("source", "new")).unwrap();
+    client.issue(set("dest", "old")).unwrap();
+
+    assert!(!client.issue(copy("source", "dest").unwrap()).unwrap());
+    assert_eq!(Some("old".to_string()), client.issue(get("dest")).unwrap());
+
+    assert!(client
+        .issue(copy("source", "dest").unwrap().replace())
+        .unwrap());
+    assert_eq!(Some("new".to_string()), client.issue(get("dest")).unwrap());
+}
+
+#[test]
+fn copy_to_db_copies_into_another_database() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("source", "value")).unwrap();
+    assert!(client.issue(copy_to_db("source", "source", 1)).unwrap());
+
+    client
+        .issue(reredis::Command::cmd("SELECT").with_arg("1"))
+        .unwrap();
+    assert_eq!(
+        Some("value".to_string()),
+        client.issue(get("source")).unwrap()
+    );
+}
+
+#[test]
+fn get_or_set_with_only_fills_in_missing_keys() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let value: String = client
+        .get_or_set_with("cached", Duration::from_secs(60), || "computed")
+        .unwrap();
+    assert_eq!("computed", value);
+
+    let value: String = client
+        .get_or_set_with("cached", Duration::from_secs(60), || -> &str {
+            panic!("the key is already set")
+        })
+        .unwrap();
+    assert_eq!("computed", value);
+    assert!(client.issue(pttl("cached")).unwrap() != TtlResult::NoExpiry);
+}
+
+#[test]
+fn racing_get_or_set_with_calls_agree_on_a_value() {
+    let server = load_redis_instance();
+    let address = server.address().to_string();
+
+    let handles: Vec<_> = (0..2)
+        .map(|index| {
+            let address = address.clone();
+            thread::spawn(move || {
+                let mut client = reredis::SyncClient::new(address).unwrap();
+                client
+                    .get_or_set_with::<String, _>("cached", Duration::from_secs(60), || {
+                        // give the other thread a chance to get between our GET and SET
+                        thread::sleep(Duration::from_millis(50));
+                        format!("thread {}", index)
+                    })
+                    .unwrap()
+            })
+        })
+        .collect();
+    let values: Vec<String> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    assert_eq!(values[0], values[1]);
+    let mut client = reredis::SyncClient::new(address).unwrap();
+    assert_eq!(
+        Some(values[0].clone()),
+        client.issue(get("cached")).unwrap()
+    );
+}
+
+#[test]
+fn scan_iter_visits_every_matching_key() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    for index in 0..250 {
+        client
+            .issue(set(format!("user:{}", index), "value"))
+            .unwrap();
+    }
+    client.issue(set("other", "value")).unwrap();
+
+    let mut keys: Vec<Vec<u8>> = client
+        .scan_iter("user:*", 20)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    keys.sort();
+    keys.dedup();
+
+    assert_eq!(250, keys.len());
+    assert!(keys.iter().all(|key| key.starts_with(b"user:")));
+}
+
+#[test]
+fn delete_matching_only_removes_matching_keys() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    for index in 0..500 {
+        client
+            .issue(set(format!("cache:{}", index), "value"))
+            .unwrap();
+    }
+    client.issue(set("user:1", "value")).unwrap();
+    client.issue(set("cache", "value")).unwrap();
+
+    assert_eq!(500, client.delete_matching("cache:*").unwrap());
+    assert_eq!(0, client.scan_iter("cache:*", 100).count());
+    assert_eq!(
+        Some("value".to_string()),
+        client.issue(get("user:1")).unwrap()
+    );
+    assert_eq!(
+        Some("value".to_string()),
+        client.issue(get("cache")).unwrap()
+    );
+}
+
+#[test]
+fn delete_matching_follows_the_cursor_to_the_end() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"SCAN" if command[1] == b"0" => b"*2\r\n$2\r\n17\r\n*2\r\n$1\r\na\r\n$1\r\nb\r\n".to_vec(),
+        // an empty page in the middle of the scan
+        b"SCAN" if command[1] == b"17" => b"*2\r\n$2\r\n42\r\n*0\r\n".to_vec(),
+        b"SCAN" => b"*2\r\n$1\r\n0\r\n*1\r\n$1\r\nc\r\n".to_vec(),
+        b"UNLINK" => format!(":{}\r\n", command.len() - 1).into_bytes(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+
+    assert_eq!(3, client.delete_matching("*").unwrap());
+}
+
+#[test]
+fn delete_matching_unlinks_keys_that_are_not_utf8() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"SCAN" => b"*2\r\n$1\r\n0\r\n*2\r\n$4\r\nkey\xFF\r\n$3\r\nkey\r\n".to_vec(),
+        b"UNLINK" if command[1..] == [b"key\xFF".to_vec(), b"key".to_vec()] => b":2\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+
+    assert_eq!(2, client.delete_matching("key*").unwrap());
+}
+
+#[test]
+fn scan_pages_end_with_a_zero_cursor() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+    client.issue(set("key", "value")).unwrap();
+
+    let mut page = client.issue(scan(0)).unwrap();
+    while !page.is_complete() {
+        page = client.issue(scan(page.cursor)).unwrap();
+    }
+    assert_eq!(0, page.cursor);
+}
+
+#[test]
+fn get_dynamic_reads_each_type_of_key() {
+    use reredis::DynamicValue;
+
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("string", "value")).unwrap();
+    client.issue(rpush("list", "a")).unwrap();
+    client.issue(rpush("list", "b")).unwrap();
+    client
+        .issue(reredis::Command::cmd_with_args(
+            "SADD",
+            vec!["set", "member"],
+        ))
+        .unwrap();
+    client.issue(hsetnx("hash", "field", "value")).unwrap();
+    client.issue(zadd("zset", 1.5, "member").unwrap()).unwrap();
+
+    assert_eq!(
+        DynamicValue::String(b"value".to_vec()),
+        client.get_dynamic("string").unwrap()
+    );
+    assert_eq!(
+        DynamicValue::List(vec![b"a".to_vec(), b"b".to_vec()]),
+        client.get_dynamic("list").unwrap()
+    );
+    assert_eq!(
+        DynamicValue::Set(vec![b"member".to_vec()]),
+        client.get_dynamic("set").unwrap()
+    );
+    assert_eq!(
+        DynamicValue::Hash(vec![(b"field".to_vec(), b"value".to_vec())]),
+        client.get_dynamic("hash").unwrap()
+    );
+    assert_eq!(
+        DynamicValue::SortedSet(vec![(b"member".to_vec(), 1.5)]),
+        client.get_dynamic("zset").unwrap()
+    );
+    assert_eq!(
+        DynamicValue::Missing,
+        client.get_dynamic("missing").unwrap()
+    );
+}
+
+#[test]
+fn get_dynamic_reads_by_the_reported_type() {
+    use reredis::DynamicValue;
+
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"TYPE" if command[1] == b"hash" => b"+hash\r\n".to_vec(),
+        b"TYPE" if command[1] == b"stream" => b"+stream\r\n".to_vec(),
+        b"TYPE" => b"+none\r\n".to_vec(),
+        b"HGETALL" => b"*2\r\n$1\r\nf\r\n$1\r\nv\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+
+    assert_eq!(
+        DynamicValue::Hash(vec![(b"f".to_vec(), b"v".to_vec())]),
+        client.get_dynamic("hash").unwrap()
+    );
+    assert_eq!(
+        DynamicValue::Unsupported(KeyType::Stream),
+        client.get_dynamic("stream").unwrap()
+    );
+    assert_eq!(
+        DynamicValue::Missing,
+        client.get_dynamic("missing").unwrap()
+    );
+}
+
+#[test]
+fn expired_keys_report_their_remaining_ttl() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("key", "value")).unwrap();
+    assert_eq!(TtlResult::NoExpiry, client.issue(ttl("key")).unwrap());
+    assert!(client
+        .issue(expire("key", Duration::from_secs(100)).unwrap())
+        .unwrap());
+
+    match client.issue(ttl("key")).unwrap() {
+        TtlResult::Expires(remaining) => {
+            assert!(remaining <= Duration::from_secs(100));
+            assert!(remaining >= Duration::from_secs(98));
+        }
+        other => panic!("expected the key to expire, got {:?}", other),
+    }
+    match client.issue(pttl("key")).unwrap() {
+        TtlResult::Expires(remaining) => assert!(remaining > Duration::from_secs(98)),
+        other => panic!("expected the key to expire, got {:?}", other),
+    }
+    assert_eq!(TtlResult::NoKey, client.issue(ttl("missing")).unwrap());
+}
+
+#[test]
+fn deleted_keys_no_longer_exist() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("a", 1)).unwrap();
+    client.issue(set("b", 2)).unwrap();
+    assert_eq!(2, client.issue(exists("a").and_key("b")).unwrap());
+
+    assert_eq!(1, client.issue(del("a").and_key("missing")).unwrap());
+    assert_eq!(1, client.issue(exists("a").and_key("b")).unwrap());
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+
+use crate::utils::load_redis_instance;
+
+#[test]
+fn lrange_returns_the_requested_elements() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert_eq!(
+        3,
+        client.issue(rpush("my-list", 1).value(2).value(3)).unwrap()
+    );
+    assert_eq!(4, client.issue(lpush("my-list", 0)).unwrap());
+
+    assert_eq!(
+        vec![0, 1, 2, 3],
+        client.issue(lrange::<i64>("my-list", 0, -1)).unwrap()
+    );
+    assert_eq!(
+        vec![1, 2],
+        client.issue(lrange::<i64>("my-list", 1, 2)).unwrap()
+    );
+}
+
+#[test]
+fn lrange_iter_streams_through_a_large_list_in_order() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .issue(rpush("my-list", 0).with_values(1..10_000))
+        .unwrap();
+
+    let items = client
+        .lrange_iter::<i64>("my-list", 128)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!((0..10_000).collect::<Vec<_>>(), items);
+}
+
+#[test]
+fn lrange_iter_returns_nothing_for_a_missing_list() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert_eq!(0, client.lrange_iter::<i64>("missing", 10).count());
+}
+
+#[test]
+fn pushing_onto_a_string_is_a_wrong_type_error() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+    client.issue(set("my-key", "value")).unwrap();
+
+    match client.issue(lpush("my-key", "item")) {
+        Err(reredis::RedisError::RedisReturnedError(error)) => {
+            assert_eq!(reredis::ErrorKind::WrongType, error.error_kind())
+        }
+        other => panic!("expected a WRONGTYPE error, got {:?}", other),
+    }
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use crate::utils::load_redis_instance;
+use std::time::Duration;
+
+#[test]
+fn lock_cannot_be_acquired_twice_until_it_is_released() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let guard = client
+        .acquire_lock("my-lock", Duration::from_secs(10))
+        .unwrap()
+        .expect("lock should be free");
+
+    assert!(client
+        .acquire_lock("my-lock", Duration::from_secs(10))
+        .unwrap()
+        .is_none());
+
+    assert!(client.issue(guard.release()).unwrap());
+
+    assert!(client
+        .acquire_lock("my-lock", Duration::from_secs(10))
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn lock_is_not_released_if_somebody_else_has_taken_it() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let guard = client
+        .acquire_lock("my-lock", Duration::from_millis(10))
+        .unwrap()
+        .expect("lock should be free");
+
+    std::thread::sleep(Duration::from_millis(50));
+    let _other_guard = client
+        .acquire_lock("my-lock", Duration::from_secs(10))
+        .unwrap()
+        .expect("lock should have expired");
+
+    assert!(!client.issue(guard.release()).unwrap());
+    assert!(client
+        .acquire_lock("my-lock", Duration::from_secs(10))
+        .unwrap()
+        .is_none());
+}
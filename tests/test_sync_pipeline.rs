@@ -0,0 +1,130 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+use reredis::RedisError;
+
+use crate::utils::{load_redis_instance, mock_server};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[test]
+fn pipelines_return_each_commands_output_in_order() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let (set_result, count, value) = client
+        .pipeline((
+            set("key", "value"),
+            incr("counter"),
+            get::<String, _>("key"),
+        ))
+        .execute()
+        .unwrap();
+
+    assert_eq!((), set_result);
+    assert_eq!(1, count);
+    assert_eq!(Some("value".to_string()), value);
+}
+
+#[test]
+fn lenient_pipelines_report_each_commands_outcome() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+    client.issue(set("string", "value")).unwrap();
+
+    let (before, wrong_type, after) = client
+        .pipeline((incr("counter"), lpush("string", "item"), incr("counter")))
+        .execute_results()
+        .unwrap();
+
+    assert_eq!(1, before.unwrap());
+    match wrong_type {
+        Err(RedisError::RedisReturnedError(error)) => {
+            assert!(error.to_string().starts_with("WRONGTYPE"))
+        }
+        other => panic!("expected a WRONGTYPE error, got {:?}", other),
+    }
+    assert_eq!(2, after.unwrap());
+
+    // every reply was read, so the connection is still usable
+    assert_eq!(Some(2), client.issue(get::<i64, _>("counter")).unwrap());
+}
+
+#[test]
+fn strict_pipelines_fail_with_the_first_error_but_stay_in_sync() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"INCR" => b":1\r\n".to_vec(),
+        b"LPUSH" => {
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n".to_vec()
+        }
+        b"PING" => b"+PONG\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+
+    match client
+        .pipeline((incr("counter"), lpush("string", "item"), incr("counter")))
+        .execute()
+    {
+        Err(RedisError::RedisReturnedError(error)) => {
+            assert!(error.to_string().starts_with("WRONGTYPE"))
+        }
+        other => panic!("expected a WRONGTYPE error, got {:?}", other),
+    }
+
+    client.issue(ping()).unwrap();
+}
+
+#[test]
+fn a_trailing_wait_covers_the_pipelined_writes() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    // there are no replicas, so WAIT has nothing to wait for and reports zero straight away
+    let ((), (), (), replicas) = client
+        .pipeline((
+            set("first", 1),
+            set("second", 2),
+            set("third", 3),
+            wait(0, Duration::from_millis(100)),
+        ))
+        .execute()
+        .unwrap();
+
+    assert_eq!(0, replicas);
+    assert_eq!(Some(3), client.issue(get::<i64, _>("third")).unwrap());
+}
+
+#[test]
+fn wait_replies_are_decoded_in_their_place_in_the_tuple() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = received.clone();
+    let address = mock_server(move |command| {
+        log.lock().unwrap().push(command[0].clone());
+        match command[0].as_slice() {
+            b"SET" => b"+OK\r\n".to_vec(),
+            b"WAIT" => b":2\r\n".to_vec(),
+            _ => b"-ERR unexpected command\r\n".to_vec(),
+        }
+    });
+    let mut client = reredis::SyncClient::new(address).unwrap();
+
+    let ((), (), replicas) = client
+        .pipeline((
+            set("first", 1),
+            set("second", 2),
+            wait(2, Duration::from_secs(1)),
+        ))
+        .execute()
+        .unwrap();
+
+    assert_eq!(2, replicas);
+    // WAIT only covers writes that the server has already seen
+    assert_eq!(
+        vec![b"SET".to_vec(), b"SET".to_vec(), b"WAIT".to_vec()],
+        *received.lock().unwrap()
+    );
+}
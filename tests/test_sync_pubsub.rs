@@ -0,0 +1,485 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+
+use crate::utils::{load_redis_instance, mock_server, scripted_server};
+use reredis::BufferPolicy;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn published_messages_are_received_by_subscribers() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap();
+
+    assert_eq!(1, publisher.issue(publish("news", "hello")).unwrap());
+
+    let message = subscription.next_message().unwrap();
+    assert_eq!("news", message.channel_str());
+    assert_eq!(b"hello".to_vec(), message.payload);
+}
+
+#[test]
+fn pubsub_clients_receive_their_own_messages() {
+    let server = load_redis_instance();
+    let mut client = reredis::PubSubClient::new(server.address()).unwrap();
+
+    client.subscribe(vec!["news"]).unwrap();
+    assert_eq!(1, client.publish("news", "hello").unwrap());
+
+    let message = client.next_message().unwrap();
+    assert_eq!("news", message.channel_str());
+    assert_eq!(b"hello".to_vec(), message.payload);
+
+    // the command connection isn't affected by the subscription
+    client.issue(set("key", "value")).unwrap();
+    assert_eq!(Some("value".to_string()), client.issue(get("key")).unwrap());
+}
+
+#[test]
+fn pattern_subscriptions_report_the_pattern_and_channel() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .psubscribe(vec!["news.*"])
+        .unwrap();
+
+    assert_eq!(1, publisher.issue(publish("news.tech", "hello")).unwrap());
+
+    let message = subscription.next_message().unwrap();
+    assert_eq!(Some(b"news.*".to_vec()), message.pattern);
+    assert_eq!("news.tech", message.channel_str());
+    assert_eq!(b"hello".to_vec(), message.payload);
+}
+
+#[test]
+fn sharded_messages_are_received_by_shard_subscribers() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .ssubscribe(vec!["news"])
+        .unwrap();
+
+    // sharded and regular pub/sub are separate, so PUBLISH doesn't reach this subscriber
+    assert_eq!(0, publisher.issue(publish("news", "regular")).unwrap());
+    assert_eq!(1, publisher.issue(spublish("news", "sharded")).unwrap());
+
+    let message = subscription.next_message().unwrap();
+    assert_eq!("news", message.channel_str());
+    assert_eq!(b"sharded".to_vec(), message.payload);
+
+    subscription.sunsubscribe(vec!["news"]).unwrap();
+    assert!(subscription.into_client().is_ok());
+}
+
+#[test]
+fn publishers_can_see_who_is_listening() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    let _subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap();
+    let _pattern_subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .psubscribe(vec!["sport.*"])
+        .unwrap();
+
+    assert_eq!(
+        vec!["news".to_string()],
+        publisher.issue(pubsub_channels("*")).unwrap()
+    );
+    let counts = publisher
+        .issue(pubsub_numsub(vec!["news", "weather"]))
+        .unwrap();
+    assert_eq!(Some(&1), counts.get("news"));
+    assert_eq!(Some(&0), counts.get("weather"));
+    assert_eq!(1, publisher.issue(pubsub_numpat()).unwrap());
+}
+
+#[test]
+fn direct_messages_have_no_pattern() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap();
+    subscription.psubscribe(vec!["sport.*"]).unwrap();
+    assert_eq!(2, subscription.subscription_count());
+
+    publisher.issue(publish("news", "hello")).unwrap();
+    assert_eq!(None, subscription.next_message().unwrap().pattern);
+
+    subscription.unsubscribe_all().unwrap();
+    assert_eq!(1, subscription.subscription_count());
+    subscription.punsubscribe_all().unwrap();
+    assert!(subscription.into_client().is_ok());
+}
+
+#[test]
+fn subscribing_to_many_channels_consumes_every_confirmation() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+
+    let channels: Vec<String> = (0..100).map(|i| format!("channel-{}", i)).collect();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(channels)
+        .unwrap();
+
+    // if any confirmations were left unread, they'd be returned here instead of the message
+    publisher.issue(publish("channel-99", "last")).unwrap();
+    let message = subscription.next_message().unwrap();
+    assert_eq!("channel-99", message.channel_str());
+    assert_eq!(b"last".to_vec(), message.payload);
+}
+
+#[test]
+fn subscription_count_follows_subscribes_and_unsubscribes() {
+    let server = load_redis_instance();
+    let subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(vec!["first", "second"])
+        .unwrap();
+    assert_eq!(2, subscription.subscription_count());
+
+    let mut subscription = subscription.into_client().unwrap_err();
+    subscription.unsubscribe(vec!["first"]).unwrap();
+    assert_eq!(1, subscription.subscription_count());
+
+    subscription.unsubscribe_all().unwrap();
+    assert_eq!(0, subscription.subscription_count());
+
+    let mut client = subscription.into_client().unwrap();
+    assert_eq!((), client.issue(ping()).unwrap());
+}
+
+#[test]
+fn channel_names_do_not_need_to_be_utf8() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(vec![b"\x00\xFF"])
+        .unwrap();
+
+    publisher.issue(publish(b"\x00\xFF", "binary")).unwrap();
+
+    let message = subscription.next_message().unwrap();
+    assert_eq!(vec![0x00, 0xFF], message.channel);
+    assert_eq!(b"binary".to_vec(), message.payload);
+}
+
+#[test]
+fn reconnecting_subscriptions_resubscribe_after_a_restart() {
+    let mut server = load_redis_instance();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap()
+        .with_reconnect(50);
+
+    server.restart();
+    let waiting = thread::spawn(move || subscription.next_message().unwrap());
+
+    // messages published before the subscription is restored are lost, so keep publishing until
+    // someone is listening
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    while publisher.issue(publish("news", "after restart")).unwrap() == 0 {
+        thread::sleep(std::time::Duration::from_millis(50));
+    }
+
+    let message = waiting.join().unwrap();
+    assert_eq!("news", message.channel_str());
+    assert_eq!(b"after restart".to_vec(), message.payload);
+}
+
+// A server that answers a SUBSCRIBE with its confirmation and then a burst of `count` messages,
+// far faster than the tests below consume them
+fn flooding_server(count: usize) -> String {
+    mock_server(move |command| match command[0].as_slice() {
+        b"SUBSCRIBE" => {
+            let mut reply = b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n".to_vec();
+            for index in 0..count {
+                let payload = index.to_string();
+                reply.extend_from_slice(b"*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n");
+                reply
+                    .extend_from_slice(format!("${}\r\n{}\r\n", payload.len(), payload).as_bytes());
+            }
+            reply
+        }
+        b"UNSUBSCRIBE" => b"*3\r\n$11\r\nunsubscribe\r\n$4\r\nnews\r\n:0\r\n".to_vec(),
+        b"PING" => b"+PONG\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    })
+}
+
+fn payload(message: reredis::Message) -> usize {
+    String::from_utf8(message.payload).unwrap().parse().unwrap()
+}
+
+#[test]
+fn full_buffers_drop_the_oldest_messages() {
+    let mut subscription = reredis::SyncClient::new(flooding_server(100))
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap()
+        .with_buffer(10, BufferPolicy::DropOldest);
+
+    // give the background reader time to get through the whole burst
+    thread::sleep(Duration::from_millis(200));
+
+    let payloads: Vec<usize> = (0..10)
+        .map(|_| payload(subscription.next_message().unwrap()))
+        .collect();
+    assert_eq!((90..100).collect::<Vec<_>>(), payloads);
+}
+
+#[test]
+fn full_blocking_buffers_keep_every_message() {
+    let mut subscription = reredis::SyncClient::new(flooding_server(100))
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap()
+        .with_buffer(10, BufferPolicy::Block);
+
+    thread::sleep(Duration::from_millis(200));
+
+    let payloads: Vec<usize> = (0..100)
+        .map(|_| payload(subscription.next_message().unwrap()))
+        .collect();
+    assert_eq!((0..100).collect::<Vec<_>>(), payloads);
+}
+
+#[test]
+fn buffered_subscriptions_can_still_be_turned_back_into_clients() {
+    let mut subscription = reredis::SyncClient::new(flooding_server(0))
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap()
+        .with_buffer(10, BufferPolicy::Block);
+
+    subscription.unsubscribe(vec!["news"]).unwrap();
+    let mut client = subscription.into_client().unwrap();
+    client.issue(ping()).unwrap();
+}
+
+#[test]
+fn buffered_shard_subscriptions_end_when_the_last_shard_channel_is_dropped() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"SSUBSCRIBE" => b"*3\r\n$10\r\nssubscribe\r\n$4\r\nnews\r\n:1\r\n".to_vec(),
+        b"SUNSUBSCRIBE" => b"*3\r\n$12\r\nsunsubscribe\r\n$4\r\nnews\r\n:0\r\n".to_vec(),
+        // a list that happens to look like a message, which mustn't be mistaken for one
+        b"LRANGE" => b"*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let mut subscription = reredis::SyncClient::new(address)
+        .unwrap()
+        .ssubscribe(vec!["news"])
+        .unwrap()
+        .with_buffer(10, BufferPolicy::Block);
+
+    subscription.sunsubscribe(vec!["news"]).unwrap();
+    let mut client = subscription.into_client().unwrap();
+    let list = client
+        .issue_with_timeout(lrange::<String>("list", 0, -1), Duration::from_secs(1))
+        .unwrap();
+    assert_eq!(vec!["message", "news", "hello"], list);
+}
+
+// A server whose first connection confirms the subscription and then drops while the client is
+// waiting for a message.  Later connections confirm the subscription and send one message.
+fn dropping_server() -> String {
+    scripted_server(|index, connection| {
+        connection.read_command();
+        connection.write(b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n");
+        if index == 0 {
+            thread::sleep(Duration::from_millis(200));
+        } else {
+            connection.write(b"*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n");
+            connection.hold_open();
+        }
+    })
+}
+
+#[test]
+fn dropped_connections_are_recoverable_errors() {
+    let mut subscription = reredis::SyncClient::new(dropping_server())
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap();
+
+    let error = subscription.next_message().unwrap_err();
+    assert!(error.is_connection_error(), "unexpected error {:?}", error);
+
+    subscription.reconnect().unwrap();
+    let message = subscription.next_message().unwrap();
+    assert_eq!("news", message.channel_str());
+    assert_eq!(b"hello".to_vec(), message.payload);
+}
+
+#[test]
+fn blocked_reads_resume_after_a_dropped_connection() {
+    let mut subscription = reredis::SyncClient::new(dropping_server())
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap()
+        .with_reconnect(3);
+
+    let message = subscription.next_message().unwrap();
+    assert_eq!("news", message.channel_str());
+    assert_eq!(b"hello".to_vec(), message.payload);
+}
+
+#[test]
+fn reconnecting_subscriptions_log_in_again() {
+    // like `dropping_server`, but every connection has to log in first
+    let address = scripted_server(|index, connection| {
+        match connection.read_command() {
+            Some(command) if command == [b"AUTH".to_vec(), b"secret".to_vec()] => {
+                connection.write(b"+OK\r\n")
+            }
+            _ => return connection.write(b"-NOAUTH Authentication required.\r\n"),
+        }
+        connection.read_command();
+        connection.write(b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n");
+        if index == 0 {
+            thread::sleep(Duration::from_millis(200));
+        } else {
+            connection.write(b"*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n");
+            connection.hold_open();
+        }
+    });
+    let mut subscription = reredis::SyncClient::with_auth(address, "secret")
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap()
+        .with_reconnect(3);
+
+    let message = subscription.next_message().unwrap();
+    assert_eq!(b"hello".to_vec(), message.payload);
+}
+
+#[test]
+fn waiting_for_a_message_can_time_out() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(vec!["news"])
+        .unwrap();
+
+    assert_eq!(
+        None,
+        subscription
+            .next_message_timeout(Duration::from_millis(50))
+            .unwrap()
+    );
+
+    publisher.issue(publish("news", "hello")).unwrap();
+    let message = subscription
+        .next_message_timeout(Duration::from_secs(5))
+        .unwrap()
+        .unwrap();
+    assert_eq!(b"hello".to_vec(), message.payload);
+}
+
+// A server that confirms the subscription, then sends a message split in two, 300ms apart
+fn slow_publishing_server() -> String {
+    scripted_server(|_, connection| {
+        connection.read_command();
+        connection.write(b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n");
+        connection.write(b"*3\r\n$7\r\nmessage\r\n");
+        thread::sleep(Duration::from_millis(300));
+        connection.write(b"$4\r\nnews\r\n$5\r\nhello\r\n");
+        connection.hold_open();
+    })
+}
+
+#[test]
+fn timed_out_waits_leave_the_subscription_usable() {
+    let address = slow_publishing_server();
+    for buffered in [false, true] {
+        let mut subscription = reredis::SyncClient::new(address.as_str())
+            .unwrap()
+            .subscribe(vec!["news"])
+            .unwrap();
+        if buffered {
+            subscription = subscription.with_buffer(10, BufferPolicy::Block);
+        }
+
+        assert_eq!(
+            None,
+            subscription
+                .next_message_timeout(Duration::from_millis(50))
+                .unwrap()
+        );
+        let message = subscription
+            .next_message_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(b"hello".to_vec(), message.payload);
+    }
+}
+
+#[test]
+fn messages_can_be_waited_for_on_one_channel() {
+    let server = load_redis_instance();
+    let mut publisher = reredis::SyncClient::new(server.address()).unwrap();
+    let mut subscription = reredis::SyncClient::new(server.address())
+        .unwrap()
+        .subscribe(vec!["A", "B"])
+        .unwrap();
+
+    publisher.issue(publish("B", "first")).unwrap();
+    publisher.issue(publish("A", "second")).unwrap();
+
+    let message = subscription.next_message_on("A").unwrap();
+    assert_eq!(b"second".to_vec(), message.payload);
+    let message = subscription.next_message().unwrap();
+    assert_eq!("B", message.channel_str());
+    assert_eq!(b"first".to_vec(), message.payload);
+}
+
+// A server that confirms a subscription to A and B, then sends messages to B, B and A
+fn two_channel_server() -> String {
+    scripted_server(|_, connection| {
+        connection.read_command();
+        connection.write(
+            b"*3\r\n$9\r\nsubscribe\r\n$1\r\nA\r\n:1\r\n\
+              *3\r\n$9\r\nsubscribe\r\n$1\r\nB\r\n:2\r\n\
+              *3\r\n$7\r\nmessage\r\n$1\r\nB\r\n$5\r\nfirst\r\n\
+              *3\r\n$7\r\nmessage\r\n$1\r\nB\r\n$6\r\nsecond\r\n\
+              *3\r\n$7\r\nmessage\r\n$1\r\nA\r\n$5\r\nthird\r\n",
+        );
+        connection.hold_open();
+    })
+}
+
+#[test]
+fn messages_for_other_channels_are_kept_in_order() {
+    let mut subscription = reredis::SyncClient::new(two_channel_server())
+        .unwrap()
+        .subscribe(vec!["A", "B"])
+        .unwrap();
+
+    let payloads = vec![
+        subscription.next_message_on("A").unwrap().payload,
+        subscription.next_message_on("B").unwrap().payload,
+        subscription.next_message().unwrap().payload,
+    ];
+    assert_eq!(
+        vec![b"third".to_vec(), b"first".to_vec(), b"second".to_vec()],
+        payloads
+    );
+}
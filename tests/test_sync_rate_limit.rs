@@ -0,0 +1,33 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use crate::utils::load_redis_instance;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn rate_limit_counts_hits_within_a_window() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let window = Duration::from_millis(200);
+    assert_eq!(1, client.rate_limit("hits", window).unwrap());
+    assert_eq!(2, client.rate_limit("hits", window).unwrap());
+    assert_eq!(3, client.rate_limit("hits", window).unwrap());
+
+    thread::sleep(Duration::from_millis(300));
+    assert_eq!(1, client.rate_limit("hits", window).unwrap());
+}
+
+#[test]
+fn rate_limit_keys_are_counted_separately() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let window = Duration::from_secs(10);
+    assert_eq!(1, client.rate_limit("first", window).unwrap());
+    assert_eq!(2, client.rate_limit("first", window).unwrap());
+    assert_eq!(1, client.rate_limit("second", window).unwrap());
+}
@@ -0,0 +1,294 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+use reredis::Command;
+
+use crate::utils::{load_redis_instance, mock_server, RedisInstance};
+use reredis::sync_client::HealthStatus;
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn time_returns_roughly_the_local_time() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let server_time = client.issue(time()).unwrap();
+    let local_time = SystemTime::now();
+
+    let skew = match local_time.duration_since(server_time) {
+        Ok(skew) => skew,
+        Err(error) => error.duration(),
+    };
+    assert!(skew < Duration::from_secs(5));
+}
+
+#[test]
+fn waitaof_is_acknowledged_locally_when_aof_is_enabled() {
+    let server = RedisInstance::new()
+        .with_setting("appendonly", ["yes"])
+        .build();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("my-key", 1)).unwrap();
+    let (local, replicas) = client.issue(waitaof(1, 0, Duration::from_secs(1))).unwrap();
+
+    assert!(local >= 1);
+    assert_eq!(0, replicas);
+}
+
+#[test]
+fn command_getkeys_extracts_the_keys_from_a_command() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let set_command = Command::cmd("SET").with_arg("foo").with_arg("bar");
+    assert_eq!(
+        vec!["foo".to_string()],
+        client.issue(command_getkeys(&set_command)).unwrap()
+    );
+
+    let mset_command = Command::cmd_with_args("MSET", vec!["a", "1", "b", "2"]);
+    assert_eq!(
+        vec!["a".to_string(), "b".to_string()],
+        client.issue(command_getkeys(&mset_command)).unwrap()
+    );
+}
+
+#[test]
+fn command_list_includes_the_basic_commands() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let commands = client.issue(command_list()).unwrap();
+    assert!(commands.contains(&"get".to_string()));
+    assert!(commands.contains(&"set".to_string()));
+    assert_eq!(
+        commands.len() as u64,
+        client.issue(command_count()).unwrap()
+    );
+}
+
+#[test]
+fn durable_writes_pass_through_the_command_result() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .issue_durable(set("my-key", 1), 0, Duration::from_millis(100))
+        .unwrap();
+    assert_eq!(
+        2,
+        client
+            .issue_durable(incr("my-key"), 0, Duration::from_millis(100))
+            .unwrap()
+    );
+}
+
+#[test]
+fn durable_writes_fail_without_enough_replicas() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    match client.issue_durable(set("my-key", 1), 1, Duration::from_millis(100)) {
+        Err(reredis::RedisError::InsufficientReplicas {
+            acknowledged: 0,
+            requested: 1,
+        }) => {}
+        other => panic!("expected the WAIT to time out, got {:?}", other),
+    }
+    assert_eq!(Some(1), client.issue(get("my-key")).unwrap());
+}
+
+#[test]
+fn debug_object_reports_quicklist_nodes_for_long_lists() {
+    let server = RedisInstance::new()
+        .with_setting("enable-debug-command", ["yes"])
+        .build();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    let element = "x".repeat(100);
+    client
+        .issue(rpush("my-list", element.as_str()).with_values(vec![element.as_str(); 999]))
+        .unwrap();
+
+    let object = client.issue(debug_object("my-list")).unwrap();
+    assert_eq!("quicklist", object.encoding);
+    assert!(object.ql_nodes.unwrap() > 1);
+    assert!(object.ql_avg_node.is_some());
+}
+
+#[test]
+fn validated_commands_with_the_wrong_arity_are_rejected_before_sending() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    match client.issue_validated(Command::cmd("GET")) {
+        Err(reredis::RedisError::ValidationError(reredis::ValidationError::WrongArity {
+            arity: 2,
+            args: 0,
+            ..
+        })) => {}
+        other => panic!("expected an arity error, got {:?}", other),
+    }
+
+    client.issue(set("my-key", "value")).unwrap();
+    assert!(client
+        .issue_validated(Command::cmd("get").with_arg("my-key"))
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn debug_change_repl_id_is_acknowledged() {
+    let server = RedisInstance::new()
+        .with_setting("enable-debug-command", ["yes"])
+        .build();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(debug_change_repl_id()).unwrap();
+    client
+        .issue(
+            Command::cmd("DEBUG")
+                .with_arg("CHANGE-REPL-ID")
+                .expecting_ok(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn per_call_timeouts_override_the_client_default() {
+    let server = RedisInstance::new()
+        .with_setting("enable-debug-command", ["yes"])
+        .build();
+    let sleep = || Command::cmd("DEBUG").with_arg("SLEEP").with_arg("0.5");
+
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+    client.set_timeout(Some(Duration::from_millis(100)));
+    client
+        .issue_with_timeout(sleep(), Duration::from_secs(5))
+        .unwrap();
+    client.issue(ping()).unwrap();
+
+    match client.issue(sleep()) {
+        Err(reredis::RedisError::Timeout) => {}
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+#[test]
+fn health_checks_report_the_round_trip_latency() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    match client.health_check(Duration::from_secs(5)) {
+        HealthStatus::Ok(latency) => assert!(latency < Duration::from_secs(1)),
+        other => panic!("expected a healthy server, got {:?}", other),
+    }
+}
+
+#[test]
+fn health_checks_time_out_on_unresponsive_servers() {
+    let address = mock_server(|_| Vec::new());
+    let mut client = reredis::SyncClient::new(address).unwrap();
+
+    match client.health_check(Duration::from_millis(100)) {
+        HealthStatus::Timeout => {}
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+#[test]
+fn lolwut_replies_with_some_art() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert!(!client.issue(lolwut()).unwrap().is_empty());
+    assert!(!client.issue(lolwut().version(5)).unwrap().is_empty());
+}
+
+#[test]
+fn values_survive_a_debug_reload() {
+    let server = RedisInstance::new()
+        .with_setting("enable-debug-command", ["yes"])
+        .build();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("string", b"\x00\xFFbinary")).unwrap();
+    client.issue(set("number", 12345)).unwrap();
+    client
+        .issue(rpush("list", "one").with_values(vec!["two", "three"]))
+        .unwrap();
+    client
+        .issue(
+            zadd("zset", 1.5, "one")
+                .unwrap()
+                .member(2.5, "two")
+                .unwrap(),
+        )
+        .unwrap();
+    client
+        .issue(
+            Command::cmd("HSET")
+                .with_arg("hash")
+                .with_arg("field")
+                .with_arg("value"),
+        )
+        .unwrap();
+
+    client.issue(debug_reload()).unwrap();
+
+    assert_eq!(
+        Some(b"\x00\xFFbinary".to_vec()),
+        client.issue(get("string")).unwrap()
+    );
+    assert_eq!(Some(12345), client.issue(get::<i64, _>("number")).unwrap());
+    assert_eq!(
+        vec!["one", "two", "three"],
+        client.issue(lrange::<String>("list", 0, -1)).unwrap()
+    );
+    assert_eq!(
+        vec![("one".to_string(), 1.5), ("two".to_string(), 2.5)],
+        client.issue(zunion(vec!["zset"]).with_scores()).unwrap()
+    );
+    assert_eq!(
+        Some(reredis::RedisValue::String(b"value".to_vec())),
+        client
+            .issue(Command::cmd("HGET").with_arg("hash").with_arg("field"))
+            .unwrap()
+    );
+}
+
+#[test]
+fn config_resetstat_clears_the_command_counts() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("key", "value")).unwrap();
+    assert_eq!((), client.issue(config_resetstat()).unwrap());
+
+    let stats = client
+        .issue(Command::cmd("INFO").with_arg("commandstats"))
+        .unwrap();
+    match stats {
+        Some(reredis::RedisValue::String(stats)) => {
+            assert!(!String::from_utf8(stats).unwrap().contains("cmdstat_set"))
+        }
+        other => panic!("expected the command stats, got {:?}", other),
+    }
+}
+
+#[test]
+fn swapdb_exchanges_the_contents_of_two_databases() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client.issue(set("key", "value")).unwrap();
+    assert_eq!((), client.issue(swapdb(0, 1)).unwrap());
+    assert_eq!(None, client.issue(get::<String, _>("key")).unwrap());
+
+    client.issue(Command::cmd("SELECT").with_arg("1")).unwrap();
+    assert_eq!(Some("value".to_string()), client.issue(get("key")).unwrap());
+}
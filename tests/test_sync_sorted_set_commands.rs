@@ -0,0 +1,175 @@
+#![cfg(feature = "sync-client")]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+
+use crate::utils::load_redis_instance;
+
+#[test]
+fn zadd_incr_that_would_make_a_nan_score_is_a_classified_error() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .issue(zadd("my-set", f64::INFINITY, "one").unwrap())
+        .unwrap();
+    match client.issue(
+        zadd("my-set", f64::NEG_INFINITY, "one")
+            .unwrap()
+            .incr()
+            .unwrap(),
+    ) {
+        Err(reredis::RedisError::ScoreNotANumber(_)) => {}
+        other => panic!("expected a NaN score error, got {:?}", other),
+    }
+}
+
+#[test]
+fn zadd_flags_control_which_members_are_updated() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    assert_eq!(
+        2,
+        client
+            .issue(
+                zadd("my-set", 1.0, "one")
+                    .unwrap()
+                    .member(2.0, "two")
+                    .unwrap()
+            )
+            .unwrap()
+    );
+    assert_eq!(
+        0,
+        client
+            .issue(zadd("my-set", 5.0, "one").unwrap().nx().unwrap())
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        client
+            .issue(zadd("my-set", 5.0, "one").unwrap().gt().unwrap().changed())
+            .unwrap()
+    );
+    assert_eq!(
+        Some(7.5),
+        client
+            .issue(zadd("my-set", 2.5, "one").unwrap().incr().unwrap())
+            .unwrap()
+    );
+    assert_eq!(
+        None,
+        client
+            .issue(
+                zadd("my-set", -1.0, "one")
+                    .unwrap()
+                    .gt()
+                    .unwrap()
+                    .incr()
+                    .unwrap()
+            )
+            .unwrap()
+    );
+    assert_eq!(
+        1,
+        client
+            .issue(zcount(
+                "my-set",
+                ScoreBound::Exclusive(2.0),
+                ScoreBound::PosInfinity
+            ))
+            .unwrap()
+    );
+}
+
+#[test]
+fn weighted_unions_aggregate_scores_across_sets() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .issue(zadd("first", 1.0, "a").unwrap().member(2.0, "b").unwrap())
+        .unwrap();
+    client
+        .issue(
+            zadd("second", 10.0, "b")
+                .unwrap()
+                .member(20.0, "c")
+                .unwrap(),
+        )
+        .unwrap();
+
+    let summed = client
+        .issue(
+            zunion(vec!["first", "second"])
+                .weights(vec![2.0, 0.5])
+                .with_scores(),
+        )
+        .unwrap();
+    assert_eq!(
+        vec![
+            ("a".to_string(), 2.0),
+            ("b".to_string(), 9.0),
+            ("c".to_string(), 10.0)
+        ],
+        summed
+    );
+
+    let maxed = client
+        .issue(
+            zunion(vec!["first", "second"])
+                .weights(vec![2.0, 0.5])
+                .aggregate(Aggregate::Max)
+                .with_scores(),
+        )
+        .unwrap();
+    assert_eq!(
+        vec![
+            ("a".to_string(), 2.0),
+            ("b".to_string(), 5.0),
+            ("c".to_string(), 10.0)
+        ],
+        maxed
+    );
+}
+
+#[test]
+fn intersections_and_differences_select_the_right_members() {
+    let server = load_redis_instance();
+    let mut client = reredis::SyncClient::new(server.address()).unwrap();
+
+    client
+        .issue(zadd("first", 1.0, "a").unwrap().member(2.0, "b").unwrap())
+        .unwrap();
+    client
+        .issue(zadd("second", 3.0, "b").unwrap().member(4.0, "c").unwrap())
+        .unwrap();
+
+    assert_eq!(
+        vec!["b".to_string()],
+        client.issue(zinter(vec!["first", "second"])).unwrap()
+    );
+    assert_eq!(
+        vec![("b".to_string(), 2.0)],
+        client
+            .issue(
+                zinter(vec!["first", "second"])
+                    .aggregate(Aggregate::Min)
+                    .with_scores()
+            )
+            .unwrap()
+    );
+    assert_eq!(
+        vec!["a".to_string()],
+        client.issue(zdiff(vec!["first", "second"])).unwrap()
+    );
+    assert_eq!(
+        vec![("a".to_string(), 1.0)],
+        client
+            .issue(zdiff(vec!["first", "second"]).with_scores())
+            .unwrap()
+    );
+}
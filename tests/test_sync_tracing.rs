@@ -0,0 +1,82 @@
+#![cfg(all(feature = "sync-client", feature = "tracing"))]
+
+extern crate reredis;
+mod utils;
+
+use reredis::commands::*;
+
+use crate::utils::mock_server;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata};
+
+// Records the name and `command` field of every span that is created
+#[derive(Default)]
+struct SpanRecorder {
+    spans: Arc<Mutex<Vec<(String, String)>>>,
+    next_id: AtomicU64,
+}
+
+struct CommandField(String);
+
+impl Visit for CommandField {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "command" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl tracing::Subscriber for SpanRecorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut command = CommandField(String::new());
+        span.record(&mut command);
+        self.spans
+            .lock()
+            .unwrap()
+            .push((span.metadata().name().to_string(), command.0));
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn each_command_gets_its_own_span() {
+    let address = mock_server(|command| match command[0].as_slice() {
+        b"SET" => b"+OK\r\n".to_vec(),
+        b"GET" => b"$5\r\nvalue\r\n".to_vec(),
+        _ => b"-ERR unexpected command\r\n".to_vec(),
+    });
+    let recorder = SpanRecorder::default();
+    let spans = recorder.spans.clone();
+
+    tracing::subscriber::with_default(recorder, || {
+        let mut client = reredis::SyncClient::new(address).unwrap();
+        client.issue(set("key", "value")).unwrap();
+        client.issue(get::<String, _>("key")).unwrap();
+    });
+
+    assert_eq!(
+        vec![
+            ("redis_command".to_string(), "SET".to_string()),
+            ("redis_command".to_string(), "GET".to_string()),
+        ],
+        *spans.lock().unwrap()
+    );
+}
@@ -0,0 +1,6 @@
+use reredis::commands::keys;
+
+fn main() {
+    let mut client = reredis::SyncClient::new("localhost:6379").unwrap();
+    client.issue(keys("*")).unwrap();
+}
@@ -1,8 +1,10 @@
 #![allow(dead_code)]
 
 use rand::Rng;
-use std::io::Read;
-use std::net::TcpStream;
+use reredis::test_server::read_command;
+use std::io::{BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
 use std::process::{Child, Command, Stdio};
 use std::time::Duration;
 use std::{fs, thread};
@@ -17,6 +19,7 @@ lazy_static! {
 #[derive(Debug)]
 pub struct RedisRunner {
     process: Child,
+    port: u16,
     connection_string: String,
     #[allow(dead_code)] // basically just keeping it around to prevent dropping
     data_dir: TempDir,
@@ -26,6 +29,45 @@ impl RedisRunner {
     pub fn address(&self) -> &str {
         &self.connection_string
     }
+
+    // Kills the server and starts a new one on the same port, dropping every connection to it
+    pub fn restart(&mut self) {
+        self.process.kill().unwrap();
+        self.process.wait().unwrap();
+        self.process = spawn_redis(self.data_dir.path(), self.port);
+        wait_for_redis(&mut self.process, &self.connection_string);
+    }
+}
+
+fn spawn_redis(data_dir: &Path, port: u16) -> Child {
+    Command::new("redis-server")
+        .args(&[data_dir.join("redis.conf").to_str().unwrap()])
+        .args(&["--port", &port.to_string()])
+        .args(&["--dir", data_dir.join("data").to_str().unwrap()])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+fn wait_for_redis(process: &mut Child, connection_string: &str) {
+    loop {
+        if process.try_wait().unwrap().is_some() {
+            let mut stdout_buffer = String::new();
+            process
+                .stdout
+                .take()
+                .unwrap()
+                .read_to_string(&mut stdout_buffer)
+                .unwrap();
+            eprintln!("{}", stdout_buffer);
+            panic!("redis-server has already closed, cannot connect to it")
+        }
+        if TcpStream::connect(connection_string).is_err() {
+            thread::sleep(Duration::from_millis(100));
+        } else {
+            break;
+        }
+    }
 }
 
 impl Drop for RedisRunner {
@@ -64,7 +106,7 @@ impl RedisInstance {
     pub fn build(self) -> RedisRunner {
         let _lock = GLOBAL_LOCK.lock();
         let port = loop {
-            let temp_port = rand::thread_rng().gen_range(6000, 6999);
+            let temp_port: u16 = rand::thread_rng().gen_range(6000, 6999);
             if TcpStream::connect(format!("localhost:{}", temp_port)).is_err() {
                 break temp_port;
             }
@@ -78,36 +120,13 @@ impl RedisInstance {
         fs::write(data_dir.path().join("redis.conf"), self.settings).unwrap();
         fs::create_dir(data_dir.path().join("data")).unwrap();
 
-        let mut process = Command::new("redis-server")
-            .args(&[data_dir.path().join("redis.conf").to_str().unwrap()])
-            .args(&["--port", &port.to_string()])
-            .args(&["--dir", data_dir.path().join("data").to_str().unwrap()])
-            .stdout(Stdio::piped())
-            .spawn()
-            .unwrap();
-
+        let mut process = spawn_redis(data_dir.path(), port);
         let connection_string = format!("localhost:{}", port);
-
-        loop {
-            if process.try_wait().unwrap().is_some() {
-                let mut stdout_buffer = String::new();
-                process
-                    .stdout
-                    .unwrap()
-                    .read_to_string(&mut stdout_buffer)
-                    .unwrap();
-                eprintln!("{}", stdout_buffer);
-                panic!("redis-server has already closed, cannot connect to it")
-            }
-            if TcpStream::connect(&connection_string).is_err() {
-                thread::sleep(Duration::from_millis(100));
-            } else {
-                break;
-            }
-        }
+        wait_for_redis(&mut process, &connection_string);
 
         RedisRunner {
             process,
+            port,
             data_dir,
             connection_string,
         }
@@ -117,3 +136,79 @@ impl RedisInstance {
 pub fn load_redis_instance() -> RedisRunner {
     RedisInstance::new().build()
 }
+
+// A fake server, for behaviour that's awkward to get out of a real redis.  Every command is passed
+// to `reply` as its list of arguments, and whatever that returns is written back as-is.
+pub fn mock_server(reply: impl Fn(&[Vec<u8>]) -> Vec<u8> + Send + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => break,
+            };
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            while let Some(command) = read_command(&mut reader) {
+                if writer.write_all(&reply(&command)).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    address
+}
+
+// One connection to a `scripted_server`, for the script to read commands from and write replies to
+pub struct ScriptedConnection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl ScriptedConnection {
+    pub fn read_command(&mut self) -> Option<Vec<Vec<u8>>> {
+        read_command(&mut self.reader)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        self.writer.write_all(bytes).unwrap();
+    }
+
+    // Keeps the connection open until the client closes it, ignoring anything else it sends
+    pub fn hold_open(&mut self) {
+        while self.read_command().is_some() {}
+    }
+}
+
+// A fake server for when replies depend on more than the command, e.g. on timing or on which
+// connection this is.  Each connection runs `script` in its own thread, with the index of the
+// connection, and is closed as soon as the script returns.
+pub fn scripted_server(
+    script: impl Fn(usize, &mut ScriptedConnection) + Send + Sync + 'static,
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let script = std::sync::Arc::new(script);
+
+    thread::spawn(move || {
+        for (index, stream) in listener.incoming().enumerate() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => break,
+            };
+            let script = script.clone();
+            thread::spawn(move || {
+                let mut connection = ScriptedConnection {
+                    writer: stream.try_clone().unwrap(),
+                    reader: BufReader::new(stream),
+                };
+                script(index, &mut connection);
+            });
+        }
+    });
+
+    address
+}